@@ -1,7 +1,35 @@
 // src/crypto/benches/crypto_benchmarks.rs
 
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use qraiop_crypto::pqc::kyber::{KeyEncapsulation, MlKem1024, MlKem512, MlKem768};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use qraiop_crypto::pqc::kyber::{MlKem1024, MlKem512, MlKem768};
+use qraiop_crypto::KeyEncapsulation;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts heap allocations so benchmarks can report them next to timings.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    black_box(f());
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
 
 fn benchmark_ml_kem_512(c: &mut Criterion) {
     c.bench_function("ML-KEM-512 keypair", |b| {
@@ -35,6 +63,34 @@ fn benchmark_ml_kem_768(c: &mut Criterion) {
     });
 }
 
+fn benchmark_ml_kem_768_decapsulate_owned(c: &mut Criterion) {
+    let (pk, sk) = MlKem768::keypair().unwrap();
+    let (ct, _ss) = MlKem768::encapsulate(&pk).unwrap();
+
+    let owned = ct.clone();
+    println!(
+        "ML-KEM-768 decapsulate: {} allocation(s), decapsulate_owned: {}",
+        allocations(|| MlKem768::decapsulate(&sk, &ct).unwrap()),
+        allocations(|| MlKem768::decapsulate_owned(&sk, owned).unwrap()),
+    );
+
+    c.bench_function("ML-KEM-768 decapsulate (borrowed)", |b| {
+        b.iter_batched(
+            || ct.clone(),
+            |ct| black_box(MlKem768::decapsulate(&sk, &ct).unwrap()),
+            BatchSize::SmallInput,
+        );
+    });
+
+    c.bench_function("ML-KEM-768 decapsulate_owned", |b| {
+        b.iter_batched(
+            || ct.clone(),
+            |ct| black_box(MlKem768::decapsulate_owned(&sk, ct).unwrap()),
+            BatchSize::SmallInput,
+        );
+    });
+}
+
 fn benchmark_ml_kem_1024(c: &mut Criterion) {
     c.bench_function("ML-KEM-1024 keypair", |b| {
         b.iter(|| {
@@ -47,6 +103,7 @@ criterion_group!(
     benches,
     benchmark_ml_kem_512,
     benchmark_ml_kem_768,
+    benchmark_ml_kem_768_decapsulate_owned,
     benchmark_ml_kem_1024
 );
 criterion_main!(benches);
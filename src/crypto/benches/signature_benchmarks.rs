@@ -0,0 +1,92 @@
+// src/crypto/benches/signature_benchmarks.rs
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use qraiop_crypto::pqc::dilithium::{MlDsa44, MlDsa65, MlDsa87};
+use qraiop_crypto::pqc::sphincs::{SlhDsa128s, SlhDsa192s, SlhDsa256s};
+use qraiop_crypto::DigitalSignature;
+
+const MESSAGE_SIZES: [(usize, &str); 2] = [(1024, "1 KiB"), (1024 * 1024, "1 MiB")];
+
+/// Signs and verifies with one ML-DSA parameter set, both over the raw
+/// message and through the SHA-512 pre-hash of `sign_reader`. Large
+/// messages favour the pre-hash, as SHA-512 is faster than the SHAKE256
+/// that otherwise absorbs the whole message.
+macro_rules! benchmark_ml_dsa {
+    ($function:ident, $scheme:ident, $name:literal) => {
+        fn $function(c: &mut Criterion) {
+            let (pk, sk) = $scheme::keypair().unwrap();
+            let mut group = c.benchmark_group($name);
+            for (size, label) in MESSAGE_SIZES {
+                let message = vec![0x5a; size];
+                group.throughput(Throughput::Bytes(size as u64));
+
+                group.bench_with_input(BenchmarkId::new("sign", label), &message, |b, m| {
+                    b.iter(|| black_box($scheme::sign(&sk, m).unwrap()));
+                });
+                group.bench_with_input(
+                    BenchmarkId::new("sign pre-hashed", label),
+                    &message,
+                    |b, m| b.iter(|| black_box($scheme::sign_reader(&sk, &m[..]).unwrap())),
+                );
+
+                let signature = $scheme::sign(&sk, &message).unwrap();
+                group.bench_with_input(BenchmarkId::new("verify", label), &message, |b, m| {
+                    b.iter(|| black_box($scheme::verify(&pk, m, &signature).unwrap()));
+                });
+                let signature = $scheme::sign_reader(&sk, &message[..]).unwrap();
+                group.bench_with_input(
+                    BenchmarkId::new("verify pre-hashed", label),
+                    &message,
+                    |b, m| {
+                        b.iter(|| {
+                            black_box($scheme::verify_reader(&pk, &m[..], &signature).unwrap())
+                        })
+                    },
+                );
+            }
+            group.finish();
+        }
+    };
+}
+
+benchmark_ml_dsa!(benchmark_ml_dsa_44, MlDsa44, "ML-DSA-44");
+benchmark_ml_dsa!(benchmark_ml_dsa_65, MlDsa65, "ML-DSA-65");
+benchmark_ml_dsa!(benchmark_ml_dsa_87, MlDsa87, "ML-DSA-87");
+
+/// SLH-DSA signing takes hundreds of milliseconds for the small "s"
+/// variants, so these run with criterion's minimum sample count and a 1 KiB
+/// message only: the message is hashed once, so its size barely matters.
+macro_rules! benchmark_slh_dsa {
+    ($function:ident, $scheme:ident, $name:literal) => {
+        fn $function(c: &mut Criterion) {
+            let (pk, sk) = $scheme::keypair().unwrap();
+            let message = vec![0x5a; MESSAGE_SIZES[0].0];
+            let mut group = c.benchmark_group($name);
+            group.sample_size(10);
+
+            group.bench_function("sign 1 KiB", |b| {
+                b.iter(|| black_box($scheme::sign(&sk, &message).unwrap()));
+            });
+            let signature = $scheme::sign(&sk, &message).unwrap();
+            group.bench_function("verify 1 KiB", |b| {
+                b.iter(|| black_box($scheme::verify(&pk, &message, &signature).unwrap()));
+            });
+            group.finish();
+        }
+    };
+}
+
+benchmark_slh_dsa!(benchmark_slh_dsa_128s, SlhDsa128s, "SLH-DSA-128s");
+benchmark_slh_dsa!(benchmark_slh_dsa_192s, SlhDsa192s, "SLH-DSA-192s");
+benchmark_slh_dsa!(benchmark_slh_dsa_256s, SlhDsa256s, "SLH-DSA-256s");
+
+criterion_group!(
+    benches,
+    benchmark_ml_dsa_44,
+    benchmark_ml_dsa_65,
+    benchmark_ml_dsa_87,
+    benchmark_slh_dsa_128s,
+    benchmark_slh_dsa_192s,
+    benchmark_slh_dsa_256s
+);
+criterion_main!(benches);
@@ -0,0 +1,83 @@
+//! Records the resolved versions of the algorithm backends for `info()`.
+//!
+//! Cargo does not expose dependency versions to the compiler, so this reads
+//! them from the `Cargo.lock` of the build: next to this manifest when
+//! building the crate itself, or the first one above `OUT_DIR` when it is a
+//! dependency. If no lock file is found the versions are left unset and
+//! `info()` reports them as unknown.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const BACKENDS: &[&str] = &[
+    "pqcrypto-mlkem",
+    "pqcrypto-mldsa",
+    "pqcrypto-sphincsplus",
+    "hbs-lms",
+];
+
+fn find_lock_file() -> Option<PathBuf> {
+    let manifest_dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR")?);
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR")?);
+    manifest_dir
+        .ancestors()
+        .chain(out_dir.ancestors())
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|path| path.is_file())
+}
+
+/// Version of `package` in a `Cargo.lock`, taking the highest if several
+/// are locked.
+fn locked_version(lock: &str, package: &str) -> Option<String> {
+    let name_line = format!("name = \"{}\"", package);
+    let mut lines = lock.lines();
+    let mut found: Option<String> = None;
+    while let Some(line) = lines.next() {
+        if line.trim() != name_line {
+            continue;
+        }
+        let version = lines
+            .next()
+            .and_then(|line| line.trim().strip_prefix("version = \""))
+            .and_then(|rest| rest.strip_suffix('"'));
+        if let Some(version) = version {
+            if found.as_deref().is_none_or(|v| newer(version, v)) {
+                found = Some(version.to_string());
+            }
+        }
+    }
+    found
+}
+
+fn newer(a: &str, b: &str) -> bool {
+    let parse = |v: &str| -> Vec<u64> {
+        v.split(['.', '-', '+'])
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    };
+    parse(a) > parse(b)
+}
+
+fn env_name(package: &str) -> String {
+    format!(
+        "QRAIOP_BACKEND_VERSION_{}",
+        package.to_uppercase().replace('-', "_")
+    )
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    let Some(path) = find_lock_file() else {
+        return;
+    };
+    println!("cargo:rerun-if-changed={}", path.display());
+    let Ok(lock) = fs::read_to_string(Path::new(&path)) else {
+        return;
+    };
+    for package in BACKENDS {
+        if let Some(version) = locked_version(&lock, package) {
+            println!("cargo:rustc-env={}={}", env_name(package), version);
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! Decapsulates arbitrary bytes, as a ciphertext under a fixed key and as a
+//! secret key for a valid ciphertext, and opens them as sealed messages.
+//! Malformed input must fail with an error, never panic.
+//!
+//! Run from `src/crypto` with `cargo +nightly fuzz run decapsulate`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use qraiop_crypto::hybrid::{XWing, XWingCiphertext, XWingSecretKey};
+use qraiop_crypto::pqc::kyber::{self, MlKem1024, MlKem512, MlKem768};
+use qraiop_crypto::pqc::KeyEncapsulation;
+use qraiop_crypto::seal::{self, SealConfig};
+use qraiop_crypto::KemAlgorithm;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, data)) = data.split_first() else {
+        return;
+    };
+    let seed = [0x42; 64];
+    let (algorithm, keypair) = match selector % 3 {
+        0 => (KemAlgorithm::MlKem512, MlKem512::keypair_from_seed(&seed)),
+        1 => (KemAlgorithm::MlKem768, MlKem768::keypair_from_seed(&seed)),
+        _ => (KemAlgorithm::MlKem1024, MlKem1024::keypair_from_seed(&seed)),
+    };
+    let (pk, sk) = keypair.unwrap();
+
+    if let Ok(ciphertext) = kyber::Ciphertext::from_bytes(data) {
+        let _ = algorithm.decapsulate(&sk, &ciphertext);
+    }
+    if let Ok(secret_key) = kyber::SecretKey::from_bytes(data) {
+        let (ciphertext, _) = algorithm.encapsulate(&pk).unwrap();
+        let _ = algorithm.decapsulate(&secret_key, &ciphertext);
+        let _ = secret_key.public_key();
+    }
+    let _ = seal::open(&sk, b"", data);
+    let _ = SealConfig::open(&sk, b"", data);
+
+    let (xwing_pk, xwing_sk) = XWing::keypair_from_seed(&[0x42; 32]).unwrap();
+    if let Ok(ciphertext) = XWingCiphertext::from_bytes(data) {
+        let _ = XWing::decapsulate(&xwing_sk, &ciphertext);
+    }
+    if let Ok(secret_key) = XWingSecretKey::from_bytes(data) {
+        let (ciphertext, _) = XWing::encapsulate(&xwing_pk).unwrap();
+        let _ = XWing::decapsulate(&secret_key, &ciphertext);
+    }
+});
@@ -0,0 +1,28 @@
+//! Parses arbitrary bytes as every kind of public key. Parsing must fail
+//! with an error, never panic, and accepted keys must round-trip.
+//!
+//! Run from `src/crypto` with `cargo +nightly fuzz run public_key_from_bytes`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use qraiop_crypto::hybrid::XWingPublicKey;
+use qraiop_crypto::pqc::{dilithium, kyber};
+use qraiop_crypto::wire;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(pk) = kyber::PublicKey::from_bytes(data) {
+        assert_eq!(pk.as_bytes(), data);
+        let _ = pk.validate();
+    }
+    if let Ok(pk) = dilithium::PublicKey::from_bytes(data) {
+        assert_eq!(pk.as_bytes(), data);
+    }
+    if let Ok(vk) = dilithium::VerifyingKey::from_bytes(data) {
+        assert_eq!(vk.as_bytes(), data);
+    }
+    if let Ok(pk) = XWingPublicKey::from_bytes(data) {
+        assert_eq!(pk.as_bytes(), data);
+    }
+    let _ = wire::decode_public_key(data);
+});
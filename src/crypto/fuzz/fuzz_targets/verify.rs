@@ -0,0 +1,64 @@
+//! Verifies arbitrary bytes as a signature under fixed keys, and a valid
+//! signature under arbitrary bytes as the key. Verification must return
+//! `Ok(false)` or an error, never panic.
+//!
+//! Run from `src/crypto` with `cargo +nightly fuzz run verify`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use qraiop_crypto::pqc::dilithium::{self, MlDsa44, MlDsa65, MlDsa87, VerifyingKey};
+use qraiop_crypto::pqc::lms::{self, LmsSha256H5};
+use qraiop_crypto::pqc::DigitalSignature;
+use qraiop_crypto::wire;
+use std::sync::OnceLock;
+
+const MESSAGE: &[u8] = b"fuzz";
+
+struct Keys {
+    ml_dsa: Vec<(dilithium::PublicKey, dilithium::Signature)>,
+    lms: (lms::PublicKey, lms::Signature),
+}
+
+// Key generation is far slower than verification, so it runs once.
+fn keys() -> &'static Keys {
+    static KEYS: OnceLock<Keys> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        fn signed<S: DigitalSignature>() -> (S::PublicKey, S::Signature) {
+            let (pk, sk) = S::keypair().unwrap();
+            (pk, S::sign(&sk, MESSAGE).unwrap())
+        }
+        Keys {
+            ml_dsa: vec![
+                signed::<MlDsa44>(),
+                signed::<MlDsa65>(),
+                signed::<MlDsa87>(),
+            ],
+            lms: signed::<LmsSha256H5>(),
+        }
+    })
+}
+
+fuzz_target!(|data: &[u8]| {
+    let keys = keys();
+
+    if let Ok(signature) = dilithium::Signature::from_bytes(data) {
+        for (pk, _) in &keys.ml_dsa {
+            let _ = VerifyingKey::from(pk.clone()).verify(MESSAGE, &signature);
+        }
+    }
+    if let Ok(vk) = VerifyingKey::from_bytes(data) {
+        for (_, signature) in &keys.ml_dsa {
+            let _ = vk.verify(MESSAGE, signature);
+        }
+    }
+
+    let (lms_pk, lms_signature) = &keys.lms;
+    // Only the one signature made under this key can verify
+    let signature = lms::Signature::from_bytes(data);
+    if LmsSha256H5::verify(lms_pk, MESSAGE, &signature).unwrap() {
+        assert_eq!(signature.as_bytes(), lms_signature.as_bytes());
+    }
+
+    let _ = wire::decode_signature(data);
+});
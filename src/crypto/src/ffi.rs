@@ -0,0 +1,318 @@
+//! C ABI for ML-KEM-768
+//!
+//! Every function takes raw pointers plus explicit buffer lengths and returns
+//! [`QRAIOP_OK`] or one of the negative `QRAIOP_ERR_*` status codes. Output
+//! buffers must hold at least the matching `QRAIOP_MLKEM768_*_BYTES`; inputs
+//! must be exactly that size. Nothing is written to the outputs on failure.
+//!
+//! The layout is meant for `cbindgen` (see `cbindgen.toml`). Link against the
+//! library built with
+//! `cargo rustc --release --features ffi --crate-type staticlib` (or `cdylib`).
+
+use crate::pqc::kyber::{Ciphertext, MlKem768, PublicKey, SecretKey};
+use crate::pqc::KeyEncapsulation;
+use crate::QraiopError;
+use std::os::raw::c_int;
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+
+// Spelled out rather than taken from `key_sizes` so cbindgen can emit them
+pub const QRAIOP_MLKEM768_PUBLIC_KEY_BYTES: usize = 1184;
+pub const QRAIOP_MLKEM768_SECRET_KEY_BYTES: usize = 2400;
+pub const QRAIOP_MLKEM768_CIPHERTEXT_BYTES: usize = 1088;
+pub const QRAIOP_MLKEM768_SHARED_SECRET_BYTES: usize = 32;
+
+pub const QRAIOP_OK: c_int = 0;
+/// A required pointer argument was null.
+pub const QRAIOP_ERR_NULL_POINTER: c_int = -1;
+/// An input buffer has the wrong length, or an output buffer is too small.
+pub const QRAIOP_ERR_BUFFER_SIZE: c_int = -2;
+/// The Rust side panicked; the operation did not complete.
+pub const QRAIOP_ERR_PANIC: c_int = -3;
+pub const QRAIOP_ERR_CRYPTO: c_int = -10;
+pub const QRAIOP_ERR_INVALID_KEY: c_int = -11;
+pub const QRAIOP_ERR_UNSUPPORTED_ALGORITHM: c_int = -12;
+pub const QRAIOP_ERR_SIGNATURE_VERIFICATION: c_int = -13;
+pub const QRAIOP_ERR_KEY_GENERATION: c_int = -14;
+pub const QRAIOP_ERR_ENCAPSULATION: c_int = -15;
+pub const QRAIOP_ERR_DECAPSULATION: c_int = -16;
+pub const QRAIOP_ERR_SERIALIZATION: c_int = -17;
+pub const QRAIOP_ERR_IO: c_int = -18;
+pub const QRAIOP_ERR_INVALID_CIPHERTEXT: c_int = -19;
+pub const QRAIOP_ERR_KEY_EXHAUSTED: c_int = -20;
+pub const QRAIOP_ERR_INVALID_SIGNATURE: c_int = -21;
+
+fn status(error: &QraiopError) -> c_int {
+    match error {
+        QraiopError::CryptoError(_) => QRAIOP_ERR_CRYPTO,
+        QraiopError::InvalidKey(_) => QRAIOP_ERR_INVALID_KEY,
+        QraiopError::UnsupportedAlgorithm(_) => QRAIOP_ERR_UNSUPPORTED_ALGORITHM,
+        QraiopError::SignatureVerificationFailed => QRAIOP_ERR_SIGNATURE_VERIFICATION,
+        QraiopError::KeyGenerationFailed(_) => QRAIOP_ERR_KEY_GENERATION,
+        QraiopError::EncapsulationFailed(_) => QRAIOP_ERR_ENCAPSULATION,
+        QraiopError::DecapsulationFailed(_) => QRAIOP_ERR_DECAPSULATION,
+        QraiopError::SerializationError(_) => QRAIOP_ERR_SERIALIZATION,
+        QraiopError::IoError(_) => QRAIOP_ERR_IO,
+        QraiopError::InvalidCiphertext(_) => QRAIOP_ERR_INVALID_CIPHERTEXT,
+        QraiopError::InvalidSignature(_) => QRAIOP_ERR_INVALID_SIGNATURE,
+        QraiopError::BufferTooSmall { .. } => QRAIOP_ERR_BUFFER_SIZE,
+        QraiopError::KeyExhausted => QRAIOP_ERR_KEY_EXHAUSTED,
+        QraiopError::Hybrid(e) => status(&e.source),
+    }
+}
+
+/// Runs `f`, turning its error or a panic into a status code so neither
+/// unwinds across the C boundary.
+fn call(f: impl FnOnce() -> Result<(), c_int>) -> c_int {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => QRAIOP_OK,
+        Ok(Err(code)) => code,
+        Err(_) => QRAIOP_ERR_PANIC,
+    }
+}
+
+/// # Safety
+///
+/// `ptr` must be null or valid for reads of `len` bytes.
+unsafe fn input<'a>(ptr: *const u8, len: usize, expected: usize) -> Result<&'a [u8], c_int> {
+    if ptr.is_null() {
+        return Err(QRAIOP_ERR_NULL_POINTER);
+    }
+    if len != expected {
+        return Err(QRAIOP_ERR_BUFFER_SIZE);
+    }
+    Ok(slice::from_raw_parts(ptr, len))
+}
+
+/// Checks an output buffer before anything is computed, so a bad buffer
+/// never costs a key generation.
+fn check_output(ptr: *mut u8, len: usize, needed: usize) -> Result<(), c_int> {
+    if ptr.is_null() {
+        return Err(QRAIOP_ERR_NULL_POINTER);
+    }
+    if len < needed {
+        return Err(QRAIOP_ERR_BUFFER_SIZE);
+    }
+    Ok(())
+}
+
+/// # Safety
+///
+/// `ptr` must be valid for writes of `bytes.len()` bytes, as established by
+/// [`check_output`].
+unsafe fn write(ptr: *mut u8, bytes: &[u8]) {
+    std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+}
+
+/// Generates an ML-KEM-768 key pair into `pk_out` and `sk_out`.
+///
+/// # Safety
+///
+/// `pk_out` must be valid for writes of `pk_len` bytes and `sk_out` for
+/// writes of `sk_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn qraiop_mlkem768_keypair(
+    pk_out: *mut u8,
+    pk_len: usize,
+    sk_out: *mut u8,
+    sk_len: usize,
+) -> c_int {
+    call(|| {
+        check_output(pk_out, pk_len, QRAIOP_MLKEM768_PUBLIC_KEY_BYTES)?;
+        check_output(sk_out, sk_len, QRAIOP_MLKEM768_SECRET_KEY_BYTES)?;
+        let (pk, sk) = MlKem768::keypair().map_err(|e| status(&e))?;
+        write(pk_out, pk.as_bytes());
+        write(sk_out, sk.as_bytes());
+        Ok(())
+    })
+}
+
+/// Encapsulates to the public key at `pk`, writing the ciphertext to `ct_out`
+/// and the shared secret to `ss_out`.
+///
+/// # Safety
+///
+/// `pk` must be valid for reads of `pk_len` bytes, `ct_out` for writes of
+/// `ct_len` bytes and `ss_out` for writes of `ss_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn qraiop_mlkem768_encapsulate(
+    pk: *const u8,
+    pk_len: usize,
+    ct_out: *mut u8,
+    ct_len: usize,
+    ss_out: *mut u8,
+    ss_len: usize,
+) -> c_int {
+    call(|| {
+        let pk = input(pk, pk_len, QRAIOP_MLKEM768_PUBLIC_KEY_BYTES)?;
+        check_output(ct_out, ct_len, QRAIOP_MLKEM768_CIPHERTEXT_BYTES)?;
+        check_output(ss_out, ss_len, QRAIOP_MLKEM768_SHARED_SECRET_BYTES)?;
+        let pk = PublicKey::from_bytes(pk).map_err(|e| status(&e))?;
+        let (ct, ss) = MlKem768::encapsulate(&pk).map_err(|e| status(&e))?;
+        write(ct_out, ct.as_bytes());
+        write(ss_out, ss.as_bytes());
+        Ok(())
+    })
+}
+
+/// Decapsulates the ciphertext at `ct` with the secret key at `sk`, writing
+/// the shared secret to `ss_out`.
+///
+/// # Safety
+///
+/// `sk` must be valid for reads of `sk_len` bytes, `ct` for reads of `ct_len`
+/// bytes and `ss_out` for writes of `ss_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn qraiop_mlkem768_decapsulate(
+    sk: *const u8,
+    sk_len: usize,
+    ct: *const u8,
+    ct_len: usize,
+    ss_out: *mut u8,
+    ss_len: usize,
+) -> c_int {
+    call(|| {
+        let sk = input(sk, sk_len, QRAIOP_MLKEM768_SECRET_KEY_BYTES)?;
+        let ct = input(ct, ct_len, QRAIOP_MLKEM768_CIPHERTEXT_BYTES)?;
+        check_output(ss_out, ss_len, QRAIOP_MLKEM768_SHARED_SECRET_BYTES)?;
+        let sk = SecretKey::from_bytes(sk).map_err(|e| status(&e))?;
+        let ct = Ciphertext::from_bytes(ct).map_err(|e| status(&e))?;
+        let ss = MlKem768::decapsulate(&sk, &ct).map_err(|e| status(&e))?;
+        write(ss_out, ss.as_bytes());
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    #[test]
+    fn test_ffi_roundtrip() {
+        let mut pk = [0u8; QRAIOP_MLKEM768_PUBLIC_KEY_BYTES];
+        let mut sk = [0u8; QRAIOP_MLKEM768_SECRET_KEY_BYTES];
+        let mut ct = [0u8; QRAIOP_MLKEM768_CIPHERTEXT_BYTES];
+        let mut ss1 = [0u8; QRAIOP_MLKEM768_SHARED_SECRET_BYTES];
+        let mut ss2 = [0u8; QRAIOP_MLKEM768_SHARED_SECRET_BYTES];
+
+        unsafe {
+            assert_eq!(
+                qraiop_mlkem768_keypair(pk.as_mut_ptr(), pk.len(), sk.as_mut_ptr(), sk.len()),
+                QRAIOP_OK
+            );
+            assert_eq!(
+                qraiop_mlkem768_encapsulate(
+                    pk.as_ptr(),
+                    pk.len(),
+                    ct.as_mut_ptr(),
+                    ct.len(),
+                    ss1.as_mut_ptr(),
+                    ss1.len()
+                ),
+                QRAIOP_OK
+            );
+            assert_eq!(
+                qraiop_mlkem768_decapsulate(
+                    sk.as_ptr(),
+                    sk.len(),
+                    ct.as_ptr(),
+                    ct.len(),
+                    ss2.as_mut_ptr(),
+                    ss2.len()
+                ),
+                QRAIOP_OK
+            );
+        }
+        assert_eq!(ss1, ss2);
+        assert_ne!(ss1, [0u8; QRAIOP_MLKEM768_SHARED_SECRET_BYTES]);
+    }
+
+    #[test]
+    fn test_ffi_errors() {
+        let mut pk = [0u8; QRAIOP_MLKEM768_PUBLIC_KEY_BYTES];
+        let mut sk = [0u8; QRAIOP_MLKEM768_SECRET_KEY_BYTES];
+        let mut ss = [0u8; QRAIOP_MLKEM768_SHARED_SECRET_BYTES];
+
+        unsafe {
+            assert_eq!(
+                qraiop_mlkem768_keypair(ptr::null_mut(), pk.len(), sk.as_mut_ptr(), sk.len()),
+                QRAIOP_ERR_NULL_POINTER
+            );
+            assert_eq!(
+                qraiop_mlkem768_keypair(pk.as_mut_ptr(), pk.len() - 1, sk.as_mut_ptr(), sk.len()),
+                QRAIOP_ERR_BUFFER_SIZE
+            );
+            // Failed calls leave the outputs untouched
+            assert_eq!(pk, [0u8; QRAIOP_MLKEM768_PUBLIC_KEY_BYTES]);
+
+            let ct = [0u8; QRAIOP_MLKEM768_CIPHERTEXT_BYTES];
+            assert_eq!(
+                qraiop_mlkem768_decapsulate(
+                    sk.as_ptr(),
+                    sk.len(),
+                    ct.as_ptr(),
+                    ct.len() - 1,
+                    ss.as_mut_ptr(),
+                    ss.len()
+                ),
+                QRAIOP_ERR_BUFFER_SIZE
+            );
+        }
+    }
+
+    #[test]
+    fn test_sizes_match_key_sizes() {
+        use crate::utils::key_sizes::ml_kem_768;
+
+        assert_eq!(
+            QRAIOP_MLKEM768_PUBLIC_KEY_BYTES,
+            ml_kem_768::PUBLIC_KEY_SIZE
+        );
+        assert_eq!(
+            QRAIOP_MLKEM768_SECRET_KEY_BYTES,
+            ml_kem_768::SECRET_KEY_SIZE
+        );
+        assert_eq!(
+            QRAIOP_MLKEM768_CIPHERTEXT_BYTES,
+            ml_kem_768::CIPHERTEXT_SIZE
+        );
+        assert_eq!(
+            QRAIOP_MLKEM768_SHARED_SECRET_BYTES,
+            ml_kem_768::SHARED_SECRET_SIZE
+        );
+    }
+
+    #[test]
+    fn test_status_codes_are_distinct() {
+        let errors = [
+            QraiopError::CryptoError(String::new()),
+            QraiopError::InvalidKey(String::new()),
+            QraiopError::UnsupportedAlgorithm(String::new()),
+            QraiopError::SignatureVerificationFailed,
+            QraiopError::KeyGenerationFailed(String::new()),
+            QraiopError::EncapsulationFailed(String::new()),
+            QraiopError::DecapsulationFailed(String::new()),
+            QraiopError::SerializationError(String::new()),
+            QraiopError::IoError(std::io::Error::other("io")),
+            QraiopError::InvalidCiphertext(String::new()),
+            QraiopError::InvalidSignature(String::new()),
+            QraiopError::BufferTooSmall { needed: 1, got: 0 },
+            QraiopError::KeyExhausted,
+        ];
+        let mut codes: Vec<c_int> = errors.iter().map(status).collect();
+        assert!(codes.iter().all(|&code| code < 0));
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+    }
+
+    #[test]
+    fn test_hybrid_errors_report_the_component_status() {
+        let error = QraiopError::Hybrid(crate::hybrid::HybridError {
+            component: crate::hybrid::HybridComponent::PostQuantum,
+            source: Box::new(QraiopError::InvalidCiphertext(String::new())),
+        });
+        assert_eq!(status(&error), QRAIOP_ERR_INVALID_CIPHERTEXT);
+    }
+}
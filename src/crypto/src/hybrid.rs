@@ -1,7 +1,1321 @@
 //! Hybrid cryptography combining classical and post-quantum algorithms.
 
-use crate::pqc::{DigitalSignature, KeyEncapsulation};
-use crate::{QraiopError, Result, SecurityLevel};
-use zeroize::Zeroize;
+#[cfg(feature = "dilithium")]
+use crate::pqc::dilithium::{self, MlDsa65};
+use crate::pqc::kyber::{self, MlKem768};
+#[cfg(feature = "dilithium")]
+use crate::pqc::DigitalSignature;
+use crate::pqc::KeyEncapsulation;
+use crate::utils;
+#[cfg(feature = "dilithium")]
+use crate::utils::key_sizes::ml_dsa_65;
+use crate::utils::key_sizes::ml_kem_768;
+use crate::utils::secret::Secret;
+#[cfg(feature = "dilithium")]
+use crate::SecurityLevel;
+use crate::{QraiopError, Result};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+#[cfg(feature = "dilithium")]
+use ed25519_dalek::Signer;
+use rand::{CryptoRng, RngCore};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Digest, Sha3_256, Shake256};
+use x25519_dalek::StaticSecret;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
-// Hybrid implementation ...
+/// Domain separator appended to every X-Wing combiner input (`\.//^\`).
+const XWING_LABEL: &[u8; 6] = br"\.//^\";
+
+const X25519_SIZE: usize = 32;
+
+/// Size in bytes of an X-Wing public key (`pk_M || pk_X`).
+pub const XWING_PUBLIC_KEY_SIZE: usize = ml_kem_768::PUBLIC_KEY_SIZE + X25519_SIZE;
+/// Size in bytes of an X-Wing secret key (the 32-byte seed).
+pub const XWING_SECRET_KEY_SIZE: usize = 32;
+/// Size in bytes of an X-Wing ciphertext (`ct_M || ct_X`).
+pub const XWING_CIPHERTEXT_SIZE: usize = ml_kem_768::CIPHERTEXT_SIZE + X25519_SIZE;
+/// Size in bytes of an X-Wing shared secret (a SHA3-256 digest).
+pub const XWING_SHARED_SECRET_SIZE: usize = 32;
+
+fn check_length(what: &str, actual: usize, expected: usize) -> Result<()> {
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(QraiopError::InvalidKey(format!(
+            "{} must be {} bytes, got {}",
+            what, expected, actual
+        )))
+    }
+}
+
+/// Shared secret produced by a hybrid KEM, wiped from memory on drop.
+#[derive(Debug, Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct SharedSecret(Secret);
+
+impl SharedSecret {
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.expose_secret()
+    }
+
+    /// Copies the secret out into a buffer that is wiped when dropped.
+    pub fn to_zeroizing_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(self.0.expose_secret().to_vec())
+    }
+}
+
+impl AsRef<[u8]> for SharedSecret {
+    fn as_ref(&self) -> &[u8] {
+        self.0.expose_secret()
+    }
+}
+
+/// X-Wing public key: the ML-KEM-768 key followed by the X25519 key.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "Vec<u8>")]
+pub struct XWingPublicKey(Vec<u8>);
+
+impl XWingPublicKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_length("X-Wing public key", bytes.len(), XWING_PUBLIC_KEY_SIZE)?;
+        Ok(Self(bytes.to_vec()))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl TryFrom<Vec<u8>> for XWingPublicKey {
+    type Error = QraiopError;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        check_length("X-Wing public key", bytes.len(), XWING_PUBLIC_KEY_SIZE)?;
+        Ok(Self(bytes))
+    }
+}
+
+/// X-Wing secret key. Only the 32-byte seed is stored; both component keys
+/// are re-derived from it on every decapsulation.
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop)]
+pub struct XWingSecretKey(Secret);
+
+impl XWingSecretKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_length("X-Wing secret key", bytes.len(), XWING_SECRET_KEY_SIZE)?;
+        Ok(Self(bytes.into()))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.expose_secret()
+    }
+
+    /// Copies the seed out into a buffer that is wiped when dropped.
+    pub fn to_zeroizing_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(self.0.expose_secret().to_vec())
+    }
+}
+
+impl AsRef<[u8]> for XWingSecretKey {
+    fn as_ref(&self) -> &[u8] {
+        self.0.expose_secret()
+    }
+}
+
+/// X-Wing ciphertext: the ML-KEM-768 ciphertext followed by the ephemeral
+/// X25519 public key.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "Vec<u8>")]
+pub struct XWingCiphertext(Vec<u8>);
+
+impl XWingCiphertext {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_length("X-Wing ciphertext", bytes.len(), XWING_CIPHERTEXT_SIZE)?;
+        Ok(Self(bytes.to_vec()))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl TryFrom<Vec<u8>> for XWingCiphertext {
+    type Error = QraiopError;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        check_length("X-Wing ciphertext", bytes.len(), XWING_CIPHERTEXT_SIZE)?;
+        Ok(Self(bytes))
+    }
+}
+
+/// Expanded X-Wing secret key material.
+struct XWingKeys {
+    sk_m: kyber::SecretKey,
+    sk_x: StaticSecret,
+    pk_m: kyber::PublicKey,
+    pk_x: x25519_dalek::PublicKey,
+}
+
+/// X-Wing hybrid KEM (X25519 + ML-KEM-768) following
+/// draft-connolly-cfrg-xwing-kem.
+///
+/// The shared secret is `SHA3-256(ss_M || ss_X || ct_X || pk_X || label)`,
+/// which stays secure as long as either component remains unbroken.
+pub struct XWing;
+
+impl XWing {
+    /// Derives a keypair from a 32-byte seed, which doubles as the secret key.
+    pub fn keypair_from_seed(seed: &[u8; 32]) -> Result<(XWingPublicKey, XWingSecretKey)> {
+        let keys = Self::expand_key(seed)?;
+        let mut pk = Vec::with_capacity(XWING_PUBLIC_KEY_SIZE);
+        pk.extend_from_slice(keys.pk_m.as_bytes());
+        pk.extend_from_slice(keys.pk_x.as_bytes());
+        Ok((
+            XWingPublicKey(pk),
+            XWingSecretKey(Secret::new(seed.to_vec())),
+        ))
+    }
+
+    /// Expands the seed with SHAKE256 into the ML-KEM-768 seed `d || z`
+    /// (64 bytes) and the X25519 secret key (32 bytes).
+    fn expand_key(seed: &[u8]) -> Result<XWingKeys> {
+        let mut expanded = [0u8; 96];
+        let mut shake = Shake256::default();
+        shake.update(seed);
+        shake.finalize_xof().read(&mut expanded);
+
+        let mut seed_m = [0u8; 64];
+        seed_m.copy_from_slice(&expanded[..64]);
+        let mut seed_x = [0u8; 32];
+        seed_x.copy_from_slice(&expanded[64..]);
+
+        let keypair = MlKem768::keypair_from_seed(&seed_m);
+        let sk_x = StaticSecret::from(seed_x);
+        expanded.zeroize();
+        seed_m.zeroize();
+        seed_x.zeroize();
+
+        let (pk_m, sk_m) = keypair?;
+        let pk_x = x25519_dalek::PublicKey::from(&sk_x);
+        Ok(XWingKeys {
+            sk_m,
+            sk_x,
+            pk_m,
+            pk_x,
+        })
+    }
+
+    /// Generates a key pair from a 32-byte seed drawn from `rng`. If `rng`
+    /// fails, this returns [`QraiopError::KeyGenerationFailed`] instead of
+    /// panicking.
+    pub fn keypair_with_rng<R: RngCore + CryptoRng>(
+        rng: &mut R,
+    ) -> Result<(XWingPublicKey, XWingSecretKey)> {
+        let mut seed = [0u8; XWING_SECRET_KEY_SIZE];
+        let keypair = utils::keygen_seed(rng, &mut seed)
+            .map_err(|e| e.with_algorithm("X-Wing"))
+            .and_then(|()| Self::keypair_from_seed(&seed));
+        seed.zeroize();
+        keypair
+    }
+
+    /// Encapsulates with the 64-byte ephemeral seed drawn from `rng`. If
+    /// `rng` fails, this returns [`QraiopError::EncapsulationFailed`]
+    /// instead of panicking.
+    pub fn encapsulate_with_rng<R: RngCore + CryptoRng>(
+        public_key: &XWingPublicKey,
+        rng: &mut R,
+    ) -> Result<(XWingCiphertext, SharedSecret)> {
+        let mut eseed = [0u8; 64];
+        let encapsulated = utils::fill_random(rng, &mut eseed, QraiopError::EncapsulationFailed)
+            .map_err(|e| e.with_algorithm("X-Wing"))
+            .and_then(|()| Self::encapsulate_derand(public_key, &eseed));
+        eseed.zeroize();
+        encapsulated
+    }
+
+    /// Encapsulates with caller-supplied randomness: `eseed[..32]` drives
+    /// ML-KEM-768 and `eseed[32..]` is the ephemeral X25519 secret.
+    fn encapsulate_derand(
+        public_key: &XWingPublicKey,
+        eseed: &[u8; 64],
+    ) -> Result<(XWingCiphertext, SharedSecret)> {
+        check_length(
+            "X-Wing public key",
+            public_key.0.len(),
+            XWING_PUBLIC_KEY_SIZE,
+        )?;
+        let (pk_m, pk_x) = public_key.0.split_at(ml_kem_768::PUBLIC_KEY_SIZE);
+        let pk_m = kyber::PublicKey::from_bytes(pk_m)?;
+        let mut pk_x_bytes = [0u8; X25519_SIZE];
+        pk_x_bytes.copy_from_slice(pk_x);
+        let pk_x = x25519_dalek::PublicKey::from(pk_x_bytes);
+
+        let mut m = [0u8; 32];
+        m.copy_from_slice(&eseed[..32]);
+        let mut ek_x = [0u8; 32];
+        ek_x.copy_from_slice(&eseed[32..]);
+
+        let encapsulated = MlKem768::encapsulate_deterministic(&pk_m, &m);
+        let ek_x_secret = StaticSecret::from(ek_x);
+        m.zeroize();
+        ek_x.zeroize();
+
+        let (ct_m, ss_m) = encapsulated?;
+        let ct_x = x25519_dalek::PublicKey::from(&ek_x_secret);
+        let ss_x = ek_x_secret.diffie_hellman(&pk_x);
+
+        let shared_secret = combiner(ss_m.as_bytes(), ss_x.as_bytes(), &ct_x, &pk_x);
+        let mut ct = Vec::with_capacity(XWING_CIPHERTEXT_SIZE);
+        ct.extend_from_slice(ct_m.as_bytes());
+        ct.extend_from_slice(ct_x.as_bytes());
+        Ok((XWingCiphertext(ct), shared_secret))
+    }
+}
+
+fn combiner(
+    ss_m: &[u8],
+    ss_x: &[u8],
+    ct_x: &x25519_dalek::PublicKey,
+    pk_x: &x25519_dalek::PublicKey,
+) -> SharedSecret {
+    let mut hasher = Sha3_256::new();
+    Digest::update(&mut hasher, ss_m);
+    Digest::update(&mut hasher, ss_x);
+    Digest::update(&mut hasher, ct_x.as_bytes());
+    Digest::update(&mut hasher, pk_x.as_bytes());
+    Digest::update(&mut hasher, XWING_LABEL);
+    SharedSecret(Secret::new(hasher.finalize().to_vec()))
+}
+
+impl KeyEncapsulation for XWing {
+    type PublicKey = XWingPublicKey;
+    type SecretKey = XWingSecretKey;
+    type Ciphertext = XWingCiphertext;
+    type SharedSecret = SharedSecret;
+
+    fn keypair() -> Result<(Self::PublicKey, Self::SecretKey)> {
+        Self::keypair_with_rng(&mut utils::SystemRng)
+    }
+
+    fn encapsulate(public_key: &Self::PublicKey) -> Result<(Self::Ciphertext, Self::SharedSecret)> {
+        Self::encapsulate_with_rng(public_key, &mut utils::SystemRng)
+    }
+
+    fn decapsulate(
+        secret_key: &Self::SecretKey,
+        ciphertext: &Self::Ciphertext,
+    ) -> Result<Self::SharedSecret> {
+        check_length(
+            "X-Wing secret key",
+            secret_key.0.len(),
+            XWING_SECRET_KEY_SIZE,
+        )?;
+        if ciphertext.0.len() != XWING_CIPHERTEXT_SIZE {
+            return Err(QraiopError::InvalidCiphertext(format!(
+                "X-Wing ciphertext must be {} bytes, got {}",
+                XWING_CIPHERTEXT_SIZE,
+                ciphertext.0.len()
+            )));
+        }
+        let keys = Self::expand_key(secret_key.0.expose_secret())?;
+
+        let (ct_m, ct_x) = ciphertext.0.split_at(ml_kem_768::CIPHERTEXT_SIZE);
+        let ct_m = kyber::Ciphertext::from_bytes(ct_m)?;
+        let mut ct_x_bytes = [0u8; X25519_SIZE];
+        ct_x_bytes.copy_from_slice(ct_x);
+        let ct_x = x25519_dalek::PublicKey::from(ct_x_bytes);
+
+        let ss_m = MlKem768::decapsulate(&keys.sk_m, &ct_m)?;
+        let ss_x = keys.sk_x.diffie_hellman(&ct_x);
+        Ok(combiner(
+            ss_m.as_bytes(),
+            ss_x.as_bytes(),
+            &ct_x,
+            &keys.pk_x,
+        ))
+    }
+
+    fn algorithm_name() -> &'static str {
+        "X-Wing"
+    }
+
+    /// Assigned in draft-connolly-cfrg-xwing-kem.
+    fn algorithm_oid() -> Option<&'static str> {
+        Some("1.3.6.1.4.1.62253.25722")
+    }
+
+    fn public_key_size() -> usize {
+        XWING_PUBLIC_KEY_SIZE
+    }
+
+    fn secret_key_size() -> usize {
+        XWING_SECRET_KEY_SIZE
+    }
+
+    fn ciphertext_size() -> usize {
+        XWING_CIPHERTEXT_SIZE
+    }
+
+    fn shared_secret_size() -> usize {
+        XWING_SHARED_SECRET_SIZE
+    }
+}
+
+/// Ephemeral-static X25519 Diffie-Hellman packaged as a KEM, for use as the
+/// classical half of a [`HybridKem`]. The ciphertext is the ephemeral public key.
+pub struct X25519Kem;
+
+impl KeyEncapsulation for X25519Kem {
+    type PublicKey = x25519_dalek::PublicKey;
+    type SecretKey = StaticSecret;
+    type Ciphertext = x25519_dalek::PublicKey;
+    type SharedSecret = SharedSecret;
+
+    fn keypair() -> Result<(Self::PublicKey, Self::SecretKey)> {
+        let mut bytes = Zeroizing::new([0u8; X25519_SIZE]);
+        utils::keygen_seed(&mut utils::SystemRng, bytes.as_mut())
+            .map_err(|e| e.with_algorithm("X25519"))?;
+        let secret_key = StaticSecret::from(*bytes);
+        Ok((x25519_dalek::PublicKey::from(&secret_key), secret_key))
+    }
+
+    fn encapsulate(public_key: &Self::PublicKey) -> Result<(Self::Ciphertext, Self::SharedSecret)> {
+        let mut bytes = Zeroizing::new([0u8; X25519_SIZE]);
+        utils::fill_random(
+            &mut utils::SystemRng,
+            bytes.as_mut(),
+            QraiopError::EncapsulationFailed,
+        )
+        .map_err(|e| e.with_algorithm("X25519"))?;
+        let ephemeral = StaticSecret::from(*bytes);
+        let shared = ephemeral.diffie_hellman(public_key);
+        if !shared.was_contributory() {
+            return Err(QraiopError::EncapsulationFailed(
+                "X25519 public key is a low-order point".to_string(),
+            ));
+        }
+        Ok((
+            x25519_dalek::PublicKey::from(&ephemeral),
+            SharedSecret(Secret::new(shared.as_bytes().to_vec())),
+        ))
+    }
+
+    fn decapsulate(
+        secret_key: &Self::SecretKey,
+        ciphertext: &Self::Ciphertext,
+    ) -> Result<Self::SharedSecret> {
+        let shared = secret_key.diffie_hellman(ciphertext);
+        if !shared.was_contributory() {
+            return Err(QraiopError::DecapsulationFailed(
+                "Invalid ciphertext: X25519 ephemeral key is a low-order point".to_string(),
+            ));
+        }
+        Ok(SharedSecret(Secret::new(shared.as_bytes().to_vec())))
+    }
+
+    fn algorithm_name() -> &'static str {
+        "X25519"
+    }
+
+    /// `id-X25519` (RFC 8410).
+    fn algorithm_oid() -> Option<&'static str> {
+        Some("1.3.101.110")
+    }
+
+    fn public_key_size() -> usize {
+        X25519_SIZE
+    }
+
+    fn secret_key_size() -> usize {
+        X25519_SIZE
+    }
+
+    fn ciphertext_size() -> usize {
+        X25519_SIZE
+    }
+
+    fn shared_secret_size() -> usize {
+        X25519_SIZE
+    }
+}
+
+/// Derives the final hybrid secret from both component secrets and the
+/// ciphertexts they were transported in.
+pub trait KemCombiner {
+    fn combine(
+        classical_secret: &[u8],
+        post_quantum_secret: &[u8],
+        classical_ciphertext: &[u8],
+        post_quantum_ciphertext: &[u8],
+    ) -> SharedSecret;
+
+    /// Length in bytes of the combined secret.
+    fn output_size() -> usize;
+}
+
+/// Default combiner: SHA3-256 over a domain label followed by each input,
+/// length-prefixed so that no two input tuples hash the same bytes.
+pub struct Sha3Combiner;
+
+impl Sha3Combiner {
+    const LABEL: &'static [u8] = b"QRAIOP-HybridKem-SHA3-256";
+}
+
+impl KemCombiner for Sha3Combiner {
+    fn combine(
+        classical_secret: &[u8],
+        post_quantum_secret: &[u8],
+        classical_ciphertext: &[u8],
+        post_quantum_ciphertext: &[u8],
+    ) -> SharedSecret {
+        let mut hasher = Sha3_256::new();
+        Digest::update(&mut hasher, Self::LABEL);
+        for input in [
+            classical_secret,
+            post_quantum_secret,
+            classical_ciphertext,
+            post_quantum_ciphertext,
+        ] {
+            Digest::update(&mut hasher, (input.len() as u32).to_be_bytes());
+            Digest::update(&mut hasher, input);
+        }
+        SharedSecret(Secret::new(hasher.finalize().to_vec()))
+    }
+
+    fn output_size() -> usize {
+        32
+    }
+}
+
+/// Which half of a [`HybridKem`] an error came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HybridComponent {
+    Classical,
+    PostQuantum,
+}
+
+impl fmt::Display for HybridComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HybridComponent::Classical => "classical",
+            HybridComponent::PostQuantum => "post-quantum",
+        })
+    }
+}
+
+/// A [`HybridKem`] operation failed in one of its components, surfaced as
+/// [`QraiopError::Hybrid`] so a broken side of a handshake can be told
+/// apart from the other.
+#[derive(Debug, thiserror::Error)]
+#[error("{component} component of hybrid KEM failed: {source}")]
+pub struct HybridError {
+    pub component: HybridComponent,
+    pub source: Box<QraiopError>,
+}
+
+impl HybridComponent {
+    fn wrap(self, error: QraiopError) -> QraiopError {
+        QraiopError::Hybrid(HybridError {
+            component: self,
+            source: Box::new(error),
+        })
+    }
+}
+
+/// Public key of a [`HybridKem`].
+pub struct HybridPublicKey<C: KeyEncapsulation, P: KeyEncapsulation> {
+    pub classical: C::PublicKey,
+    pub post_quantum: P::PublicKey,
+}
+
+/// Secret key of a [`HybridKem`]; each component is responsible for wiping
+/// its own key material.
+pub struct HybridSecretKey<C: KeyEncapsulation, P: KeyEncapsulation> {
+    pub classical: C::SecretKey,
+    pub post_quantum: P::SecretKey,
+}
+
+/// Ciphertext of a [`HybridKem`].
+pub struct HybridCiphertext<C: KeyEncapsulation, P: KeyEncapsulation> {
+    pub classical: C::Ciphertext,
+    pub post_quantum: P::Ciphertext,
+}
+
+/// Generic hybrid KEM running a classical KEM `C` and a post-quantum KEM `P`
+/// side by side and merging their secrets with the combiner `F`.
+///
+/// Both component shared secrets must zeroize themselves on drop; they only
+/// live until the combiner has consumed them.
+///
+/// If either component fails, the whole operation fails with a
+/// [`HybridError`] naming that component; there is never a fallback to a
+/// secret derived from the other component alone.
+pub struct HybridKem<C, P, F = Sha3Combiner>(PhantomData<(C, P, F)>);
+
+impl<C, P, F> KeyEncapsulation for HybridKem<C, P, F>
+where
+    C: KeyEncapsulation,
+    P: KeyEncapsulation,
+    C::Ciphertext: AsRef<[u8]>,
+    P::Ciphertext: AsRef<[u8]>,
+    C::SharedSecret: AsRef<[u8]> + ZeroizeOnDrop,
+    P::SharedSecret: AsRef<[u8]> + ZeroizeOnDrop,
+    F: KemCombiner,
+{
+    type PublicKey = HybridPublicKey<C, P>;
+    type SecretKey = HybridSecretKey<C, P>;
+    type Ciphertext = HybridCiphertext<C, P>;
+    type SharedSecret = SharedSecret;
+
+    fn keypair() -> Result<(Self::PublicKey, Self::SecretKey)> {
+        let (classical_pk, classical_sk) =
+            C::keypair().map_err(|e| HybridComponent::Classical.wrap(e))?;
+        let (post_quantum_pk, post_quantum_sk) =
+            P::keypair().map_err(|e| HybridComponent::PostQuantum.wrap(e))?;
+        Ok((
+            HybridPublicKey {
+                classical: classical_pk,
+                post_quantum: post_quantum_pk,
+            },
+            HybridSecretKey {
+                classical: classical_sk,
+                post_quantum: post_quantum_sk,
+            },
+        ))
+    }
+
+    fn encapsulate(public_key: &Self::PublicKey) -> Result<(Self::Ciphertext, Self::SharedSecret)> {
+        let (classical_ct, classical_ss) = C::encapsulate(&public_key.classical)
+            .map_err(|e| HybridComponent::Classical.wrap(e))?;
+        let (post_quantum_ct, post_quantum_ss) = P::encapsulate(&public_key.post_quantum)
+            .map_err(|e| HybridComponent::PostQuantum.wrap(e))?;
+        let shared_secret = F::combine(
+            classical_ss.as_ref(),
+            post_quantum_ss.as_ref(),
+            classical_ct.as_ref(),
+            post_quantum_ct.as_ref(),
+        );
+        Ok((
+            HybridCiphertext {
+                classical: classical_ct,
+                post_quantum: post_quantum_ct,
+            },
+            shared_secret,
+        ))
+    }
+
+    fn decapsulate(
+        secret_key: &Self::SecretKey,
+        ciphertext: &Self::Ciphertext,
+    ) -> Result<Self::SharedSecret> {
+        let classical_ss = C::decapsulate(&secret_key.classical, &ciphertext.classical)
+            .map_err(|e| HybridComponent::Classical.wrap(e))?;
+        let post_quantum_ss = P::decapsulate(&secret_key.post_quantum, &ciphertext.post_quantum)
+            .map_err(|e| HybridComponent::PostQuantum.wrap(e))?;
+        Ok(F::combine(
+            classical_ss.as_ref(),
+            post_quantum_ss.as_ref(),
+            ciphertext.classical.as_ref(),
+            ciphertext.post_quantum.as_ref(),
+        ))
+    }
+
+    fn algorithm_name() -> &'static str {
+        "HybridKem"
+    }
+
+    /// Arbitrary component pairs have no registered identifier.
+    fn algorithm_oid() -> Option<&'static str> {
+        None
+    }
+
+    fn public_key_size() -> usize {
+        C::public_key_size() + P::public_key_size()
+    }
+
+    fn secret_key_size() -> usize {
+        C::secret_key_size() + P::secret_key_size()
+    }
+
+    fn ciphertext_size() -> usize {
+        C::ciphertext_size() + P::ciphertext_size()
+    }
+
+    fn shared_secret_size() -> usize {
+        F::output_size()
+    }
+}
+
+const REKEY_LABEL: &[u8] = b"QRAIOP-Rekey-SHA3-256";
+
+fn rekey_combine(old_secret: &[u8], fresh_secret: &[u8]) -> SharedSecret {
+    let mut hasher = Sha3_256::new();
+    Digest::update(&mut hasher, REKEY_LABEL);
+    for input in [old_secret, fresh_secret] {
+        Digest::update(&mut hasher, (input.len() as u32).to_be_bytes());
+        Digest::update(&mut hasher, input);
+    }
+    SharedSecret(Secret::new(hasher.finalize().to_vec()))
+}
+
+/// Rotates a shared secret onto a new key pair of the KEM `K`.
+///
+/// A KEM cannot wrap a chosen value, so the old secret is not carried over
+/// unchanged: this encapsulates a fresh secret to `new_public_key` and
+/// returns SHA3-256 over a domain label, `old_secret` and the fresh secret.
+/// **The rotated secret differs from `old_secret`**; rederive any keys that
+/// came from it. The holder of the new secret key recovers it with
+/// [`rekey_decapsulate`].
+///
+/// Security model: the rotated secret is as strong as the stronger of its
+/// two inputs. An attacker needs both `old_secret` and either the new
+/// secret key or a break of `K` to learn it, so rotating after a suspected
+/// key compromise recovers secrecy as long as `old_secret` was not itself
+/// exposed. Conversely, both sides must already share `old_secret`; to
+/// hand a secret to a party that never had it, encapsulate to them
+/// directly instead.
+pub fn rekey<K>(
+    old_secret: &impl AsRef<[u8]>,
+    new_public_key: &K::PublicKey,
+) -> Result<(K::Ciphertext, SharedSecret)>
+where
+    K: KeyEncapsulation,
+    K::SharedSecret: AsRef<[u8]>,
+{
+    let (ciphertext, fresh) = K::encapsulate(new_public_key)?;
+    let secret = rekey_combine(old_secret.as_ref(), fresh.as_ref());
+    Ok((ciphertext, secret))
+}
+
+/// Recipient side of [`rekey`]: decapsulates `ciphertext` with the new
+/// secret key and combines the result with `old_secret` the same way.
+pub fn rekey_decapsulate<K>(
+    old_secret: &impl AsRef<[u8]>,
+    new_secret_key: &K::SecretKey,
+    ciphertext: &K::Ciphertext,
+) -> Result<SharedSecret>
+where
+    K: KeyEncapsulation,
+    K::SharedSecret: AsRef<[u8]>,
+{
+    let fresh = K::decapsulate(new_secret_key, ciphertext)?;
+    Ok(rekey_combine(old_secret.as_ref(), fresh.as_ref()))
+}
+
+#[cfg(feature = "dilithium")]
+/// Prefixed to the message before either component signs it, so neither
+/// half verifies on its own as a signature over the caller's message.
+const HYBRID_SIGNATURE_LABEL: &[u8] = b"QRAIOP-HybridSignature-Ed25519-ML-DSA-65";
+
+#[cfg(feature = "dilithium")]
+/// Size in bytes of a [`HybridSignature`] public key (`pk_Ed25519 || pk_ML-DSA`).
+pub const HYBRID_SIGNATURE_PUBLIC_KEY_SIZE: usize =
+    ed25519_dalek::PUBLIC_KEY_LENGTH + ml_dsa_65::PUBLIC_KEY_SIZE;
+#[cfg(feature = "dilithium")]
+/// Size in bytes of a [`CombinedSignature`]: each component signature
+/// preceded by its length as a big-endian `u32`.
+pub const HYBRID_SIGNATURE_SIZE: usize =
+    4 + ed25519_dalek::SIGNATURE_LENGTH + 4 + ml_dsa_65::SIGNATURE_SIZE;
+
+#[cfg(feature = "dilithium")]
+/// Public key of a [`HybridSignature`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HybridSignaturePublicKey {
+    pub classical: ed25519_dalek::VerifyingKey,
+    pub post_quantum: dilithium::PublicKey,
+}
+
+#[cfg(feature = "dilithium")]
+impl HybridSignaturePublicKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_length(
+            "Hybrid signature public key",
+            bytes.len(),
+            HYBRID_SIGNATURE_PUBLIC_KEY_SIZE,
+        )?;
+        let (classical, post_quantum) = bytes.split_at(ed25519_dalek::PUBLIC_KEY_LENGTH);
+        let classical = classical
+            .try_into()
+            .ok()
+            .and_then(|bytes| ed25519_dalek::VerifyingKey::from_bytes(bytes).ok())
+            .ok_or_else(|| QraiopError::InvalidKey("Invalid Ed25519 public key".to_string()))?;
+        Ok(Self {
+            classical,
+            post_quantum: dilithium::PublicKey::from_bytes(post_quantum)?,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [
+            self.classical.as_bytes().as_slice(),
+            self.post_quantum.as_bytes(),
+        ]
+        .concat()
+    }
+}
+
+#[cfg(feature = "dilithium")]
+/// Secret key of a [`HybridSignature`]. Both components wipe themselves on
+/// drop.
+#[derive(Clone)]
+pub struct HybridSignatureSecretKey {
+    pub classical: ed25519_dalek::SigningKey,
+    pub post_quantum: dilithium::SecretKey,
+}
+
+#[cfg(feature = "dilithium")]
+impl ZeroizeOnDrop for HybridSignatureSecretKey {}
+
+#[cfg(feature = "dilithium")]
+impl fmt::Debug for HybridSignatureSecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HybridSignatureSecretKey([REDACTED])")
+    }
+}
+
+#[cfg(feature = "dilithium")]
+/// Signature of a [`HybridSignature`]:
+/// `len(sig_Ed25519) || sig_Ed25519 || len(sig_ML-DSA) || sig_ML-DSA`, with
+/// lengths as big-endian `u32`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CombinedSignature(Vec<u8>);
+
+#[cfg(feature = "dilithium")]
+impl CombinedSignature {
+    /// Parses an encoded signature, checking only that the length prefixes
+    /// account for every byte.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if Self::split(bytes).is_none() {
+            return Err(QraiopError::SerializationError(
+                "Malformed hybrid signature encoding".to_string(),
+            ));
+        }
+        Ok(Self(bytes.to_vec()))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    fn encode(classical: &[u8], post_quantum: &[u8]) -> Self {
+        let mut out = Vec::with_capacity(8 + classical.len() + post_quantum.len());
+        for part in [classical, post_quantum] {
+            out.extend_from_slice(&(part.len() as u32).to_be_bytes());
+            out.extend_from_slice(part);
+        }
+        Self(out)
+    }
+
+    /// Splits an encoding into the Ed25519 and ML-DSA signatures.
+    fn split(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+        fn take(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+            let (len, rest) = bytes.split_first_chunk::<4>()?;
+            let len = u32::from_be_bytes(*len) as usize;
+            (rest.len() >= len).then(|| rest.split_at(len))
+        }
+        let (classical, rest) = take(bytes)?;
+        let (post_quantum, rest) = take(rest)?;
+        rest.is_empty().then_some((classical, post_quantum))
+    }
+}
+
+#[cfg(feature = "dilithium")]
+/// Ed25519 and ML-DSA-65 composite signature, for deployments migrating to
+/// post-quantum signatures that still need classical assurance.
+///
+/// Both components sign the same domain-separated message, and a signature
+/// is valid only if both of them verify, so forging one requires breaking
+/// both Ed25519 and ML-DSA-65. The reported security level is ML-DSA-65's,
+/// as Ed25519 adds nothing against a quantum adversary.
+pub struct HybridSignature;
+
+#[cfg(feature = "dilithium")]
+impl HybridSignature {
+    fn labeled(message: &[u8]) -> Vec<u8> {
+        [HYBRID_SIGNATURE_LABEL, message].concat()
+    }
+}
+
+#[cfg(feature = "dilithium")]
+impl DigitalSignature for HybridSignature {
+    type PublicKey = HybridSignaturePublicKey;
+    type SecretKey = HybridSignatureSecretKey;
+    type Signature = CombinedSignature;
+
+    fn keypair() -> Result<(Self::PublicKey, Self::SecretKey)> {
+        let mut seed = Zeroizing::new([0u8; ed25519_dalek::SECRET_KEY_LENGTH]);
+        utils::keygen_seed(&mut utils::SystemRng, seed.as_mut())
+            .map_err(|e| e.with_algorithm("Ed25519+ML-DSA-65"))?;
+        let classical = ed25519_dalek::SigningKey::from_bytes(&seed);
+        let (post_quantum_pk, post_quantum_sk) = MlDsa65::keypair()?;
+        Ok((
+            HybridSignaturePublicKey {
+                classical: classical.verifying_key(),
+                post_quantum: post_quantum_pk,
+            },
+            HybridSignatureSecretKey {
+                classical,
+                post_quantum: post_quantum_sk,
+            },
+        ))
+    }
+
+    fn sign(secret_key: &Self::SecretKey, message: &[u8]) -> Result<Self::Signature> {
+        let message = Self::labeled(message);
+        let classical = secret_key.classical.sign(&message);
+        let post_quantum = MlDsa65::sign(&secret_key.post_quantum, &message)?;
+        Ok(CombinedSignature::encode(
+            &classical.to_bytes(),
+            post_quantum.as_bytes(),
+        ))
+    }
+
+    fn verify(
+        public_key: &Self::PublicKey,
+        message: &[u8],
+        signature: &Self::Signature,
+    ) -> Result<bool> {
+        let Some((classical, post_quantum)) = CombinedSignature::split(&signature.0) else {
+            return Ok(false);
+        };
+        let Ok(classical) = ed25519_dalek::Signature::from_slice(classical) else {
+            return Ok(false);
+        };
+        let Ok(post_quantum) = dilithium::Signature::from_bytes(post_quantum) else {
+            return Ok(false);
+        };
+
+        let message = Self::labeled(message);
+        let classical_valid = public_key
+            .classical
+            .verify_strict(&message, &classical)
+            .is_ok();
+        let post_quantum_valid =
+            MlDsa65::verify(&public_key.post_quantum, &message, &post_quantum)?;
+        Ok(classical_valid && post_quantum_valid)
+    }
+
+    fn algorithm_name() -> &'static str {
+        "Ed25519+ML-DSA-65"
+    }
+
+    /// The combined encoding is specific to this crate, so no composite
+    /// signature OID applies.
+    fn algorithm_oid() -> Option<&'static str> {
+        None
+    }
+
+    fn security_level() -> SecurityLevel {
+        MlDsa65::security_level()
+    }
+
+    fn public_key_size() -> usize {
+        HYBRID_SIGNATURE_PUBLIC_KEY_SIZE
+    }
+
+    fn secret_key_size() -> usize {
+        ed25519_dalek::SECRET_KEY_LENGTH + ml_dsa_65::SECRET_KEY_SIZE
+    }
+
+    fn signature_size() -> usize {
+        HYBRID_SIGNATURE_SIZE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pqc::kyber::{MlKem1024, MlKem512};
+
+    #[derive(serde::Deserialize)]
+    struct XWingVector {
+        seed: String,
+        eseed: String,
+        ss: String,
+        sk: String,
+        pk: String,
+        ct: String,
+    }
+
+    #[test]
+    fn test_shared_secret_is_eq() {
+        fn assert_eq_impl<T: Eq>() {}
+        assert_eq_impl::<SharedSecret>();
+    }
+
+    #[test]
+    fn test_xwing_roundtrip() {
+        let (pk, sk) = XWing::keypair().unwrap();
+        let (ct, ss1) = XWing::encapsulate(&pk).unwrap();
+        let ss2 = XWing::decapsulate(&sk, &ct).unwrap();
+
+        assert_eq!(pk.as_bytes().len(), XWING_PUBLIC_KEY_SIZE);
+        assert_eq!(ct.as_bytes().len(), XWING_CIPHERTEXT_SIZE);
+        assert!(ss1 == ss2, "Shared secrets should match");
+    }
+
+    /// Test vectors published alongside draft-connolly-cfrg-xwing-kem.
+    #[test]
+    fn test_xwing_known_answers() {
+        let vectors: Vec<XWingVector> =
+            serde_json::from_str(include_str!("../tests/vectors/xwing.json")).unwrap();
+        assert!(!vectors.is_empty());
+
+        for vector in vectors {
+            let seed: [u8; 32] = hex::decode(&vector.seed).unwrap().try_into().unwrap();
+            let eseed: [u8; 64] = hex::decode(&vector.eseed).unwrap().try_into().unwrap();
+
+            let (pk, sk) = XWing::keypair_from_seed(&seed).unwrap();
+            assert_eq!(hex::encode(sk.as_bytes()), vector.sk);
+            assert_eq!(hex::encode(pk.as_bytes()), vector.pk);
+
+            let (ct, ss) = XWing::encapsulate_derand(&pk, &eseed).unwrap();
+            assert_eq!(hex::encode(ct.as_bytes()), vector.ct);
+            assert_eq!(hex::encode(ss.as_bytes()), vector.ss);
+
+            let ss = XWing::decapsulate(&sk, &ct).unwrap();
+            assert_eq!(hex::encode(ss.as_bytes()), vector.ss);
+        }
+    }
+
+    #[test]
+    fn test_xwing_rejects_malformed_input() {
+        let (pk, sk) = XWing::keypair().unwrap();
+        let (ct, _) = XWing::encapsulate(&pk).unwrap();
+
+        assert!(XWingPublicKey::from_bytes(&pk.as_bytes()[1..]).is_err());
+        assert!(XWingCiphertext::from_bytes(&ct.as_bytes()[1..]).is_err());
+        let truncated = XWingCiphertext(ct.as_bytes()[1..].to_vec());
+        assert!(matches!(
+            XWing::decapsulate(&sk, &truncated),
+            Err(QraiopError::InvalidCiphertext(_))
+        ));
+    }
+
+    #[test]
+    fn test_xwing_with_failing_rng() {
+        assert!(matches!(
+            XWing::keypair_with_rng(&mut utils::FailingRng),
+            Err(QraiopError::KeyGenerationFailed(_))
+        ));
+
+        let (pk, _) = XWing::keypair().unwrap();
+        assert!(matches!(
+            XWing::encapsulate_with_rng(&pk, &mut utils::FailingRng),
+            Err(QraiopError::EncapsulationFailed(_))
+        ));
+    }
+
+    type X25519MlKem1024 = HybridKem<X25519Kem, MlKem1024>;
+
+    #[test]
+    fn test_hybrid_kem_roundtrip() {
+        let (pk, sk) = X25519MlKem1024::keypair().unwrap();
+        let (ct, ss1) = X25519MlKem1024::encapsulate(&pk).unwrap();
+        let ss2 = X25519MlKem1024::decapsulate(&sk, &ct).unwrap();
+        assert_eq!(ss1.as_bytes().len(), 32);
+        assert!(ss1 == ss2, "Shared secrets should match");
+    }
+
+    #[test]
+    fn test_hybrid_kem_binds_both_components() {
+        let (pk, sk) = X25519MlKem1024::keypair().unwrap();
+        let (ct1, ss1) = X25519MlKem1024::encapsulate(&pk).unwrap();
+        let (ct2, ss2) = X25519MlKem1024::encapsulate(&pk).unwrap();
+
+        // Swapping in the other encapsulation's classical component
+        let mixed = HybridCiphertext::<X25519Kem, MlKem1024> {
+            classical: ct2.classical,
+            post_quantum: ct1.post_quantum.clone(),
+        };
+        let ss = X25519MlKem1024::decapsulate(&sk, &mixed).unwrap();
+        assert!(ss != ss1 && ss != ss2);
+
+        // ... and its post-quantum component
+        let mixed = HybridCiphertext::<X25519Kem, MlKem1024> {
+            classical: ct1.classical,
+            post_quantum: ct2.post_quantum,
+        };
+        let ss = X25519MlKem1024::decapsulate(&sk, &mixed).unwrap();
+        assert!(ss != ss1 && ss != ss2);
+    }
+
+    #[test]
+    fn test_hybrid_kem_reports_failing_component() {
+        let (pk, sk) = X25519MlKem1024::keypair().unwrap();
+        let (ct, _) = X25519MlKem1024::encapsulate(&pk).unwrap();
+
+        let component_of =
+            |ct: &HybridCiphertext<X25519Kem, MlKem1024>| match X25519MlKem1024::decapsulate(
+                &sk, ct,
+            ) {
+                Err(QraiopError::Hybrid(e)) => (e.component, *e.source),
+                other => panic!("expected a hybrid error, got {:?}", other.map(|_| ())),
+            };
+
+        // The identity point is a low-order X25519 public key
+        let bad_classical = HybridCiphertext::<X25519Kem, MlKem1024> {
+            classical: x25519_dalek::PublicKey::from([0u8; 32]),
+            post_quantum: ct.post_quantum.clone(),
+        };
+        let (component, source) = component_of(&bad_classical);
+        assert_eq!(component, HybridComponent::Classical);
+        assert!(matches!(source, QraiopError::DecapsulationFailed(_)));
+
+        // A well-formed ML-KEM-512 ciphertext has the wrong length here
+        let (pk_512, _) = MlKem512::keypair().unwrap();
+        let bad_post_quantum = HybridCiphertext::<X25519Kem, MlKem1024> {
+            classical: ct.classical,
+            post_quantum: MlKem512::encapsulate(&pk_512).unwrap().0,
+        };
+        let (component, source) = component_of(&bad_post_quantum);
+        assert_eq!(component, HybridComponent::PostQuantum);
+        assert!(matches!(source, QraiopError::InvalidCiphertext(_)));
+        assert!(X25519MlKem1024::decapsulate(&sk, &bad_post_quantum)
+            .unwrap_err()
+            .to_string()
+            .starts_with("post-quantum component of hybrid KEM failed: Invalid ciphertext"));
+    }
+
+    /// X25519, except that key generation always fails.
+    struct NoKeysKem;
+
+    impl KeyEncapsulation for NoKeysKem {
+        type PublicKey = x25519_dalek::PublicKey;
+        type SecretKey = StaticSecret;
+        type Ciphertext = x25519_dalek::PublicKey;
+        type SharedSecret = SharedSecret;
+
+        fn keypair() -> Result<(Self::PublicKey, Self::SecretKey)> {
+            Err(QraiopError::KeyGenerationFailed("no keys".to_string()))
+        }
+
+        fn encapsulate(
+            public_key: &Self::PublicKey,
+        ) -> Result<(Self::Ciphertext, Self::SharedSecret)> {
+            X25519Kem::encapsulate(public_key)
+        }
+
+        fn decapsulate(
+            secret_key: &Self::SecretKey,
+            ciphertext: &Self::Ciphertext,
+        ) -> Result<Self::SharedSecret> {
+            X25519Kem::decapsulate(secret_key, ciphertext)
+        }
+
+        fn algorithm_name() -> &'static str {
+            "no keys"
+        }
+
+        fn algorithm_oid() -> Option<&'static str> {
+            None
+        }
+
+        fn public_key_size() -> usize {
+            X25519Kem::public_key_size()
+        }
+
+        fn secret_key_size() -> usize {
+            X25519Kem::secret_key_size()
+        }
+
+        fn ciphertext_size() -> usize {
+            X25519Kem::ciphertext_size()
+        }
+
+        fn shared_secret_size() -> usize {
+            X25519Kem::shared_secret_size()
+        }
+    }
+
+    #[test]
+    fn test_hybrid_keypair_reports_failing_component() {
+        let component_of = |result: Result<()>| match result {
+            Err(QraiopError::Hybrid(e)) => (e.component, *e.source),
+            other => panic!("expected a hybrid error, got {:?}", other),
+        };
+
+        let (component, source) =
+            component_of(HybridKem::<NoKeysKem, MlKem1024>::keypair().map(|_| ()));
+        assert_eq!(component, HybridComponent::Classical);
+        assert!(matches!(source, QraiopError::KeyGenerationFailed(_)));
+
+        let (component, source) =
+            component_of(HybridKem::<X25519Kem, NoKeysKem>::keypair().map(|_| ()));
+        assert_eq!(component, HybridComponent::PostQuantum);
+        assert!(matches!(source, QraiopError::KeyGenerationFailed(_)));
+    }
+
+    struct ReversedCombiner;
+
+    impl KemCombiner for ReversedCombiner {
+        fn combine(
+            classical_secret: &[u8],
+            post_quantum_secret: &[u8],
+            classical_ciphertext: &[u8],
+            post_quantum_ciphertext: &[u8],
+        ) -> SharedSecret {
+            Sha3Combiner::combine(
+                post_quantum_secret,
+                classical_secret,
+                post_quantum_ciphertext,
+                classical_ciphertext,
+            )
+        }
+
+        fn output_size() -> usize {
+            Sha3Combiner::output_size()
+        }
+    }
+
+    #[test]
+    fn test_rekey_roundtrip() {
+        let (old_pk, old_sk) = MlKem768::keypair().unwrap();
+        let (ct, old_ss) = MlKem768::encapsulate(&old_pk).unwrap();
+        assert_eq!(MlKem768::decapsulate(&old_sk, &ct).unwrap(), old_ss);
+
+        let (new_pk, new_sk) = MlKem768::keypair().unwrap();
+        let (ct, rotated) = rekey::<MlKem768>(&old_ss, &new_pk).unwrap();
+        assert_ne!(rotated.as_bytes(), old_ss.as_bytes());
+        assert_eq!(
+            rekey_decapsulate::<MlKem768>(&old_ss, &new_sk, &ct).unwrap(),
+            rotated
+        );
+
+        // Both the old secret and the new secret key are needed
+        let (_, other_ss) = MlKem768::encapsulate(&old_pk).unwrap();
+        assert_ne!(
+            rekey_decapsulate::<MlKem768>(&other_ss, &new_sk, &ct).unwrap(),
+            rotated
+        );
+        assert_ne!(
+            rekey_decapsulate::<MlKem768>(&old_ss, &old_sk, &ct).unwrap(),
+            rotated
+        );
+    }
+
+    #[test]
+    fn test_hybrid_kem_custom_combiner() {
+        type Custom = HybridKem<X25519Kem, MlKem1024, ReversedCombiner>;
+
+        let (pk, sk) = Custom::keypair().unwrap();
+        let (ct, ss1) = Custom::encapsulate(&pk).unwrap();
+        let ss2 = Custom::decapsulate(&sk, &ct).unwrap();
+        assert!(ss1 == ss2);
+
+        let default = X25519MlKem1024::decapsulate(&sk, &ct).unwrap();
+        assert!(default != ss1);
+    }
+
+    #[test]
+    fn test_sizes() {
+        let (pk, sk) = XWing::keypair().unwrap();
+        let (ct, ss) = XWing::encapsulate(&pk).unwrap();
+        assert_eq!(pk.as_bytes().len(), XWing::public_key_size());
+        assert_eq!(sk.as_bytes().len(), XWing::secret_key_size());
+        assert_eq!(ct.as_bytes().len(), XWing::ciphertext_size());
+        assert_eq!(ss.as_bytes().len(), XWing::shared_secret_size());
+
+        assert_eq!(X25519MlKem1024::public_key_size(), 32 + 1568);
+        assert_eq!(X25519MlKem1024::secret_key_size(), 32 + 3168);
+        assert_eq!(X25519MlKem1024::ciphertext_size(), 32 + 1568);
+        assert_eq!(X25519MlKem1024::shared_secret_size(), 32);
+    }
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let (pk, sk) = XWing::keypair_from_seed(&[0xab; 32]).unwrap();
+        let (_, ss) = XWing::encapsulate(&pk).unwrap();
+        assert_eq!(format!("{:?}", sk), "XWingSecretKey([REDACTED; 32])");
+        assert_eq!(format!("{:?}", ss), "SharedSecret([REDACTED; 32])");
+    }
+
+    #[test]
+    #[cfg(feature = "dilithium")]
+    fn test_hybrid_signature_roundtrip() {
+        let (pk, sk) = HybridSignature::keypair().unwrap();
+        let signature = HybridSignature::sign(&sk, b"transitional message").unwrap();
+        assert_eq!(
+            signature.as_bytes().len(),
+            HybridSignature::signature_size()
+        );
+        assert!(HybridSignature::verify(&pk, b"transitional message", &signature).unwrap());
+        assert!(!HybridSignature::verify(&pk, b"other message", &signature).unwrap());
+
+        let pk_bytes = pk.to_bytes();
+        assert_eq!(pk_bytes.len(), HybridSignature::public_key_size());
+        let pk = HybridSignaturePublicKey::from_bytes(&pk_bytes).unwrap();
+        let signature = CombinedSignature::from_bytes(signature.as_bytes()).unwrap();
+        assert!(HybridSignature::verify(&pk, b"transitional message", &signature).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "dilithium")]
+    fn test_hybrid_signature_needs_both_halves() {
+        let (pk, sk) = HybridSignature::keypair().unwrap();
+        let signature = HybridSignature::sign(&sk, b"message").unwrap();
+
+        // Byte 4 is inside the Ed25519 signature, the last byte inside ML-DSA
+        for index in [4, signature.as_bytes().len() - 1] {
+            let mut corrupted = signature.as_bytes().to_vec();
+            corrupted[index] ^= 0x01;
+            let corrupted = CombinedSignature::from_bytes(&corrupted).unwrap();
+            assert!(!HybridSignature::verify(&pk, b"message", &corrupted).unwrap());
+        }
+
+        // Neither half is a valid signature over the raw message by itself
+        let (_, post_quantum) = CombinedSignature::split(signature.as_bytes()).unwrap();
+        let post_quantum = dilithium::Signature::from_bytes(post_quantum).unwrap();
+        assert!(!MlDsa65::verify(&pk.post_quantum, b"message", &post_quantum).unwrap());
+
+        let (other_pk, _) = HybridSignature::keypair().unwrap();
+        assert!(!HybridSignature::verify(&other_pk, b"message", &signature).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "dilithium")]
+    fn test_hybrid_signature_rejects_malformed_encoding() {
+        let (_, sk) = HybridSignature::keypair().unwrap();
+        let signature = HybridSignature::sign(&sk, b"message").unwrap();
+        let bytes = signature.as_bytes();
+
+        assert!(CombinedSignature::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(CombinedSignature::from_bytes(&[bytes, &[0]].concat()).is_err());
+        assert!(CombinedSignature::from_bytes(&[0xff; 8]).is_err());
+        assert!(HybridSignaturePublicKey::from_bytes(&[0u8; 31]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "dilithium")]
+    fn test_hybrid_signature_secret_key_zeroizes() {
+        fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
+        assert_zeroize_on_drop::<ed25519_dalek::SigningKey>();
+        assert_zeroize_on_drop::<dilithium::SecretKey>();
+        assert_zeroize_on_drop::<HybridSignatureSecretKey>();
+
+        let (_, sk) = HybridSignature::keypair().unwrap();
+        assert_eq!(format!("{:?}", sk), "HybridSignatureSecretKey([REDACTED])");
+    }
+
+    #[test]
+    fn test_xwing_deserialize_checks_length() {
+        let (pk, _) = XWing::keypair().unwrap();
+        let (ct, _) = XWing::encapsulate(&pk).unwrap();
+        let decoded: XWingPublicKey =
+            bincode::deserialize(&bincode::serialize(&pk).unwrap()).unwrap();
+        assert_eq!(decoded.as_bytes(), pk.as_bytes());
+        let decoded: XWingCiphertext =
+            bincode::deserialize(&bincode::serialize(&ct).unwrap()).unwrap();
+        assert_eq!(decoded.as_bytes(), ct.as_bytes());
+
+        let short = bincode::serialize(&vec![0u8; 100]).unwrap();
+        assert!(bincode::deserialize::<XWingPublicKey>(&short).is_err());
+        assert!(bincode::deserialize::<XWingCiphertext>(&short).is_err());
+        assert!(serde_json::from_str::<XWingPublicKey>("[]").is_err());
+    }
+}
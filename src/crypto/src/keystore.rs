@@ -0,0 +1,319 @@
+//! Named KEM key pairs kept in one passphrase-protected container
+//!
+//! A [`Keystore`] maps names to [`KeyPair`]s of one parameter set. Saved,
+//! it is a single blob: a manifest of names and public keys readable
+//! without the passphrase, then every secret key, encrypted together with
+//! Argon2id and AES-256-GCM by [`passphrase::encrypt`]:
+//!
+//! ```text
+//! version (1) || manifest_length (4, BE) || manifest || sealed_secrets
+//!
+//! manifest = count (4, BE) || entry_0 || ... || entry_n
+//! entry_i  = name_length (2, BE) || name (UTF-8)
+//!              || spki_length (4, BE) || public_key (SPKI DER)
+//! sealed_secrets = passphrase::encrypt(
+//!     SHA-256(version || manifest_length || manifest)
+//!       || pkcs8_length (4, BE) || secret_key (PKCS#8 DER)   per entry
+//! )
+//! ```
+//!
+//! Entries are written in name order and the secret keys follow the
+//! manifest's order. The manifest hash inside the sealed part ties the two
+//! together, so a manifest edited to rename entries or swap in other public
+//! keys fails to load. Both key encodings carry the algorithm OID, so a
+//! store also fails to load as the wrong parameter set.
+
+use crate::pqc::{KeyEncapsulation, KeyPair};
+use crate::utils::passphrase;
+use crate::utils::pkcs8::{self, Pkcs8Algorithm};
+use crate::{QraiopError, Result};
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use argon2::Params;
+use core::fmt;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+const FORMAT_VERSION: u8 = 1;
+const HASH_SIZE: usize = 32;
+
+/// Named key pairs of the KEM `A`, e.g. `Keystore<MlKem768>`.
+pub struct Keystore<A: KeyEncapsulation> {
+    entries: BTreeMap<String, KeyPair<A>>,
+}
+
+impl<A> Keystore<A>
+where
+    A: KeyEncapsulation
+        + Pkcs8Algorithm<
+            PublicKey = <A as KeyEncapsulation>::PublicKey,
+            SecretKey = <A as KeyEncapsulation>::SecretKey,
+        >,
+{
+    pub fn new() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Stores `pair` under `name`, returning the pair it replaces, if any.
+    pub fn add(&mut self, name: impl Into<String>, pair: KeyPair<A>) -> Option<KeyPair<A>> {
+        self.entries.insert(name.into(), pair)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&KeyPair<A>> {
+        self.entries.get(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<KeyPair<A>> {
+        self.entries.remove(name)
+    }
+
+    /// Names of every entry, in order.
+    pub fn list(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Serializes the store, encrypting the secret keys under `passphrase`
+    /// with the default Argon2id cost.
+    pub fn save(&self, passphrase: &[u8]) -> Result<Vec<u8>> {
+        self.save_with_params(passphrase, Params::default())
+    }
+
+    pub(crate) fn save_with_params(&self, passphrase: &[u8], params: Params) -> Result<Vec<u8>> {
+        let count = u32::try_from(self.entries.len()).map_err(|_| {
+            QraiopError::SerializationError("Keystore has too many entries".to_string())
+        })?;
+        let mut manifest = count.to_be_bytes().to_vec();
+        let mut secrets = Zeroizing::new(Vec::new());
+        for (name, pair) in &self.entries {
+            let name_length = u16::try_from(name.len()).map_err(|_| {
+                QraiopError::SerializationError(format!(
+                    "Keystore entry name is {} bytes, at most {} allowed",
+                    name.len(),
+                    u16::MAX
+                ))
+            })?;
+            manifest.extend_from_slice(&name_length.to_be_bytes());
+            manifest.extend_from_slice(name.as_bytes());
+            let public_key = pkcs8::to_spki_der::<A>(pair.public())?;
+            manifest.extend_from_slice(&(public_key.len() as u32).to_be_bytes());
+            manifest.extend_from_slice(&public_key);
+            let secret_key = pkcs8::to_pkcs8_der::<A>(pair.secret())?;
+            secrets.extend_from_slice(&(secret_key.len() as u32).to_be_bytes());
+            secrets.extend_from_slice(&secret_key);
+        }
+
+        let mut out = Vec::with_capacity(1 + 4 + manifest.len());
+        out.push(FORMAT_VERSION);
+        out.extend_from_slice(&(manifest.len() as u32).to_be_bytes());
+        out.extend_from_slice(&manifest);
+        let mut plaintext = Zeroizing::new(Sha256::digest(&out).to_vec());
+        plaintext.extend_from_slice(&secrets);
+        out.extend_from_slice(&passphrase::encrypt_with_params(
+            passphrase, &plaintext, params,
+        )?);
+        Ok(out)
+    }
+
+    /// Parses a store written by [`Keystore::save`] and decrypts its secret
+    /// keys. An unknown version, a malformed container, a wrong passphrase
+    /// or a manifest that does not match the secret keys all fail with
+    /// [`QraiopError::SerializationError`].
+    pub fn load(passphrase: &[u8], data: &[u8]) -> Result<Self> {
+        let mut reader = data;
+        let version = take(&mut reader, 1)?[0];
+        if version != FORMAT_VERSION {
+            return Err(QraiopError::SerializationError(format!(
+                "Unsupported keystore version {}",
+                version
+            )));
+        }
+        let manifest_length = take_u32(&mut reader)?;
+        let mut manifest = take(&mut reader, manifest_length)?;
+        let header = &data[..data.len() - reader.len()];
+
+        let count = take_u32(&mut manifest)?;
+        let mut public_keys = Vec::new();
+        for _ in 0..count {
+            let name_length = take(&mut manifest, 2)?;
+            let name_length = usize::from(u16::from_be_bytes([name_length[0], name_length[1]]));
+            let name = core::str::from_utf8(take(&mut manifest, name_length)?).map_err(|_| {
+                QraiopError::SerializationError("Keystore entry name is not UTF-8".to_string())
+            })?;
+            let spki_length = take_u32(&mut manifest)?;
+            let public_key = pkcs8::from_spki_der::<A>(take(&mut manifest, spki_length)?)?;
+            public_keys.push((name.to_string(), public_key));
+        }
+        if !manifest.is_empty() {
+            return Err(trailing_bytes("manifest"));
+        }
+
+        let plaintext = passphrase::decrypt(passphrase, reader)?;
+        let mut secrets = plaintext.as_slice();
+        if take(&mut secrets, HASH_SIZE)? != Sha256::digest(header).as_slice() {
+            return Err(QraiopError::SerializationError(
+                "Keystore manifest does not match its secret keys".to_string(),
+            ));
+        }
+        let mut entries = BTreeMap::new();
+        for (name, public_key) in public_keys {
+            let pkcs8_length = take_u32(&mut secrets)?;
+            let secret_key = pkcs8::from_pkcs8_der::<A>(take(&mut secrets, pkcs8_length)?)?;
+            if entries
+                .insert(name, KeyPair::from_parts(public_key, secret_key))
+                .is_some()
+            {
+                return Err(QraiopError::SerializationError(
+                    "Keystore has two entries with the same name".to_string(),
+                ));
+            }
+        }
+        if !secrets.is_empty() {
+            return Err(trailing_bytes("secret keys"));
+        }
+        Ok(Self { entries })
+    }
+}
+
+// Written out to avoid the `A: Default` bound a derive would add
+impl<A> Default for Keystore<A>
+where
+    A: KeyEncapsulation
+        + Pkcs8Algorithm<
+            PublicKey = <A as KeyEncapsulation>::PublicKey,
+            SecretKey = <A as KeyEncapsulation>::SecretKey,
+        >,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lists the names only, leaving the keys out of logs entirely.
+impl<A: KeyEncapsulation> fmt::Debug for Keystore<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Keystore")
+            .field("names", &self.entries.keys())
+            .finish()
+    }
+}
+
+/// Splits `len` bytes off the front of `input`.
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if input.len() < len {
+        return Err(QraiopError::SerializationError(format!(
+            "Keystore is truncated: needed {} more bytes, got {}",
+            len,
+            input.len()
+        )));
+    }
+    let (taken, rest) = input.split_at(len);
+    *input = rest;
+    Ok(taken)
+}
+
+fn take_u32(input: &mut &[u8]) -> Result<usize> {
+    let bytes = take(input, 4)?;
+    Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+}
+
+fn trailing_bytes(section: &str) -> QraiopError {
+    QraiopError::SerializationError(format!("Keystore {} has trailing bytes", section))
+}
+
+#[cfg(all(test, feature = "kyber"))]
+mod tests {
+    use super::*;
+    use crate::pqc::kyber::{MlKem512, MlKem768};
+
+    fn sample() -> Keystore<MlKem768> {
+        let mut keystore = Keystore::new();
+        for name in ["alice", "bob", "carol"] {
+            assert!(keystore
+                .add(name, KeyPair::<MlKem768>::generate().unwrap())
+                .is_none());
+        }
+        keystore
+    }
+
+    fn save(keystore: &Keystore<MlKem768>) -> Vec<u8> {
+        keystore
+            .save_with_params(b"hunter2", passphrase::test_params())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let keystore = sample();
+        let loaded = Keystore::<MlKem768>::load(b"hunter2", &save(&keystore)).unwrap();
+        assert_eq!(loaded.list().collect::<Vec<_>>(), ["alice", "bob", "carol"]);
+        for name in keystore.list() {
+            let (original, reloaded) = (keystore.get(name).unwrap(), loaded.get(name).unwrap());
+            assert_eq!(original.public(), reloaded.public());
+            assert_eq!(original.secret().as_bytes(), reloaded.secret().as_bytes());
+        }
+
+        // A key that went through the store still decapsulates
+        let bob = loaded.get("bob").unwrap();
+        let (ct, ss) = MlKem768::encapsulate(keystore.get("bob").unwrap().public()).unwrap();
+        assert_eq!(MlKem768::decapsulate(bob.secret(), &ct).unwrap(), ss);
+    }
+
+    #[test]
+    fn test_add_get_remove() {
+        let mut keystore = sample();
+        assert_eq!(keystore.len(), 3);
+        let replaced = keystore.add("bob", KeyPair::generate().unwrap()).unwrap();
+        assert_ne!(keystore.get("bob").unwrap().public(), replaced.public());
+        assert!(keystore.remove("alice").is_some());
+        assert!(keystore.remove("alice").is_none());
+        assert!(keystore.get("alice").is_none());
+        assert_eq!(keystore.list().collect::<Vec<_>>(), ["bob", "carol"]);
+        assert_eq!(
+            format!("{:?}", keystore),
+            r#"Keystore { names: ["bob", "carol"] }"#
+        );
+
+        let empty = Keystore::<MlKem768>::default();
+        let loaded = Keystore::<MlKem768>::load(b"hunter2", &save(&empty)).unwrap();
+        assert!(loaded.is_empty());
+    }
+
+    #[test]
+    fn test_load_rejects_bad_input() {
+        let saved = save(&sample());
+        let load = |data: &[u8]| Keystore::<MlKem768>::load(b"hunter2", data);
+        let is_serialization_error = |result: Result<Keystore<MlKem768>>| {
+            matches!(result, Err(QraiopError::SerializationError(_)))
+        };
+
+        let mut unknown_version = saved.clone();
+        unknown_version[0] = 2;
+        assert!(is_serialization_error(load(&unknown_version)));
+        assert!(is_serialization_error(load(&[])));
+        assert!(is_serialization_error(load(&saved[..saved.len() / 2])));
+        assert!(is_serialization_error(Keystore::<MlKem768>::load(
+            b"wrong", &saved
+        )));
+
+        // Renaming an entry in the readable manifest breaks the binding
+        let mut renamed = saved.clone();
+        let at = saved.windows(5).position(|w| w == b"alice").unwrap();
+        renamed[at] = b'A';
+        assert!(is_serialization_error(load(&renamed)));
+
+        // The keys carry their OID, so the store is not an ML-KEM-512 one
+        assert!(Keystore::<MlKem512>::load(b"hunter2", &saved).is_err());
+    }
+}
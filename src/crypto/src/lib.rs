@@ -1,17 +1,338 @@
 //! QRAIOP Quantum-Resistant Cryptographic Library
 //!
 //! This library provides production-ready implementations of NIST-approved
-//! post-quantum cryptographic algorithms including ML-KEM, ML-DSA, and SLH-DSA.
+//! post-quantum cryptographic algorithms including ML-KEM, ML-DSA, SLH-DSA and
+//! the stateful LMS scheme.
+//!
+//! # Feature flags
+//!
+//! - `std` (default): streaming signatures over [`std::io::Read`], the
+//!   benchmark helpers, [`init`] logging setup and the AVX2/NEON backends.
+//!   Without it the crate is `#![no_std]` and needs only `alloc` plus a
+//!   `getrandom` source; key generation, encapsulation, signing, sealing and
+//!   the key encodings all remain available. CI checks this with
+//!   `cargo build --no-default-features --features kyber,dilithium,sphincs`.
+//! - `rayon` (default, implies `std`): parallelizes
+//!   [`pqc::dilithium::MlDsa65::verify_batch`] and friends, and enables
+//!   [`pqc::generate_keypairs_parallel`].
+//! - `js`: required on `wasm32-unknown-unknown` (browsers and Node), where
+//!   it sources randomness for key generation and [`utils::secure_random`]
+//!   from `crypto.getRandomValues`. Build with
+//!   `cargo build --target wasm32-unknown-unknown --no-default-features --features js`
+//!   and a clang that can target wasm32 for the C backends.
+//! - `kyber`, `dilithium`, `sphincs` (all default): the ML-KEM, ML-DSA and
+//!   SLH-DSA families, each with its C backend. Disable the ones you don't
+//!   use to shrink the binary, e.g. for ML-KEM alone:
+//!   `default-features = false, features = ["std", "kyber"]`.
+//!   [`ALGORITHMS`] and [`info`] list only what is compiled in. The
+//!   [`hybrid`], [`seal`] and [`tls`] modules need `kyber`, and the hybrid
+//!   signature also needs `dilithium`. LMS is always available.
+//! - `legacy_kyber` (implies `kyber`): round-3 Kyber in
+//!   [`pqc::legacy_kyber`], only for talking to peers that predate FIPS 203.
+//! - `ffi` (implies `std` and `kyber`): the C ABI for ML-KEM-768 in [`ffi`].
+//! - `self_test` (implies `kyber` and `dilithium`): known-answer self-tests
+//!   in [`self_test`], run by [`init`].
+//! - `locked`: `mlock`s secret keys and shared secrets on Unix so they are
+//!   never written to swap.
+//! - `test_rng` (implies `std`): key generation, [`utils::secure_random`]
+//!   and most encapsulations draw from a fixed-seed ChaCha20 stream per
+//!   thread, so test runs are reproducible; see [`utils::reset_test_rng`].
+//!   Only for tests: every key is predictable, and the crate refuses to
+//!   build with it in release mode.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
+#[cfg(all(feature = "test_rng", not(debug_assertions)))]
+compile_error!(
+    "the `test_rng` feature makes every generated key predictable and is only for debug-build tests"
+);
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+use utils::key_sizes::{self, KeySizes};
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "kyber")]
+pub mod hybrid;
+pub mod keystore;
 pub mod pqc;
+#[cfg(feature = "kyber")]
+pub mod seal;
+#[cfg(feature = "self_test")]
+pub mod self_test;
+#[cfg(feature = "kyber")]
+pub mod tls;
 pub mod utils;
+pub mod wire;
 
 // Re-export main types publicly
-pub use pqc::{DigitalSignature, HashBasedSignature, KeyEncapsulation};
+#[cfg(feature = "kyber")]
+pub use pqc::kyber::KemAlgorithm;
+pub use pqc::{
+    kem_by_name, DigitalSignature, DynKem, HashBasedSignature, KeyEncapsulation, KeyPair,
+    SigningKeyPair,
+};
+// Secret key types implement `Zeroize` and export through `Zeroizing`, so
+// callers can wipe keys early without depending on `zeroize` themselves.
+pub use zeroize::{Zeroize, Zeroizing};
 
 /// Library version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Names of the algorithms compiled into this build, in the order of
+/// [`SupportedAlgorithm::ALL`]. With every family feature enabled, as by
+/// default, this is all nine.
+pub const ALGORITHMS: &[&str] = &ENABLED_ALGORITHMS;
+
+const ENABLED_ALGORITHMS: [&str; SupportedAlgorithm::enabled_count()] = {
+    let mut names = [""; SupportedAlgorithm::enabled_count()];
+    let (mut i, mut j) = (0, 0);
+    while i < SupportedAlgorithm::ALL.len() {
+        if SupportedAlgorithm::ALL[i].is_enabled() {
+            names[j] = ALGORITHM_NAMES[i];
+            j += 1;
+        }
+        i += 1;
+    }
+    names
+};
+
+/// Every algorithm name, enabled or not, indexed by [`SupportedAlgorithm`].
+const ALGORITHM_NAMES: [&str; 9] = [
+    "ML-KEM-512",
+    "ML-KEM-768",
+    "ML-KEM-1024",
+    "ML-DSA-44",
+    "ML-DSA-65",
+    "ML-DSA-87",
+    "SLH-DSA-128s",
+    "SLH-DSA-192s",
+    "SLH-DSA-256s",
+];
+
+/// What an algorithm is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum AlgorithmKind {
+    /// Key encapsulation
+    Kem,
+    /// Digital signatures
+    Signature,
+    /// Digital signatures resting only on the security of a hash function
+    HashBasedSignature,
+}
+
+/// Any algorithm this library knows, parsed from its name. Variants exist
+/// whether or not their family feature is enabled, so encodings stay
+/// stable across builds; see [`SupportedAlgorithm::is_enabled`]. For
+/// operating a KEM picked this way, see `SupportedAlgorithm::kem`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum SupportedAlgorithm {
+    MlKem512,
+    MlKem768,
+    MlKem1024,
+    MlDsa44,
+    MlDsa65,
+    MlDsa87,
+    SlhDsa128s,
+    SlhDsa192s,
+    SlhDsa256s,
+}
+
+impl SupportedAlgorithm {
+    /// Every variant, including ones not enabled in this build.
+    pub const ALL: [SupportedAlgorithm; 9] = [
+        SupportedAlgorithm::MlKem512,
+        SupportedAlgorithm::MlKem768,
+        SupportedAlgorithm::MlKem1024,
+        SupportedAlgorithm::MlDsa44,
+        SupportedAlgorithm::MlDsa65,
+        SupportedAlgorithm::MlDsa87,
+        SupportedAlgorithm::SlhDsa128s,
+        SupportedAlgorithm::SlhDsa192s,
+        SupportedAlgorithm::SlhDsa256s,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        ALGORITHM_NAMES[*self as usize]
+    }
+
+    /// Whether the feature for this algorithm's family (`kyber`,
+    /// `dilithium` or `sphincs`) is enabled.
+    pub const fn is_enabled(&self) -> bool {
+        match self {
+            SupportedAlgorithm::MlKem512
+            | SupportedAlgorithm::MlKem768
+            | SupportedAlgorithm::MlKem1024 => cfg!(feature = "kyber"),
+            SupportedAlgorithm::MlDsa44
+            | SupportedAlgorithm::MlDsa65
+            | SupportedAlgorithm::MlDsa87 => cfg!(feature = "dilithium"),
+            SupportedAlgorithm::SlhDsa128s
+            | SupportedAlgorithm::SlhDsa192s
+            | SupportedAlgorithm::SlhDsa256s => cfg!(feature = "sphincs"),
+        }
+    }
+
+    const fn enabled_count() -> usize {
+        let (mut i, mut count) = (0, 0);
+        while i < Self::ALL.len() {
+            if Self::ALL[i].is_enabled() {
+                count += 1;
+            }
+            i += 1;
+        }
+        count
+    }
+
+    pub fn kind(&self) -> AlgorithmKind {
+        match self {
+            SupportedAlgorithm::MlKem512
+            | SupportedAlgorithm::MlKem768
+            | SupportedAlgorithm::MlKem1024 => AlgorithmKind::Kem,
+            SupportedAlgorithm::MlDsa44
+            | SupportedAlgorithm::MlDsa65
+            | SupportedAlgorithm::MlDsa87 => AlgorithmKind::Signature,
+            SupportedAlgorithm::SlhDsa128s
+            | SupportedAlgorithm::SlhDsa192s
+            | SupportedAlgorithm::SlhDsa256s => AlgorithmKind::HashBasedSignature,
+        }
+    }
+
+    // Spelled out rather than asked of the signature types so it works
+    // with their families disabled
+    pub fn security_level(&self) -> SecurityLevel {
+        match self {
+            SupportedAlgorithm::MlKem512 => SecurityLevel::Level1,
+            SupportedAlgorithm::MlKem768 => SecurityLevel::Level3,
+            SupportedAlgorithm::MlKem1024 => SecurityLevel::Level5,
+            SupportedAlgorithm::MlDsa44 => SecurityLevel::Level2,
+            SupportedAlgorithm::MlDsa65 => SecurityLevel::Level3,
+            SupportedAlgorithm::MlDsa87 => SecurityLevel::Level5,
+            SupportedAlgorithm::SlhDsa128s => SecurityLevel::Level1,
+            SupportedAlgorithm::SlhDsa192s => SecurityLevel::Level3,
+            SupportedAlgorithm::SlhDsa256s => SecurityLevel::Level5,
+        }
+    }
+
+    /// Encoded key, ciphertext and signature sizes of this parameter set.
+    pub fn sizes(&self) -> KeySizes {
+        key_sizes::for_algorithm(self.name()).expect("every algorithm has known sizes")
+    }
+
+    /// The runtime-dispatch KEM for this algorithm, or `None` for
+    /// signature schemes.
+    #[cfg(feature = "kyber")]
+    pub fn kem(&self) -> Option<KemAlgorithm> {
+        match self {
+            SupportedAlgorithm::MlKem512 => Some(KemAlgorithm::MlKem512),
+            SupportedAlgorithm::MlKem768 => Some(KemAlgorithm::MlKem768),
+            SupportedAlgorithm::MlKem1024 => Some(KemAlgorithm::MlKem1024),
+            _ => None,
+        }
+    }
+}
+
+/// Everything [`algorithm_metadata`] reports about one algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlgorithmInfo {
+    pub algorithm: SupportedAlgorithm,
+    pub name: &'static str,
+    pub kind: AlgorithmKind,
+    pub security_level: SecurityLevel,
+    pub sizes: KeySizes,
+}
+
+/// Describes each algorithm compiled into this build, in the order of
+/// [`ALGORITHMS`], so callers can list them without a table of their own.
+pub fn algorithm_metadata() -> impl Iterator<Item = AlgorithmInfo> {
+    SupportedAlgorithm::ALL
+        .into_iter()
+        .filter(SupportedAlgorithm::is_enabled)
+        .map(|algorithm| AlgorithmInfo {
+            algorithm,
+            name: algorithm.name(),
+            kind: algorithm.kind(),
+            security_level: algorithm.security_level(),
+            sizes: algorithm.sizes(),
+        })
+}
+
+impl fmt::Display for SupportedAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SupportedAlgorithm {
+    type Err = QraiopError;
+
+    /// Fails with [`QraiopError::UnsupportedAlgorithm`] for unknown names and
+    /// for algorithms whose family feature is disabled.
+    fn from_str(s: &str) -> Result<Self> {
+        SupportedAlgorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.name() == s && algorithm.is_enabled())
+            .ok_or_else(|| QraiopError::UnsupportedAlgorithm(s.to_string()))
+    }
+}
+
+impl TryFrom<&str> for SupportedAlgorithm {
+    type Error = QraiopError;
+
+    fn try_from(s: &str) -> Result<Self> {
+        s.parse()
+    }
+}
+
+/// NIST post-quantum security category. Odd categories are defined by key
+/// search on AES, even ones by collision search on SHA-2, and each is at
+/// least as strong as the one below it, so levels compare in that order.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum SecurityLevel {
+    /// At least as hard to break as AES-128
+    Level1,
+    /// At least as hard to find a collision in as SHA-256
+    Level2,
+    /// At least as hard to break as AES-192
+    Level3,
+    /// At least as hard to find a collision in as SHA-384
+    Level4,
+    /// At least as hard to break as AES-256
+    Level5,
+}
+
+impl SecurityLevel {
+    /// Maps a NIST category number (1 to 5) to its level.
+    pub fn from_category(category: u8) -> Option<Self> {
+        match category {
+            1 => Some(Self::Level1),
+            2 => Some(Self::Level2),
+            3 => Some(Self::Level3),
+            4 => Some(Self::Level4),
+            5 => Some(Self::Level5),
+            _ => None,
+        }
+    }
+
+    /// The NIST category number of this level.
+    pub fn category(self) -> u8 {
+        match self {
+            Self::Level1 => 1,
+            Self::Level2 => 2,
+            Self::Level3 => 3,
+            Self::Level4 => 4,
+            Self::Level5 => 5,
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum QraiopError {
     #[error("Cryptographic operation failed: {0}")]
@@ -22,30 +343,207 @@ pub enum QraiopError {
     UnsupportedAlgorithm(String),
     #[error("Signature verification failed")]
     SignatureVerificationFailed,
+    #[error("Key generation failed: {0}")]
+    KeyGenerationFailed(String),
     #[error("Key encapsulation failed: {0}")]
     EncapsulationFailed(String),
+    /// Decapsulation of a well-formed ciphertext failed. Only X25519 does
+    /// that, alone or in a hybrid KEM, for a low-order point; ML-KEM and
+    /// Kyber implicitly reject instead, and a ciphertext of the wrong length
+    /// is [`QraiopError::InvalidCiphertext`].
+    #[error("Key decapsulation failed: {0}")]
+    DecapsulationFailed(String),
+    #[error("Invalid ciphertext: {0}")]
+    InvalidCiphertext(String),
+    #[error("Invalid signature encoding: {0}")]
+    InvalidSignature(String),
     #[error("Serialization error: {0}")]
     SerializationError(String),
+    #[error("Buffer too small: need {needed} bytes, got {got}")]
+    BufferTooSmall { needed: usize, got: usize },
+    #[error("Signing key exhausted: every one-time signature has been used")]
+    KeyExhausted,
+    #[cfg(feature = "kyber")]
+    #[error(transparent)]
+    Hybrid(#[from] hybrid::HybridError),
+    #[cfg(feature = "std")]
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 }
 
-pub type Result<T> = std::result::Result<T, QraiopError>;
+impl QraiopError {
+    /// Prefixes the message with the algorithm that failed, e.g.
+    /// `"ML-KEM-512: ..."`, for callers handling several parameter sets.
+    ///
+    /// Variants without a message, and messages that already start with
+    /// `algorithm`, are returned unchanged, so the variant can still be
+    /// matched on.
+    pub fn with_algorithm(self, algorithm: &str) -> Self {
+        let prefix = |message: String| {
+            if message.starts_with(algorithm) {
+                message
+            } else {
+                format!("{}: {}", algorithm, message)
+            }
+        };
+        match self {
+            Self::CryptoError(m) => Self::CryptoError(prefix(m)),
+            Self::InvalidKey(m) => Self::InvalidKey(prefix(m)),
+            Self::UnsupportedAlgorithm(m) => Self::UnsupportedAlgorithm(prefix(m)),
+            Self::KeyGenerationFailed(m) => Self::KeyGenerationFailed(prefix(m)),
+            Self::EncapsulationFailed(m) => Self::EncapsulationFailed(prefix(m)),
+            Self::DecapsulationFailed(m) => Self::DecapsulationFailed(prefix(m)),
+            Self::InvalidCiphertext(m) => Self::InvalidCiphertext(prefix(m)),
+            Self::InvalidSignature(m) => Self::InvalidSignature(prefix(m)),
+            Self::SerializationError(m) => Self::SerializationError(prefix(m)),
+            other => other,
+        }
+    }
+}
+
+// Decoding failures in the formats the crate reads all surface as
+// `SerializationError`, whatever the format, with the underlying error's
+// message kept.
+impl From<hex::FromHexError> for QraiopError {
+    fn from(e: hex::FromHexError) -> Self {
+        QraiopError::SerializationError(format!("Invalid hex: {}", e))
+    }
+}
+
+impl From<base64::DecodeError> for QraiopError {
+    fn from(e: base64::DecodeError) -> Self {
+        QraiopError::SerializationError(format!("Invalid base64: {}", e))
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<bincode::Error> for QraiopError {
+    fn from(e: bincode::Error) -> Self {
+        QraiopError::SerializationError(format!("bincode: {}", e))
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<serde_json::Error> for QraiopError {
+    fn from(e: serde_json::Error) -> Self {
+        QraiopError::SerializationError(format!("JSON: {}", e))
+    }
+}
 
+pub type Result<T> = core::result::Result<T, QraiopError>;
+
+/// [`init_with_logger`] with `install_logger` set, for binaries that leave
+/// logging to this crate.
 pub fn init() -> Result<()> {
-    env_logger::init();
+    init_with_logger(true)
+}
+
+/// Prepares the library: with the `self_test` feature this runs
+/// [`self_test::run_self_tests`], failing if they do. With `std` as well
+/// they run only on the first call, through
+/// [`self_test::ensure_self_tested`].
+///
+/// With `install_logger` and the `std` feature this also installs the
+/// `env_logger` backend, unless a `log` backend is already set, which is
+/// left alone. Applications that configure logging themselves should pass
+/// `false`; the crate only ever logs through the `log` macros. Calling
+/// this more than once is harmless.
+pub fn init_with_logger(install_logger: bool) -> Result<()> {
+    #[cfg(feature = "std")]
+    if install_logger {
+        // Fails only when a logger is already installed
+        let _ = env_logger::try_init();
+    }
+    #[cfg(not(feature = "std"))]
+    let _ = install_logger;
+    #[cfg(all(feature = "self_test", feature = "std"))]
+    self_test::ensure_self_tested()?;
+    #[cfg(all(feature = "self_test", not(feature = "std")))]
+    self_test::run_self_tests()?;
     Ok(())
 }
 
+/// Every cargo feature of this crate and whether it is compiled in.
+const FEATURES: &[(&str, bool)] = &[
+    ("std", cfg!(feature = "std")),
+    ("rayon", cfg!(feature = "rayon")),
+    ("js", cfg!(feature = "js")),
+    ("kyber", cfg!(feature = "kyber")),
+    ("dilithium", cfg!(feature = "dilithium")),
+    ("sphincs", cfg!(feature = "sphincs")),
+    ("legacy_kyber", cfg!(feature = "legacy_kyber")),
+    ("ffi", cfg!(feature = "ffi")),
+    ("self_test", cfg!(feature = "self_test")),
+    ("locked", cfg!(feature = "locked")),
+    ("timing_tests", cfg!(feature = "timing_tests")),
+    ("serde_secret", cfg!(feature = "serde_secret")),
+    ("pqcrypto_interop", cfg!(feature = "pqcrypto_interop")),
+    ("test_rng", cfg!(feature = "test_rng")),
+];
+
+/// Backend crates and their versions as recorded by `build.rs`, paired with
+/// whether they are compiled in.
+const BACKENDS: &[(&str, Option<&str>, bool)] = &[
+    (
+        "pqcrypto-mlkem",
+        option_env!("QRAIOP_BACKEND_VERSION_PQCRYPTO_MLKEM"),
+        cfg!(feature = "kyber"),
+    ),
+    (
+        "pqcrypto-mldsa",
+        option_env!("QRAIOP_BACKEND_VERSION_PQCRYPTO_MLDSA"),
+        cfg!(feature = "dilithium"),
+    ),
+    (
+        "pqcrypto-sphincsplus",
+        option_env!("QRAIOP_BACKEND_VERSION_PQCRYPTO_SPHINCSPLUS"),
+        cfg!(feature = "sphincs"),
+    ),
+    (
+        "hbs-lms",
+        option_env!("QRAIOP_BACKEND_VERSION_HBS_LMS"),
+        true,
+    ),
+];
+
+/// Describes this build, e.g. for attaching to a support ticket.
 pub fn info() -> LibraryInfo {
     LibraryInfo {
         version: VERSION.to_string(),
+        algorithms: ALGORITHMS.iter().map(|name| name.to_string()).collect(),
+        features: FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| name.to_string())
+            .collect(),
+        backends: BACKENDS
+            .iter()
+            .filter(|(_, _, enabled)| *enabled)
+            .map(|(name, version, _)| BackendInfo {
+                name: name.to_string(),
+                version: version.unwrap_or("unknown").to_string(),
+            })
+            .collect(),
     }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct LibraryInfo {
     pub version: String,
+    /// Same as [`ALGORITHMS`]
+    pub algorithms: Vec<String>,
+    /// Cargo features of this crate that are enabled
+    pub features: Vec<String>,
+    /// Algorithm backend crates compiled in, with their resolved versions
+    pub backends: Vec<BackendInfo>,
+}
+
+/// A backend crate and its version from the build's `Cargo.lock`, or
+/// `"unknown"` if no lock file was found.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BackendInfo {
+    pub name: String,
+    pub version: String,
 }
 
 #[cfg(test)]
@@ -55,11 +553,182 @@ mod tests {
     #[test]
     fn test_library_init() {
         assert!(init().is_ok());
+        // Neither a second init nor one alongside the application's own
+        // logger panics
+        assert!(init().is_ok());
+        assert!(init_with_logger(false).is_ok());
+        assert!(init_with_logger(false).is_ok());
+    }
+
+    #[test]
+    fn test_every_algorithm_name_parses() {
+        let enabled = SupportedAlgorithm::ALL
+            .into_iter()
+            .filter(SupportedAlgorithm::is_enabled);
+        assert!(enabled
+            .map(|algorithm| algorithm.name())
+            .eq(ALGORITHMS.iter().copied()));
+        for name in ALGORITHMS {
+            let algorithm = SupportedAlgorithm::try_from(*name).unwrap();
+            assert_eq!(algorithm.name(), *name);
+            assert_eq!(algorithm.to_string(), *name);
+        }
+        assert!(matches!(
+            "ML-KEM-2048".parse::<SupportedAlgorithm>(),
+            Err(QraiopError::UnsupportedAlgorithm(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "kyber")]
+    fn test_supported_algorithm_metadata() {
+        let kem = SupportedAlgorithm::MlKem768;
+        assert_eq!(kem.kind(), AlgorithmKind::Kem);
+        assert_eq!(kem.security_level(), SecurityLevel::Level3);
+        assert_eq!(kem.kem(), Some(KemAlgorithm::MlKem768));
+
+        let signature = SupportedAlgorithm::MlDsa44;
+        assert_eq!(signature.kind(), AlgorithmKind::Signature);
+        assert_eq!(signature.security_level(), SecurityLevel::Level2);
+        assert_eq!(signature.kem(), None);
+
+        for algorithm in SupportedAlgorithm::ALL {
+            if let Some(kem) = algorithm.kem() {
+                assert_eq!(kem.name(), algorithm.name());
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "dilithium", feature = "sphincs"))]
+    fn test_security_levels_match_signature_types() {
+        use pqc::dilithium::{MlDsa44, MlDsa65, MlDsa87};
+        use pqc::sphincs::{SlhDsa128s, SlhDsa192s, SlhDsa256s};
+
+        let levels = [
+            MlDsa44::security_level(),
+            MlDsa65::security_level(),
+            MlDsa87::security_level(),
+            SlhDsa128s::security_level(),
+            SlhDsa192s::security_level(),
+            SlhDsa256s::security_level(),
+        ];
+        for (algorithm, level) in SupportedAlgorithm::ALL[3..].iter().zip(levels) {
+            assert_eq!(algorithm.security_level(), level, "{}", algorithm);
+        }
+    }
+
+    #[test]
+    fn test_disabled_algorithms_are_rejected() {
+        for algorithm in SupportedAlgorithm::ALL {
+            assert_eq!(
+                algorithm.name().parse::<SupportedAlgorithm>().is_ok(),
+                algorithm.is_enabled()
+            );
+        }
+    }
+
+    #[test]
+    fn test_security_level_categories() {
+        for category in 1..=5 {
+            let level = SecurityLevel::from_category(category).unwrap();
+            assert_eq!(level.category(), category);
+        }
+        assert_eq!(SecurityLevel::from_category(0), None);
+        assert_eq!(SecurityLevel::from_category(6), None);
+    }
+
+    #[test]
+    fn test_security_level_ordering() {
+        assert!(SecurityLevel::Level1 < SecurityLevel::Level2);
+        assert!(SecurityLevel::Level2 < SecurityLevel::Level3);
+        assert!(SecurityLevel::Level5 >= SecurityLevel::Level3);
+        assert_eq!(
+            [
+                SecurityLevel::Level5,
+                SecurityLevel::Level1,
+                SecurityLevel::Level2
+            ]
+            .into_iter()
+            .max(),
+            Some(SecurityLevel::Level5)
+        );
+    }
+
+    #[test]
+    fn test_algorithm_metadata() {
+        let infos: Vec<AlgorithmInfo> = algorithm_metadata().collect();
+        let names: Vec<&str> = infos.iter().map(|info| info.name).collect();
+        assert_eq!(names, ALGORITHMS);
+
+        for info in infos {
+            let category = match info.name {
+                "ML-KEM-512" | "SLH-DSA-128s" => 1,
+                "ML-DSA-44" => 2,
+                "ML-KEM-768" | "ML-DSA-65" | "SLH-DSA-192s" => 3,
+                "ML-KEM-1024" | "ML-DSA-87" | "SLH-DSA-256s" => 5,
+                other => panic!("unexpected algorithm {}", other),
+            };
+            assert_eq!(info.security_level.category(), category);
+            match (info.kind, info.sizes) {
+                (AlgorithmKind::Kem, KeySizes::Kem(_)) => {
+                    assert!(info.name.starts_with("ML-KEM"))
+                }
+                (AlgorithmKind::Signature, KeySizes::Signature(_)) => {
+                    assert!(info.name.starts_with("ML-DSA"))
+                }
+                (AlgorithmKind::HashBasedSignature, KeySizes::Signature(_)) => {
+                    assert!(info.name.starts_with("SLH-DSA"))
+                }
+                _ => panic!("{} has sizes of the wrong kind", info.name),
+            }
+        }
     }
 
     #[test]
     fn test_library_info() {
         let info = info();
         assert_eq!(info.version, VERSION);
+        assert_eq!(info.algorithms, ALGORITHMS);
+        assert_eq!(
+            info.features.iter().any(|f| f == "kyber"),
+            cfg!(feature = "kyber")
+        );
+        assert_eq!(
+            info.features.iter().any(|f| f == "std"),
+            cfg!(feature = "std")
+        );
+        assert!(!info.features.iter().any(|f| f == "no_such_feature"));
+        let names: Vec<_> = info.backends.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names.contains(&"pqcrypto-mlkem"), cfg!(feature = "kyber"));
+        assert_eq!(
+            names.contains(&"pqcrypto-sphincsplus"),
+            cfg!(feature = "sphincs")
+        );
+        assert!(info
+            .backends
+            .iter()
+            .all(|b| b.version.split('.').count() == 3));
+    }
+
+    #[test]
+    fn test_error_conversions() {
+        let err = utils::hex_to_bytes("0g").unwrap_err();
+        assert!(
+            matches!(err, QraiopError::SerializationError(ref m)
+                if m == "Invalid hex: Invalid character 'g' at position 1"),
+            "{:?}",
+            err
+        );
+
+        use base64::Engine;
+        let err = QraiopError::from(
+            base64::engine::general_purpose::STANDARD
+                .decode("a")
+                .unwrap_err(),
+        );
+        assert!(
+            matches!(err, QraiopError::SerializationError(ref m) if m.starts_with("Invalid base64: "))
+        );
     }
 }
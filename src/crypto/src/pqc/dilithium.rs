@@ -1,11 +1,1508 @@
 //! ML-DSA (Dilithium) implementation
 //!
-//! Based on CRYSTALS-Dilithium, standardized as FIPS 204.
+//! FIPS 204 ML-DSA, on the PQClean implementation through `pqcrypto-mldsa`.
 
 use crate::pqc::DigitalSignature;
+use crate::utils::key_sizes::{ml_dsa_44, ml_dsa_65, ml_dsa_87};
+use crate::utils::secret::Secret;
+use crate::utils::{self, passphrase};
 use crate::{QraiopError, Result, SecurityLevel};
-use pqcrypto_dilithium::*;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use pqcrypto_mldsa::{mldsa44, mldsa65, mldsa87};
+use pqcrypto_traits::sign::{DetachedSignature as _, PublicKey as _, SecretKey as _};
+use rand::{CryptoRng, RngCore};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use zeroize::{Zeroize, ZeroizeOnDrop};
+#[cfg(feature = "std")]
+use sha2::{Digest, Sha512};
+#[cfg(feature = "std")]
+use std::io::{self, Read};
+use subtle::Choice;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
-// Signature implementation...
+mod keygen;
+mod public_key;
+mod signature;
+
+const PUBLIC_KEY_SIZES: [usize; 3] = [
+    ml_dsa_44::PUBLIC_KEY_SIZE,
+    ml_dsa_65::PUBLIC_KEY_SIZE,
+    ml_dsa_87::PUBLIC_KEY_SIZE,
+];
+const SECRET_KEY_SIZES: [usize; 3] = [
+    ml_dsa_44::SECRET_KEY_SIZE,
+    ml_dsa_65::SECRET_KEY_SIZE,
+    ml_dsa_87::SECRET_KEY_SIZE,
+];
+
+const SIGNATURE_SIZES: [usize; 3] = [
+    ml_dsa_44::SIGNATURE_SIZE,
+    ml_dsa_65::SIGNATURE_SIZE,
+    ml_dsa_87::SIGNATURE_SIZE,
+];
+
+fn check_length(
+    what: &str,
+    actual: usize,
+    valid: &[usize],
+    error: fn(String) -> QraiopError,
+) -> Result<()> {
+    if valid.contains(&actual) {
+        Ok(())
+    } else {
+        Err(error(format!(
+            "{} must be one of {:?} bytes, got {}",
+            what, valid, actual
+        )))
+    }
+}
+
+/// ML-DSA verification (public) key.
+///
+/// Implements `Hash`, `Eq` and `Ord` over the key bytes so keys can index
+/// maps and sorted sets; ordering is lexicographic by bytes, and comparing
+/// public material leaks nothing.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "Vec<u8>")]
+pub struct PublicKey(Vec<u8>);
+
+impl PublicKey {
+    /// Parses a raw verification key of any ML-DSA parameter set.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_length(
+            "ML-DSA public key",
+            bytes.len(),
+            &PUBLIC_KEY_SIZES,
+            QraiopError::InvalidKey,
+        )?;
+        Ok(Self(bytes.to_vec()))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Short fingerprint for display and pinning: the first 8 bytes of
+    /// SHA3-256 over the key, as colon-separated hex.
+    pub fn fingerprint(&self) -> String {
+        utils::fingerprint(&self.0)
+    }
+
+    /// Full SHA3-256 digest of the key.
+    pub fn fingerprint_full(&self) -> [u8; 32] {
+        utils::fingerprint_full(&self.0)
+    }
+
+    /// Parameter set, inferred from the key length.
+    fn algorithm_name(&self) -> &'static str {
+        match self.0.len() {
+            ml_dsa_44::PUBLIC_KEY_SIZE => "ML-DSA-44",
+            ml_dsa_65::PUBLIC_KEY_SIZE => "ML-DSA-65",
+            ml_dsa_87::PUBLIC_KEY_SIZE => "ML-DSA-87",
+            _ => "ML-DSA",
+        }
+    }
+}
+
+impl TryFrom<Vec<u8>> for PublicKey {
+    type Error = QraiopError;
+
+    /// Like [`PublicKey::from_bytes`], but takes ownership instead of copying.
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        check_length(
+            "ML-DSA public key",
+            bytes.len(),
+            &PUBLIC_KEY_SIZES,
+            QraiopError::InvalidKey,
+        )?;
+        Ok(Self(bytes))
+    }
+}
+
+impl From<PublicKey> for Vec<u8> {
+    fn from(value: PublicKey) -> Self {
+        value.0
+    }
+}
+
+/// Algorithm and short fingerprint, e.g. `ML-DSA-65 pk:3f:a0:1c:77:09:be:42:d5`.
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} pk:{}", self.algorithm_name(), self.fingerprint())
+    }
+}
+
+// Short like `Display` rather than a dump of a multi-kilobyte key.
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PublicKey({})", self)
+    }
+}
+
+/// ML-DSA signing (secret) key, wiped from memory on drop.
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SecretKey(Secret);
+
+impl SecretKey {
+    /// Parses a raw (expanded) signing key of any ML-DSA parameter set.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_length(
+            "ML-DSA secret key",
+            bytes.len(),
+            &SECRET_KEY_SIZES,
+            QraiopError::InvalidKey,
+        )?;
+        Ok(Self(bytes.into()))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.expose_secret()
+    }
+
+    /// Copies the key out into a buffer that is wiped when dropped, unlike
+    /// a copy of [`Self::as_bytes`].
+    pub fn to_zeroizing_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(self.0.expose_secret().to_vec())
+    }
+
+    /// Loads a hex- or base64-encoded signing key from the environment
+    /// variable `name`; see [`utils::secret_from_env`] for the format and
+    /// the caveats of keeping keys there. Bad data fails with
+    /// [`QraiopError::InvalidKey`].
+    #[cfg(feature = "std")]
+    pub fn from_env(name: &str) -> Result<Self> {
+        Self::from_bytes(&utils::secret_from_env(name)?)
+    }
+
+    /// Recomputes the matching public key, so only the secret key needs to
+    /// be stored. The expanded key holds a hash of the public key, which the
+    /// result is checked against, so damage to the parts the public key is
+    /// computed from fails with [`QraiopError::InvalidKey`].
+    pub fn public_key(&self) -> Result<PublicKey> {
+        public_key::derive(self.0.expose_secret()).map(PublicKey)
+    }
+
+    /// Encrypts the key under `passphrase` for storage on disk, in the same
+    /// Argon2id and AES-256-GCM format as ML-KEM keys; see
+    /// [`crate::utils::passphrase`].
+    pub fn to_encrypted_bytes(&self, passphrase: &[u8]) -> Result<Vec<u8>> {
+        passphrase::encrypt(passphrase, self.0.expose_secret())
+    }
+
+    /// Decrypts a key stored with [`Self::to_encrypted_bytes`]. A wrong
+    /// passphrase or corrupted data yields `QraiopError::SerializationError`.
+    pub fn from_encrypted_bytes(data: &[u8], passphrase: &[u8]) -> Result<Self> {
+        let bytes = passphrase::decrypt(passphrase, data)?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+/// Takes ownership of the key bytes, wiping them if the length is wrong.
+/// Unlike the public types there is no `From<SecretKey> for Vec<u8>`.
+impl TryFrom<Vec<u8>> for SecretKey {
+    type Error = QraiopError;
+
+    fn try_from(mut bytes: Vec<u8>) -> Result<Self> {
+        if let Err(error) = check_length(
+            "ML-DSA secret key",
+            bytes.len(),
+            &SECRET_KEY_SIZES,
+            QraiopError::InvalidKey,
+        ) {
+            bytes.zeroize();
+            return Err(error);
+        }
+        Ok(Self(Secret::new(bytes)))
+    }
+}
+
+impl AsRef<[u8]> for SecretKey {
+    fn as_ref(&self) -> &[u8] {
+        self.0.expose_secret()
+    }
+}
+
+/// Detached ML-DSA signature. Equality and ordering are over the encoded
+/// bytes, lexicographically, for keeping signatures in sorted sets.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "Vec<u8>")]
+pub struct Signature(Vec<u8>);
+
+impl Signature {
+    /// Parses a raw signature of any ML-DSA parameter set, e.g. off the
+    /// wire. A length that matches none of them is
+    /// [`QraiopError::InvalidSignature`]; whether the signature fits the
+    /// signer's parameter set is left to verification.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_length(
+            "ML-DSA signature",
+            bytes.len(),
+            &SIGNATURE_SIZES,
+            QraiopError::InvalidSignature,
+        )?;
+        Ok(Self(bytes.to_vec()))
+    }
+
+    /// Like [`Signature::from_bytes`], but also rejects encodings that are
+    /// not canonical, so that a signature has exactly one byte form. Use
+    /// this where signature bytes are hashed or compared as identifiers
+    /// before, or instead of, being verified.
+    ///
+    /// Only the hint part of a signature can be written more than one way.
+    /// Verification already rejects such signatures, so this changes
+    /// nothing for code that verifies first.
+    pub fn from_bytes_strict(bytes: &[u8]) -> Result<Self> {
+        let signature = Self::from_bytes(bytes)?;
+        if !signature::is_canonical(bytes) {
+            return Err(QraiopError::InvalidSignature(
+                "ML-DSA signature hint is not in canonical form".to_string(),
+            ));
+        }
+        Ok(signature)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl TryFrom<Vec<u8>> for Signature {
+    type Error = QraiopError;
+
+    /// Like [`Signature::from_bytes`], but takes ownership instead of copying.
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        check_length(
+            "ML-DSA signature",
+            bytes.len(),
+            &SIGNATURE_SIZES,
+            QraiopError::InvalidSignature,
+        )?;
+        Ok(Self(bytes))
+    }
+}
+
+impl TryFrom<&[u8]> for Signature {
+    type Error = QraiopError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self> {
+        Self::from_bytes(bytes)
+    }
+}
+
+impl From<Signature> for Vec<u8> {
+    fn from(value: Signature) -> Self {
+        value.0
+    }
+}
+
+/// Verification-only ML-DSA key, for services that check signatures and
+/// must never be able to produce them.
+///
+/// It wraps just a [`PublicKey`], so there is no way to reach signing
+/// material through it, and it picks the parameter set from the key length
+/// when verifying. It serializes exactly like the wrapped key.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct VerifyingKey(PublicKey);
+
+impl VerifyingKey {
+    /// Recomputes the verification key from a signing key, see
+    /// [`SecretKey::public_key`].
+    pub fn from_secret_key(secret_key: &SecretKey) -> Result<Self> {
+        secret_key.public_key().map(Self)
+    }
+
+    /// Parses a verification key exported with [`Self::as_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        PublicKey::from_bytes(bytes).map(Self)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+
+    pub fn public_key(&self) -> &PublicKey {
+        &self.0
+    }
+
+    /// Verifies `signature` over `message` under the key's parameter set,
+    /// with the same results as [`DigitalSignature::verify`].
+    #[must_use = "verification result must be checked"]
+    pub fn verify(&self, message: &[u8], signature: &Signature) -> Result<bool> {
+        verify_any(&self.0, message, signature)
+    }
+
+    /// Like [`Self::verify`], but an invalid signature fails with
+    /// `QraiopError::SignatureVerificationFailed`.
+    pub fn verify_strict(&self, message: &[u8], signature: &Signature) -> Result<()> {
+        if self.verify(message, signature)? {
+            Ok(())
+        } else {
+            Err(QraiopError::SignatureVerificationFailed)
+        }
+    }
+}
+
+/// Verifies under the parameter set matching the length of `public_key`.
+fn verify_any(public_key: &PublicKey, message: &[u8], signature: &Signature) -> Result<bool> {
+    match public_key.as_bytes().len() {
+        ml_dsa_44::PUBLIC_KEY_SIZE => MlDsa44::verify(public_key, message, signature),
+        ml_dsa_65::PUBLIC_KEY_SIZE => MlDsa65::verify(public_key, message, signature),
+        _ => MlDsa87::verify(public_key, message, signature),
+    }
+}
+
+/// Splits the public half off a keypair, e.g.
+/// `let verifying_key = VerifyingKey::from(pk);`.
+impl From<PublicKey> for VerifyingKey {
+    fn from(public_key: PublicKey) -> Self {
+        Self(public_key)
+    }
+}
+
+impl fmt::Display for VerifyingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Debug for VerifyingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "VerifyingKey({})", self.0)
+    }
+}
+
+/// Signatures by several signers over the same message, for approvals that
+/// must all be present.
+///
+/// Signers may use different ML-DSA parameter sets. Each signer appears at
+/// most once: adding a signature for a key already in the set replaces the
+/// old one. The set serializes as its list of key and signature bytes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureSet {
+    signatures: Vec<(PublicKey, Signature)>,
+}
+
+impl SignatureSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `signature` by the holder of `public_key`, replacing any
+    /// earlier signature by the same key.
+    pub fn add(&mut self, public_key: PublicKey, signature: Signature) {
+        match self.signatures.iter_mut().find(|(pk, _)| *pk == public_key) {
+            Some(entry) => entry.1 = signature,
+            None => self.signatures.push((public_key, signature)),
+        }
+    }
+
+    /// Number of signers in the set.
+    pub fn len(&self) -> usize {
+        self.signatures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(PublicKey, Signature)> {
+        self.signatures.iter()
+    }
+
+    /// Whether every signature in the set is valid over `message`.
+    ///
+    /// An empty set proves nothing and returns `false`, as does a set
+    /// holding one key twice, which only a deserialized set can. Checking
+    /// stops at the first invalid signature; a malformed key is an error.
+    #[must_use = "verification result must be checked"]
+    pub fn verify_all(&self, message: &[u8]) -> Result<bool> {
+        let mut keys: Vec<&PublicKey> = self.signatures.iter().map(|(pk, _)| pk).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        if keys.is_empty() || keys.len() != self.signatures.len() {
+            return Ok(false);
+        }
+        for (public_key, signature) in &self.signatures {
+            if !verify_any(public_key, message, signature)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Domain separator for the hedged signing key derivation.
+const HEDGE_LABEL: &[u8] = b"QRAIOP-ML-DSA-hedged-v1";
+
+/// Returns a copy of `secret_key` whose signing seed `K` (bytes 32..64) is
+/// replaced by `SHAKE256(label || K || rnd)`.
+///
+/// The backend draws the FIPS 204 `rnd` for the per-signature mask seed
+/// `rho'' = H(K || rnd || mu)` from the OS RNG itself, with no way to pass
+/// one in. `K` is used for nothing else, so swapping it for a value mixed
+/// with `rnd` feeds caller-supplied randomness into `rho''` as well, while
+/// the signature still verifies under the unchanged public key.
+fn hedged_secret_key(secret_key: &SecretKey, rnd: &[u8; 32]) -> SecretKey {
+    // Scoped here: at module level `Update` clashes with `sha2::Digest`
+    use sha3::digest::{ExtendableOutput, Update, XofReader};
+    use sha3::Shake256;
+
+    let mut bytes = secret_key.0.expose_secret().to_vec();
+    let mut shake = Shake256::default();
+    shake.update(HEDGE_LABEL);
+    shake.update(&bytes[32..64]);
+    shake.update(rnd);
+    shake.finalize_xof().read(&mut bytes[32..64]);
+    SecretKey(Secret::new(bytes))
+}
+
+/// Domain separator for hierarchical key derivation.
+const HD_LABEL: &[u8] = b"QRAIOP-ML-DSA-HD-v1";
+
+/// Shortest master seed accepted by [`MlDsa65::derive_keypair`] and its
+/// siblings.
+pub const MIN_MASTER_SEED_LENGTH: usize = 32;
+
+/// Key generation seed for the node at `path` below `master_seed`:
+///
+/// ```text
+/// SHAKE256(label || len(algorithm) (1) || algorithm
+///     || len(master_seed) (8, BE) || master_seed
+///     || len(path) (4, BE) || path[0] (4, BE) || ...)   first 32 bytes
+/// ```
+fn hd_seed(algorithm: &str, master_seed: &[u8], path: &[u32]) -> Result<Zeroizing<[u8; 32]>> {
+    use sha3::digest::{ExtendableOutput, Update, XofReader};
+    use sha3::Shake256;
+
+    if master_seed.len() < MIN_MASTER_SEED_LENGTH {
+        return Err(QraiopError::InvalidKey(format!(
+            "Master seed must be at least {} bytes, got {}",
+            MIN_MASTER_SEED_LENGTH,
+            master_seed.len()
+        )));
+    }
+    let mut shake = Shake256::default();
+    shake.update(HD_LABEL);
+    shake.update(&[algorithm.len() as u8]);
+    shake.update(algorithm.as_bytes());
+    shake.update(&(master_seed.len() as u64).to_be_bytes());
+    shake.update(master_seed);
+    shake.update(&(path.len() as u32).to_be_bytes());
+    for index in path {
+        shake.update(&index.to_be_bytes());
+    }
+    let mut seed = Zeroizing::new([0u8; 32]);
+    shake.finalize_xof().read(seed.as_mut());
+    Ok(seed)
+}
+
+/// Longest context string FIPS 204 allows.
+pub const MAX_CONTEXT_LENGTH: usize = 255;
+
+/// Rejects context strings FIPS 204 does not allow. The backend reports an
+/// overlong context only through a return code its Rust bindings drop, so
+/// this must run before every call that passes one.
+fn check_context(context: &[u8]) -> Result<()> {
+    if context.len() > MAX_CONTEXT_LENGTH {
+        return Err(QraiopError::CryptoError(format!(
+            "Context string must be at most {} bytes, got {}",
+            MAX_CONTEXT_LENGTH,
+            context.len()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+/// DER encoding of the SHA-512 OID (2.16.840.1.101.3.4.2.3), which the
+/// pre-hash encoding prepends to the digest.
+const SHA512_OID: [u8; 11] = [
+    0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03,
+];
+
+#[cfg(feature = "std")]
+/// Size of the chunks streamed into the pre-hash.
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+#[cfg(feature = "std")]
+/// Builds `1 || 0 || OID(SHA-512) || SHA-512(M)`, laid out like the FIPS 204
+/// HashML-DSA message with an empty context, hashing `M` from `reader` in
+/// fixed-size chunks.
+fn encode_prehashed<R: Read>(mut reader: R) -> Result<Vec<u8>> {
+    let mut hasher = Sha512::new();
+    let mut buffer = vec![0u8; READ_CHUNK_SIZE];
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        hasher.update(&buffer[..read]);
+    }
+
+    let mut encoded = Vec::with_capacity(2 + SHA512_OID.len() + 64);
+    encoded.push(1);
+    encoded.push(0);
+    encoded.extend_from_slice(&SHA512_OID);
+    encoded.extend_from_slice(&hasher.finalize());
+    Ok(encoded)
+}
+
+/// `From` the backend's native type and `TryFrom` a reference back to it.
+/// These tie callers to the exact backend version this crate depends on,
+/// hence the opt-in `pqcrypto_interop` feature.
+#[cfg(feature = "pqcrypto_interop")]
+macro_rules! native_conversions {
+    ($backend:ident, $algorithm:literal, $($ours:ident => $native:ident, $error:ident);*) => {$(
+        impl From<$backend::$native> for $ours {
+            fn from(native: $backend::$native) -> Self {
+                Self(native.as_bytes().into())
+            }
+        }
+
+        impl TryFrom<&$ours> for $backend::$native {
+            type Error = QraiopError;
+
+            fn try_from(value: &$ours) -> Result<Self> {
+                $backend::$native::from_bytes(value.as_bytes())
+                    .map_err(|e| QraiopError::$error(e.to_string()).with_algorithm($algorithm))
+            }
+        }
+    )*};
+}
+
+macro_rules! ml_dsa {
+    (
+        $(#[$meta:meta])* $name:ident,
+        $backend:ident,
+        $sizes:ident,
+        $algorithm:literal,
+        $oid:literal,
+        $level:expr
+    ) => {
+        $(#[$meta])*
+        pub struct $name;
+
+        impl $name {
+            /// Deterministically derives a key pair from a 32-byte seed.
+            ///
+            /// This is FIPS 204 `ML-DSA.KeyGen_internal` with the seed as
+            /// `xi`, so other implementations give the same keys for the
+            /// same seed. The same seed always produces the same `(pk, sk)`,
+            /// so it must be kept as secret as the secret key itself.
+            ///
+            /// Earlier versions expanded the seed without the parameter set
+            /// that FIPS 204 mixes in, and so derived different keys from
+            /// it, including through [`Self::derive_keypair`]. Keys made that
+            /// way still work, but re-deriving them from their seed now
+            /// gives new keys, whose public keys must be distributed again.
+            pub fn keypair_from_seed(seed: &[u8; 32]) -> Result<(PublicKey, SecretKey)> {
+                let (pk, sk) = keygen::generate(seed, $sizes::SECRET_KEY_SIZE)
+                    .map_err(|e| e.with_algorithm($algorithm))?;
+                Ok((PublicKey(pk), SecretKey(Secret::new(sk))))
+            }
+
+            /// Generates a key pair from a 32-byte seed drawn from `rng` and
+            /// expanded with [`Self::keypair_from_seed`], so a seeded RNG
+            /// reproduces the same sequence of keys. [`DigitalSignature::keypair`]
+            /// is this with the OS RNG. If `rng` fails, this returns
+            /// [`QraiopError::KeyGenerationFailed`] instead of panicking.
+            pub fn keypair_with_rng<R: RngCore + CryptoRng>(
+                rng: &mut R,
+            ) -> Result<(PublicKey, SecretKey)> {
+                let mut seed = [0u8; 32];
+                let keypair = utils::keygen_seed(rng, &mut seed)
+                    .map_err(|e| e.with_algorithm($algorithm))
+                    .and_then(|()| Self::keypair_from_seed(&seed));
+                seed.zeroize();
+                keypair
+            }
+
+            /// Derives the key pair at `path` in a tree of keys below
+            /// `master_seed`, so one backed-up seed recovers every key.
+            ///
+            /// Every node's seed is hashed from the master seed, the
+            /// parameter set and the full path at once rather than from
+            /// its parent's key, so a leaked key reveals nothing about its
+            /// parent, siblings or the master seed. Unlike BIP 32 there is
+            /// no derivation from public keys. `master_seed` must be at
+            /// least [`MIN_MASTER_SEED_LENGTH`] bytes, or this fails with
+            /// [`QraiopError::InvalidKey`]. Node keys changed along with
+            /// [`Self::keypair_from_seed`]; see there.
+            pub fn derive_keypair(
+                master_seed: &[u8],
+                path: &[u32],
+            ) -> Result<(PublicKey, SecretKey)> {
+                let seed = hd_seed($algorithm, master_seed, path)
+                    .map_err(|e| e.with_algorithm($algorithm))?;
+                Self::keypair_from_seed(&seed)
+            }
+
+            /// Like [`DigitalSignature::sign`], but also mixes 32 bytes from
+            /// `rng` into the per-signature randomness.
+            ///
+            /// [`DigitalSignature::sign`] is already hedged, with the FIPS
+            /// 204 `rnd` drawn by the backend from the OS RNG. This adds a
+            /// second, independent source for signers that do not want to
+            /// rely on the OS RNG alone. The randomness is combined with
+            /// the secret key rather than replacing it, so a weak or
+            /// repeating `rng` cannot leak the key. If `rng` fails, this
+            /// returns [`QraiopError::CryptoError`] instead of panicking.
+            pub fn sign_hedged<R: RngCore + CryptoRng>(
+                secret_key: &SecretKey,
+                message: &[u8],
+                rng: &mut R,
+            ) -> Result<Signature> {
+                let mut rnd = [0u8; 32];
+                let hedged = utils::fill_random(rng, &mut rnd, QraiopError::CryptoError)
+                    .map_err(|e| e.with_algorithm($algorithm))
+                    .map(|()| hedged_secret_key(secret_key, &rnd));
+                rnd.zeroize();
+                <Self as DigitalSignature>::sign(&hedged?, message)
+            }
+
+            /// Signs `message` bound to a FIPS 204 context string of at most
+            /// 255 bytes, for domain separation between protocols sharing a key.
+            ///
+            /// [`DigitalSignature::sign`] is the same as signing under the
+            /// empty context, as FIPS 204 specifies.
+            pub fn sign_with_context(
+                secret_key: &SecretKey,
+                message: &[u8],
+                context: &[u8],
+            ) -> Result<Signature> {
+                check_context(context)?;
+                let sk = $backend::SecretKey::from_bytes(secret_key.0.expose_secret())
+                    .map_err(|e| QraiopError::InvalidKey(e.to_string()).with_algorithm($algorithm))?;
+                let signature = $backend::detached_sign_ctx(message, context, &sk);
+                Ok(Signature(signature.as_bytes().to_vec()))
+            }
+
+            /// Verifies a signature made by [`Self::sign_with_context`] under
+            /// the same context string.
+            #[must_use = "verification result must be checked"]
+            pub fn verify_with_context(
+                public_key: &PublicKey,
+                message: &[u8],
+                context: &[u8],
+                signature: &Signature,
+            ) -> Result<bool> {
+                check_context(context)?;
+                let pk = $backend::PublicKey::from_bytes(&public_key.0)
+                    .map_err(|e| QraiopError::InvalidKey(e.to_string()).with_algorithm($algorithm))?;
+                let Ok(signature) = $backend::DetachedSignature::from_bytes(&signature.0) else {
+                    return Ok(false);
+                };
+                Ok($backend::verify_detached_signature_ctx(&signature, message, context, &pk).is_ok())
+            }
+
+            #[cfg(feature = "std")]
+            /// Signs everything read from `reader` without buffering it, by
+            /// signing its SHA-512 digest. Read errors surface as
+            /// `QraiopError::IoError`.
+            ///
+            /// This is not FIPS 204 HashML-DSA, which the backend does not
+            /// offer: the digest, in HashML-DSA's layout, is signed as an
+            /// ordinary ML-DSA message. Other implementations' HashML-DSA
+            /// verifiers reject these signatures; check them with
+            /// [`Self::verify_reader`].
+            pub fn sign_reader<R: Read>(secret_key: &SecretKey, reader: R) -> Result<Signature> {
+                let encoded = encode_prehashed(reader)?;
+                <Self as DigitalSignature>::sign(secret_key, &encoded)
+            }
+
+            #[cfg(feature = "std")]
+            /// Verifies a [`Self::sign_reader`] signature over everything read
+            /// from `reader`.
+            #[must_use = "verification result must be checked"]
+            pub fn verify_reader<R: Read>(
+                public_key: &PublicKey,
+                reader: R,
+                signature: &Signature,
+            ) -> Result<bool> {
+                let encoded = encode_prehashed(reader)?;
+                <Self as DigitalSignature>::verify(public_key, &encoded, signature)
+            }
+
+            /// [`DigitalSignature::verify`] with no early return on the
+            /// signature in this crate's code: a signature of the wrong
+            /// length is verified as an all-zero signature of the right
+            /// length, and the outcome is combined with the length check
+            /// as a [`subtle::Choice`]. Valid, forged and malformed
+            /// signatures all make exactly one full call into the backend.
+            ///
+            /// The backend itself is not constant time here. It stops at
+            /// the first failed check (hint encoding, the norm bound on
+            /// `z`, then a byte-by-byte comparison of the challenge), so
+            /// timing can show which check rejected a forgery. Every input
+            /// to verification is public, so this leaks nothing about
+            /// the secret key; the wrapper only keeps this crate from
+            /// adding a signal of its own. A malformed public key is still
+            /// an error.
+            #[must_use = "verification result must be checked"]
+            pub fn verify_ct(
+                public_key: &PublicKey,
+                message: &[u8],
+                signature: &Signature,
+            ) -> Result<bool> {
+                let pk = $backend::PublicKey::from_bytes(&public_key.0).map_err(|e| {
+                    QraiopError::InvalidKey(e.to_string()).with_algorithm($algorithm)
+                })?;
+                let well_formed =
+                    Choice::from(u8::from(signature.0.len() == $sizes::SIGNATURE_SIZE));
+                let mut candidate = vec![0u8; $sizes::SIGNATURE_SIZE];
+                let copied = signature.0.len().min($sizes::SIGNATURE_SIZE);
+                candidate[..copied].copy_from_slice(&signature.0[..copied]);
+                let candidate = $backend::DetachedSignature::from_bytes(&candidate)
+                    .map_err(|e| QraiopError::CryptoError(e.to_string()))?;
+                let verified = $backend::verify_detached_signature(&candidate, message, &pk);
+                Ok(bool::from(well_formed & Choice::from(u8::from(verified.is_ok()))))
+            }
+
+            /// Verifies many independent `(public key, message, signature)`
+            /// triples, returning each one's validity at the same index.
+            ///
+            /// ML-DSA has no true batch verification, so this just spreads
+            /// the individual checks across the rayon thread pool (or runs
+            /// them in order without the `rayon` feature). A malformed public
+            /// key fails the whole batch with `InvalidKey`, as in
+            /// [`DigitalSignature::verify`].
+            #[must_use = "verification result must be checked"]
+            pub fn verify_batch(items: &[(PublicKey, &[u8], Signature)]) -> Result<Vec<bool>> {
+                #[cfg(feature = "rayon")]
+                let items = items.par_iter();
+                #[cfg(not(feature = "rayon"))]
+                let items = items.iter();
+                items
+                    .map(|(public_key, message, signature)| {
+                        <Self as DigitalSignature>::verify(public_key, message, signature)
+                    })
+                    .collect()
+            }
+        }
+
+        impl DigitalSignature for $name {
+            type PublicKey = PublicKey;
+            type SecretKey = SecretKey;
+            type Signature = Signature;
+
+            // The backend's own `keypair` panics if the OS RNG fails
+            fn keypair() -> Result<(Self::PublicKey, Self::SecretKey)> {
+                Self::keypair_with_rng(&mut utils::SystemRng)
+            }
+
+            fn sign(secret_key: &Self::SecretKey, message: &[u8]) -> Result<Self::Signature> {
+                let sk = $backend::SecretKey::from_bytes(secret_key.0.expose_secret())
+                    .map_err(|e| QraiopError::InvalidKey(e.to_string()).with_algorithm($algorithm))?;
+                let signature = $backend::detached_sign(message, &sk);
+                Ok(Signature(signature.as_bytes().to_vec()))
+            }
+
+            fn verify(
+                public_key: &Self::PublicKey,
+                message: &[u8],
+                signature: &Self::Signature,
+            ) -> Result<bool> {
+                let pk = $backend::PublicKey::from_bytes(&public_key.0)
+                    .map_err(|e| QraiopError::InvalidKey(e.to_string()).with_algorithm($algorithm))?;
+                // A signature that cannot even be decoded is simply not valid
+                let Ok(signature) = $backend::DetachedSignature::from_bytes(&signature.0) else {
+                    return Ok(false);
+                };
+                Ok($backend::verify_detached_signature(&signature, message, &pk).is_ok())
+            }
+
+            fn algorithm_name() -> &'static str {
+                $algorithm
+            }
+
+            fn algorithm_oid() -> Option<&'static str> {
+                Some($oid)
+            }
+
+            fn security_level() -> SecurityLevel {
+                $level
+            }
+
+            fn public_key_size() -> usize {
+                $sizes::PUBLIC_KEY_SIZE
+            }
+
+            fn secret_key_size() -> usize {
+                $sizes::SECRET_KEY_SIZE
+            }
+
+            fn signature_size() -> usize {
+                $sizes::SIGNATURE_SIZE
+            }
+        }
+
+        #[cfg(feature = "pqcrypto_interop")]
+        native_conversions!(
+            $backend,
+            $algorithm,
+            PublicKey => PublicKey, InvalidKey;
+            SecretKey => SecretKey, InvalidKey;
+            Signature => DetachedSignature, SerializationError
+        );
+    };
+}
+
+ml_dsa!(
+    /// ML-DSA-44 (NIST security category 2).
+    MlDsa44,
+    mldsa44,
+    ml_dsa_44,
+    "ML-DSA-44",
+    "2.16.840.1.101.3.4.3.17",
+    SecurityLevel::Level2
+);
+ml_dsa!(
+    /// ML-DSA-65 (NIST security category 3).
+    MlDsa65,
+    mldsa65,
+    ml_dsa_65,
+    "ML-DSA-65",
+    "2.16.840.1.101.3.4.3.18",
+    SecurityLevel::Level3
+);
+ml_dsa!(
+    /// ML-DSA-87 (NIST security category 5).
+    MlDsa87,
+    mldsa87,
+    ml_dsa_87,
+    "ML-DSA-87",
+    "2.16.840.1.101.3.4.3.19",
+    SecurityLevel::Level5
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip<S>()
+    where
+        S: DigitalSignature<PublicKey = PublicKey, SecretKey = SecretKey, Signature = Signature>,
+    {
+        let message = b"quantum-resistant message";
+        let (pk, sk) = S::keypair().unwrap();
+        let signature = S::sign(&sk, message).unwrap();
+
+        assert!(S::verify(&pk, message, &signature).unwrap());
+        assert!(!S::verify(&pk, b"tampered message", &signature).unwrap());
+    }
+
+    #[test]
+    fn test_ml_dsa_44_roundtrip() {
+        roundtrip::<MlDsa44>();
+    }
+
+    #[test]
+    fn test_ml_dsa_65_roundtrip() {
+        roundtrip::<MlDsa65>();
+    }
+
+    #[test]
+    fn test_ml_dsa_87_roundtrip() {
+        roundtrip::<MlDsa87>();
+    }
+
+    #[test]
+    fn test_keypair_with_rng() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha20Rng;
+
+        let (pk1, sk1) = MlDsa65::keypair_with_rng(&mut ChaCha20Rng::seed_from_u64(3)).unwrap();
+        let (pk2, sk2) = MlDsa65::keypair_with_rng(&mut ChaCha20Rng::seed_from_u64(3)).unwrap();
+        assert_eq!(pk1, pk2);
+        assert_eq!(sk1.as_bytes(), sk2.as_bytes());
+
+        let err = MlDsa65::keypair_with_rng(&mut utils::FailingRng).unwrap_err();
+        assert!(
+            matches!(err, QraiopError::KeyGenerationFailed(ref m) if m.starts_with("ML-DSA-65: ")),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_sign_hedged_with_failing_rng() {
+        let (_, sk) = MlDsa44::keypair().unwrap();
+        let err = MlDsa44::sign_hedged(&sk, b"message", &mut utils::FailingRng).unwrap_err();
+        assert!(
+            matches!(err, QraiopError::CryptoError(ref m) if m.starts_with("ML-DSA-44: ")),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_tampered_signature_fails() {
+        let message = b"quantum-resistant message";
+        let (pk, sk) = MlDsa65::keypair().unwrap();
+        let mut signature = MlDsa65::sign(&sk, message).unwrap();
+        signature.0[0] ^= 0x01;
+        assert!(!MlDsa65::verify(&pk, message, &signature).unwrap());
+
+        signature.0.push(0);
+        assert!(!MlDsa65::verify(&pk, message, &signature).unwrap());
+    }
+
+    #[test]
+    fn test_sizes_and_metadata() {
+        let (pk, sk) = MlDsa44::keypair().unwrap();
+        let signature = MlDsa44::sign(&sk, b"").unwrap();
+        assert_eq!(pk.0.len(), ml_dsa_44::PUBLIC_KEY_SIZE);
+        assert_eq!(sk.0.len(), ml_dsa_44::SECRET_KEY_SIZE);
+        assert_eq!(signature.0.len(), ml_dsa_44::SIGNATURE_SIZE);
+
+        let (pk, sk) = MlDsa65::keypair().unwrap();
+        assert_eq!(pk.0.len(), ml_dsa_65::PUBLIC_KEY_SIZE);
+        assert_eq!(sk.0.len(), ml_dsa_65::SECRET_KEY_SIZE);
+
+        let (pk, sk) = MlDsa87::keypair().unwrap();
+        assert_eq!(pk.0.len(), ml_dsa_87::PUBLIC_KEY_SIZE);
+        assert_eq!(sk.0.len(), ml_dsa_87::SECRET_KEY_SIZE);
+
+        assert_eq!(MlDsa44::algorithm_name(), "ML-DSA-44");
+        assert_eq!(MlDsa65::algorithm_name(), "ML-DSA-65");
+        assert_eq!(MlDsa87::algorithm_name(), "ML-DSA-87");
+        assert_eq!(MlDsa44::security_level(), SecurityLevel::Level2);
+        assert_eq!(MlDsa65::security_level(), SecurityLevel::Level3);
+        assert_eq!(MlDsa87::security_level(), SecurityLevel::Level5);
+    }
+
+    #[test]
+    fn test_context_binding() {
+        let message = b"quantum-resistant message";
+        let (pk, sk) = MlDsa65::keypair().unwrap();
+        let signature = MlDsa65::sign_with_context(&sk, message, b"protocol-a").unwrap();
+
+        assert!(MlDsa65::verify_with_context(&pk, message, b"protocol-a", &signature).unwrap());
+        assert!(!MlDsa65::verify_with_context(&pk, message, b"protocol-b", &signature).unwrap());
+        assert!(!MlDsa65::verify_with_context(&pk, message, b"", &signature).unwrap());
+        assert!(!MlDsa65::verify(&pk, message, &signature).unwrap());
+
+        // A plain signature is one under the empty context
+        let plain = MlDsa65::sign(&sk, message).unwrap();
+        assert!(MlDsa65::verify_with_context(&pk, message, b"", &plain).unwrap());
+        assert!(!MlDsa65::verify_with_context(&pk, message, b"protocol-a", &plain).unwrap());
+        let empty = MlDsa65::sign_with_context(&sk, message, b"").unwrap();
+        assert!(MlDsa65::verify(&pk, message, &empty).unwrap());
+    }
+
+    #[test]
+    fn test_context_too_long() {
+        let (pk, sk) = MlDsa44::keypair().unwrap();
+        let context = [0u8; MAX_CONTEXT_LENGTH + 1];
+        assert!(matches!(
+            MlDsa44::sign_with_context(&sk, b"msg", &context),
+            Err(QraiopError::CryptoError(_))
+        ));
+
+        let signature = MlDsa44::sign_with_context(&sk, b"msg", &context[1..]).unwrap();
+        assert!(matches!(
+            MlDsa44::verify_with_context(&pk, b"msg", &context, &signature),
+            Err(QraiopError::CryptoError(_))
+        ));
+        assert!(MlDsa44::verify_with_context(&pk, b"msg", &context[1..], &signature).unwrap());
+    }
+
+    #[test]
+    fn test_trait_sizes_match_key_sizes() {
+        assert_eq!(MlDsa44::public_key_size(), ml_dsa_44::PUBLIC_KEY_SIZE);
+        assert_eq!(MlDsa44::secret_key_size(), ml_dsa_44::SECRET_KEY_SIZE);
+        assert_eq!(MlDsa44::signature_size(), ml_dsa_44::SIGNATURE_SIZE);
+        assert_eq!(MlDsa65::public_key_size(), ml_dsa_65::PUBLIC_KEY_SIZE);
+        assert_eq!(MlDsa65::secret_key_size(), ml_dsa_65::SECRET_KEY_SIZE);
+        assert_eq!(MlDsa65::signature_size(), ml_dsa_65::SIGNATURE_SIZE);
+        assert_eq!(MlDsa87::public_key_size(), ml_dsa_87::PUBLIC_KEY_SIZE);
+        assert_eq!(MlDsa87::secret_key_size(), ml_dsa_87::SECRET_KEY_SIZE);
+        assert_eq!(MlDsa87::signature_size(), ml_dsa_87::SIGNATURE_SIZE);
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        let (pk1, sk1) = MlDsa44::keypair().unwrap();
+        let (pk2, sk2) = MlDsa44::keypair().unwrap();
+        let sig1 = MlDsa44::sign(&sk1, b"first").unwrap();
+        let sig2 = MlDsa44::sign(&sk2, b"second").unwrap();
+
+        let items: Vec<(PublicKey, &[u8], Signature)> = vec![
+            (pk1.clone(), b"first", sig1.clone()),
+            (pk2.clone(), b"first", sig1.clone()),
+            (pk2.clone(), b"second", sig2.clone()),
+            (pk1.clone(), b"second", sig2),
+            (pk1, b"tampered", sig1),
+        ];
+        assert_eq!(
+            MlDsa44::verify_batch(&items).unwrap(),
+            vec![true, false, true, false, false]
+        );
+        assert!(MlDsa44::verify_batch(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_public_key_as_set_member() {
+        use std::collections::HashSet;
+
+        let (pk1, _) = MlDsa44::keypair().unwrap();
+        let (pk2, _) = MlDsa44::keypair().unwrap();
+        let mut keys = HashSet::new();
+        assert!(keys.insert(pk1.clone()));
+        assert!(keys.insert(pk2));
+        assert!(!keys.insert(pk1));
+        assert_eq!(keys.len(), 2);
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let (pk1, _) = MlDsa44::keypair().unwrap();
+        let (pk2, _) = MlDsa44::keypair().unwrap();
+        assert_eq!(pk1.clone().fingerprint(), pk1.fingerprint());
+        assert_ne!(pk1.fingerprint(), pk2.fingerprint());
+        assert_ne!(pk1.fingerprint_full(), pk2.fingerprint_full());
+    }
+
+    #[test]
+    fn test_verify_strict() {
+        let (pk, sk) = MlDsa44::keypair().unwrap();
+        let signature = MlDsa44::sign(&sk, b"message").unwrap();
+        MlDsa44::verify_strict(&pk, b"message", &signature).unwrap();
+        assert!(matches!(
+            MlDsa44::verify_strict(&pk, b"massage", &signature),
+            Err(QraiopError::SignatureVerificationFailed)
+        ));
+    }
+
+    #[test]
+    fn test_encrypted_secret_key() {
+        let (pk, sk) = MlDsa65::keypair().unwrap();
+        let data = passphrase::encrypt_with_params(
+            b"correct horse",
+            sk.as_bytes(),
+            passphrase::test_params(),
+        )
+        .unwrap();
+
+        let restored = SecretKey::from_encrypted_bytes(&data, b"correct horse").unwrap();
+        let signature = MlDsa65::sign(&restored, b"message").unwrap();
+        assert!(MlDsa65::verify(&pk, b"message", &signature).unwrap());
+        assert!(matches!(
+            SecretKey::from_encrypted_bytes(&data, b"wrong horse"),
+            Err(QraiopError::SerializationError(_))
+        ));
+        assert!(matches!(
+            SecretKey::from_encrypted_bytes(&data[..20], b"correct horse"),
+            Err(QraiopError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_vec_conversions() {
+        let (pk, sk) = MlDsa44::keypair().unwrap();
+        let sk: SecretKey = sk.as_bytes().to_vec().try_into().unwrap();
+        let signature = MlDsa44::sign(&sk, b"message").unwrap();
+
+        let pk: PublicKey = Vec::from(pk).try_into().unwrap();
+        let signature: Signature = Vec::from(signature).try_into().unwrap();
+        assert!(MlDsa44::verify(&pk, b"message", &signature).unwrap());
+
+        assert!(matches!(
+            PublicKey::try_from(vec![0u8; 1000]),
+            Err(QraiopError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            SecretKey::try_from(vec![0u8; 1000]),
+            Err(QraiopError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            Signature::try_from(vec![0u8; 1000]),
+            Err(QraiopError::InvalidSignature(_))
+        ));
+    }
+
+    #[test]
+    fn test_signature_from_bytes_strict() {
+        let (pk, sk) = MlDsa44::keypair().unwrap();
+        let signature = MlDsa44::sign(&sk, b"message").unwrap();
+        let bytes = signature.as_bytes();
+        assert_eq!(Signature::from_bytes_strict(bytes).unwrap(), signature);
+
+        // ML-DSA-44 hints hold up to 80 positions and 4 row counts. Fewer
+        // are ever set in practice, so the last position byte is padding.
+        let hint = &bytes[bytes.len() - 84..];
+        assert!(hint[83] < 80);
+        let mut padded = bytes.to_vec();
+        let last = padded.len() - 5;
+        padded[last] = 1;
+        let padded = Signature::from_bytes(&padded).unwrap();
+        assert_ne!(padded, signature);
+        assert!(matches!(
+            Signature::from_bytes_strict(padded.as_bytes()),
+            Err(QraiopError::InvalidSignature(_))
+        ));
+        assert!(!MlDsa44::verify(&pk, b"message", &padded).unwrap());
+
+        // Counts that go backwards cannot be decoded at all
+        let mut decreasing = bytes.to_vec();
+        let first_count = decreasing.len() - 4;
+        decreasing[first_count] = 80;
+        assert!(Signature::from_bytes_strict(&decreasing).is_err());
+    }
+
+    #[test]
+    fn test_signature_from_slice() {
+        let (pk, sk) = MlDsa65::keypair().unwrap();
+        let signature = MlDsa65::sign(&sk, b"message").unwrap();
+        let wire = signature.as_bytes();
+        assert_eq!(wire.len(), MlDsa65::signature_size());
+
+        let parsed = Signature::try_from(wire).unwrap();
+        assert_eq!(parsed, signature);
+        assert!(MlDsa65::verify(&pk, b"message", &parsed).unwrap());
+        assert!(matches!(
+            Signature::try_from(&wire[..wire.len() - 1]),
+            Err(QraiopError::InvalidSignature(_))
+        ));
+        assert!(matches!(
+            Signature::from_bytes(&[]),
+            Err(QraiopError::InvalidSignature(_))
+        ));
+    }
+
+    #[test]
+    fn test_public_values_sort_by_bytes() {
+        use alloc::collections::BTreeSet;
+
+        let keys: BTreeSet<PublicKey> = [3u8, 1, 2]
+            .into_iter()
+            .map(|b| PublicKey::from_bytes(&[b; ml_dsa_44::PUBLIC_KEY_SIZE]).unwrap())
+            .collect();
+        let firsts: Vec<u8> = keys.iter().map(|key| key.as_bytes()[0]).collect();
+        assert_eq!(firsts, [1, 2, 3]);
+        // A shorter key sorts before any longer key it is a prefix of
+        let longer = PublicKey::from_bytes(&[1; ml_dsa_65::PUBLIC_KEY_SIZE]).unwrap();
+        assert!(keys.first().unwrap() < &longer);
+
+        // Insertion order does not matter
+        let (_, sk) = MlDsa44::keypair().unwrap();
+        let signatures: Vec<Signature> = [b"a", b"b", b"c", b"d"]
+            .iter()
+            .map(|message| MlDsa44::sign(&sk, *message).unwrap())
+            .collect();
+        let forward: BTreeSet<&Signature> = signatures.iter().collect();
+        let backward: BTreeSet<&Signature> = signatures.iter().rev().collect();
+        assert!(forward.iter().eq(backward.iter()));
+        assert!(forward
+            .iter()
+            .zip(forward.iter().skip(1))
+            .all(|(a, b)| a.as_bytes() < b.as_bytes()));
+    }
+
+    #[test]
+    fn test_manual_zeroize() {
+        let (_, mut sk) = MlDsa44::keypair().unwrap();
+        assert_eq!(&sk.to_zeroizing_bytes()[..], sk.as_bytes());
+
+        sk.zeroize();
+        assert!(sk.as_bytes().is_empty());
+        assert!(matches!(
+            MlDsa44::sign(&sk, b"message"),
+            Err(QraiopError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_public_key_from_secret_key() {
+        for (pk, sk) in [
+            MlDsa44::keypair().unwrap(),
+            MlDsa65::keypair().unwrap(),
+            MlDsa87::keypair().unwrap(),
+        ] {
+            assert_eq!(sk.public_key().unwrap().as_bytes(), pk.as_bytes());
+
+            // Flip a bit of s1, just after rho, K and tr
+            let mut bytes = sk.as_bytes().to_vec();
+            bytes[128] ^= 1;
+            let tweaked = SecretKey::from_bytes(&bytes).unwrap();
+            assert!(matches!(
+                tweaked.public_key(),
+                Err(QraiopError::InvalidKey(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_signing_is_hedged() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha20Rng;
+
+        let (pk, sk) = MlDsa44::keypair().unwrap();
+
+        let first = MlDsa44::sign(&sk, b"message").unwrap();
+        let second = MlDsa44::sign(&sk, b"message").unwrap();
+        assert_ne!(first.as_bytes(), second.as_bytes());
+        MlDsa44::verify_strict(&pk, b"message", &first).unwrap();
+        MlDsa44::verify_strict(&pk, b"message", &second).unwrap();
+
+        // The same RNG output twice still gives different signatures
+        let mut rng = ChaCha20Rng::from_seed([5u8; 32]);
+        let first = MlDsa44::sign_hedged(&sk, b"message", &mut rng).unwrap();
+        let mut rng = ChaCha20Rng::from_seed([5u8; 32]);
+        let second = MlDsa44::sign_hedged(&sk, b"message", &mut rng).unwrap();
+        assert_ne!(first.as_bytes(), second.as_bytes());
+        MlDsa44::verify_strict(&pk, b"message", &first).unwrap();
+        MlDsa44::verify_strict(&pk, b"message", &second).unwrap();
+    }
+
+    #[test]
+    fn test_verifying_key() {
+        let (pk, sk) = MlDsa65::keypair().unwrap();
+        let signature = MlDsa65::sign(&sk, b"message").unwrap();
+
+        // The verification service only ever sees the exported bytes
+        let exported = VerifyingKey::from(pk.clone()).as_bytes().to_vec();
+        let verifying_key = VerifyingKey::from_bytes(&exported).unwrap();
+        assert!(verifying_key.verify(b"message", &signature).unwrap());
+        verifying_key.verify_strict(b"message", &signature).unwrap();
+        assert!(!verifying_key.verify(b"massage", &signature).unwrap());
+        assert!(matches!(
+            verifying_key.verify_strict(b"massage", &signature),
+            Err(QraiopError::SignatureVerificationFailed)
+        ));
+
+        assert_eq!(VerifyingKey::from_secret_key(&sk).unwrap(), verifying_key);
+        assert_eq!(verifying_key.public_key(), &pk);
+        assert_eq!(
+            format!("{:?}", verifying_key),
+            format!("VerifyingKey({})", pk)
+        );
+    }
+
+    #[test]
+    fn test_keypair_from_seed() {
+        let seed = [7u8; 32];
+        let keypairs = [
+            MlDsa44::keypair_from_seed(&seed).unwrap(),
+            MlDsa65::keypair_from_seed(&seed).unwrap(),
+            MlDsa87::keypair_from_seed(&seed).unwrap(),
+        ];
+        let (pk, sk) = &keypairs[1];
+        let (pk_again, sk_again) = MlDsa65::keypair_from_seed(&seed).unwrap();
+        assert_eq!(pk, &pk_again);
+        assert_eq!(sk.as_bytes(), sk_again.as_bytes());
+        assert_ne!(pk, &MlDsa65::keypair_from_seed(&[8u8; 32]).unwrap().0);
+
+        // The keys are consistent with each other and usable by the backend
+        for ((pk, sk), sign) in keypairs.iter().zip([
+            MlDsa44::sign as fn(&SecretKey, &[u8]) -> Result<Signature>,
+            MlDsa65::sign,
+            MlDsa87::sign,
+        ]) {
+            assert_eq!(&sk.public_key().unwrap(), pk);
+            let signature = sign(sk, b"seeded").unwrap();
+            assert!(VerifyingKey::from(pk.clone())
+                .verify(b"seeded", &signature)
+                .unwrap());
+        }
+    }
+
+    #[test]
+    fn test_reduce() {
+        use public_key::{reduce, Q};
+
+        for a in [
+            0,
+            1,
+            Q - 1,
+            Q,
+            Q + 1,
+            2 * Q - 1,
+            2 * Q,
+            Q * Q,
+            Q * Q + Q - 1,
+        ] {
+            assert_eq!(reduce(a), a % Q, "{}", a);
+        }
+        for a in [(1 << 62) - 1, (1 << 62) - Q, 123_456_789_012_345] {
+            assert_eq!(reduce(a), a % Q, "{}", a);
+        }
+    }
+
+    #[test]
+    fn test_derive_keypair() {
+        let master = [42u8; 32];
+        let (pk_a, sk_a) = MlDsa65::derive_keypair(&master, &[44, 0, 1]).unwrap();
+        let (pk_b, _) = MlDsa65::derive_keypair(&master, &[44, 0, 2]).unwrap();
+        assert_ne!(pk_a, pk_b);
+        let (pk_again, sk_again) = MlDsa65::derive_keypair(&master, &[44, 0, 1]).unwrap();
+        assert_eq!(pk_a, pk_again);
+        assert_eq!(sk_a.as_bytes(), sk_again.as_bytes());
+
+        let signature = MlDsa65::sign(&sk_a, b"node").unwrap();
+        assert!(MlDsa65::verify(&pk_a, b"node", &signature).unwrap());
+
+        // A path is not confused with its prefix, another master seed or
+        // another parameter set
+        let derived = |seed: &[u8], path: &[u32]| MlDsa65::derive_keypair(seed, path).unwrap().0;
+        assert_ne!(derived(&master, &[44, 0]), derived(&master, &[44, 0, 0]));
+        assert_ne!(derived(&master, &[]), derived(&[43u8; 32], &[]));
+        assert_ne!(
+            MlDsa44::derive_keypair(&master, &[1]).unwrap().0.as_bytes()[..32],
+            derived(&master, &[1]).as_bytes()[..32]
+        );
+        assert!(matches!(
+            MlDsa65::derive_keypair(&[0u8; 16], &[0]),
+            Err(QraiopError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_ct() {
+        let (pk, sk) = MlDsa65::keypair().unwrap();
+        let signature = MlDsa65::sign(&sk, b"message").unwrap();
+        let mut forged = signature.clone();
+        forged.0[10] ^= 1;
+        let truncated = Signature(signature.0[..100].to_vec());
+        let padded = Signature([signature.as_bytes(), &[0]].concat());
+
+        // The answers match verify, but a malformed signature is rejected
+        // through the same full verification instead of an early return
+        for (candidate, expected) in [
+            (&signature, true),
+            (&forged, false),
+            (&truncated, false),
+            (&padded, false),
+        ] {
+            assert_eq!(
+                MlDsa65::verify(&pk, b"message", candidate).unwrap(),
+                expected
+            );
+            assert_eq!(
+                MlDsa65::verify_ct(&pk, b"message", candidate).unwrap(),
+                expected
+            );
+        }
+        assert!(!MlDsa65::verify_ct(&pk, b"other", &signature).unwrap());
+        assert!(matches!(
+            MlDsa44::verify_ct(&pk, b"message", &signature),
+            Err(QraiopError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_signature_set() {
+        let (pk44, sk44) = MlDsa44::keypair().unwrap();
+        let (pk65, sk65) = MlDsa65::keypair().unwrap();
+        let (pk, sk) = MlDsa87::keypair().unwrap();
+        let mut set = SignatureSet::new();
+        assert!(!set.verify_all(b"release v1").unwrap());
+        set.add(pk44, MlDsa44::sign(&sk44, b"release v1").unwrap());
+        set.add(pk65, MlDsa65::sign(&sk65, b"release v1").unwrap());
+        set.add(pk.clone(), MlDsa87::sign(&sk, b"release v1").unwrap());
+        assert_eq!(set.len(), 3);
+        assert!(set.verify_all(b"release v1").unwrap());
+        assert!(!set.verify_all(b"release v2").unwrap());
+
+        let decoded: SignatureSet =
+            bincode::deserialize(&bincode::serialize(&set).unwrap()).unwrap();
+        assert_eq!(decoded, set);
+
+        // One signer's signature over another message spoils the set, and
+        // replacing it restores it
+        set.add(pk.clone(), MlDsa87::sign(&sk, b"release v2").unwrap());
+        assert_eq!(set.len(), 3);
+        assert!(!set.verify_all(b"release v1").unwrap());
+        set.add(pk.clone(), MlDsa87::sign(&sk, b"release v1").unwrap());
+        assert!(set.verify_all(b"release v1").unwrap());
+
+        // A duplicated signer smuggled in through serialization
+        let mut duplicated = decoded.clone();
+        duplicated.signatures.push(duplicated.signatures[0].clone());
+        assert!(!duplicated.verify_all(b"release v1").unwrap());
+    }
+
+    #[test]
+    fn test_public_key_display() {
+        let (pk, _) = MlDsa65::keypair().unwrap();
+        let shown = pk.to_string();
+        assert_eq!(shown, format!("ML-DSA-65 pk:{}", pk.fingerprint()));
+        assert_eq!(shown.len(), "ML-DSA-65 pk:".len() + 8 * 3 - 1);
+        assert_eq!(shown.matches(':').count(), 8);
+        assert_eq!(format!("{:?}", pk), format!("PublicKey({})", shown));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_streaming_sign_and_verify() {
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join(format!("qraiop-stream-{}.bin", std::process::id()));
+        let mut file = std::fs::File::create(&path).unwrap();
+        let chunk: Vec<u8> = (0..=255u8).cycle().take(1024 * 1024).collect();
+        for _ in 0..10 {
+            file.write_all(&chunk).unwrap();
+        }
+        drop(file);
+
+        let (pk, sk) = MlDsa65::keypair().unwrap();
+        let signature = MlDsa65::sign_reader(&sk, std::fs::File::open(&path).unwrap()).unwrap();
+        let valid =
+            MlDsa65::verify_reader(&pk, std::fs::File::open(&path).unwrap(), &signature).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(valid);
+
+        // Same bytes from memory verify too; different bytes do not
+        let data = chunk.repeat(10);
+        assert!(MlDsa65::verify_reader(&pk, data.as_slice(), &signature).unwrap());
+        assert!(!MlDsa65::verify_reader(&pk, &data[1..], &signature).unwrap());
+        assert!(!MlDsa65::verify(&pk, &data, &signature).unwrap());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_streaming_read_error() {
+        struct FailingReader;
+
+        impl Read for FailingReader {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::BrokenPipe, "connection lost"))
+            }
+        }
+
+        let (pk, sk) = MlDsa44::keypair().unwrap();
+        assert!(matches!(
+            MlDsa44::sign_reader(&sk, FailingReader),
+            Err(QraiopError::IoError(_))
+        ));
+        let signature = MlDsa44::sign(&sk, b"").unwrap();
+        assert!(matches!(
+            MlDsa44::verify_reader(&pk, FailingReader, &signature),
+            Err(QraiopError::IoError(_))
+        ));
+    }
+
+    #[test]
+    fn test_deserialize_checks_length() {
+        let (pk, sk) = MlDsa65::keypair().unwrap();
+        let signature = MlDsa65::sign(&sk, b"message").unwrap();
+        let decoded: PublicKey = bincode::deserialize(&bincode::serialize(&pk).unwrap()).unwrap();
+        assert_eq!(decoded, pk);
+        let decoded: Signature =
+            bincode::deserialize(&bincode::serialize(&signature).unwrap()).unwrap();
+        assert_eq!(decoded, signature);
+
+        let short = bincode::serialize(&vec![0u8; 100]).unwrap();
+        assert!(bincode::deserialize::<PublicKey>(&short).is_err());
+        assert!(bincode::deserialize::<Signature>(&short).is_err());
+        assert!(bincode::deserialize::<VerifyingKey>(&short).is_err());
+        assert!(serde_json::from_str::<PublicKey>("[]").is_err());
+    }
+}
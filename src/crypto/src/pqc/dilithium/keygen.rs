@@ -0,0 +1,142 @@
+//! Seeded ML-DSA key generation.
+//!
+//! FIPS 204 `ML-DSA.KeyGen_internal`, which the backend does not expose,
+//! with the arithmetic of [`super::public_key`]: the seed `xi` expands to
+//! `rho || rho' || K = H(xi || k || l)`, `s1` and `s2` are sampled from
+//! `rho'`, and `t = A * s1 + s2` is split by Power2Round into `t1`,
+//! published in the public key, and `t0`, kept in the secret key. As
+//! there, secret values are reduced with [`reduce`] rather than `%`.
+
+use super::public_key::{
+    expand_a, inverse_ntt, ntt, pack_t1, reduce, zetas, Params, Poly, D, N, POLY_T1_BYTES, Q,
+    SEED_BYTES, TR_BYTES,
+};
+use crate::{QraiopError, Result};
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
+use zeroize::Zeroizing;
+
+const CRH_BYTES: usize = 64;
+
+/// Samples a polynomial with coefficients in `[-eta, eta]` by rejection
+/// from SHAKE256(rho' || nonce), reduced into `[0, Q)`.
+fn sample_eta(rho_prime: &[u8], nonce: u16, eta: u32) -> Poly {
+    let mut shake = Shake256::default();
+    shake.update(rho_prime);
+    shake.update(&nonce.to_le_bytes());
+    let mut reader = shake.finalize_xof();
+
+    let mut a = [0; N];
+    let mut filled = 0;
+    let mut byte = [0u8; 1];
+    while filled < N {
+        reader.read(&mut byte);
+        for half in [u32::from(byte[0] & 0x0f), u32::from(byte[0] >> 4)] {
+            // Reduction mod 5 without a division, as in the reference
+            let value = match eta {
+                2 if half < 15 => half - ((205 * half) >> 10) * 5,
+                4 if half < 9 => half,
+                _ => continue,
+            };
+            if filled < N {
+                a[filled] = reduce(i64::from(eta) + Q - i64::from(value));
+                filled += 1;
+            }
+        }
+    }
+    a
+}
+
+/// Appends `values` as `bits`-wide little-endian bit fields.
+fn pack_bits(values: impl IntoIterator<Item = u32>, bits: u32, out: &mut Vec<u8>) {
+    let mut buffer = 0u64;
+    let mut buffered = 0;
+    for value in values {
+        buffer |= u64::from(value) << buffered;
+        buffered += bits;
+        while buffered >= 8 {
+            out.push(buffer as u8);
+            buffer >>= 8;
+            buffered -= 8;
+        }
+    }
+}
+
+/// Returns the encoded `(pk, sk)` for `seed` in the parameter set whose
+/// secret keys are `secret_key_len` bytes.
+pub(super) fn generate(
+    seed: &[u8; SEED_BYTES],
+    secret_key_len: usize,
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let params = Params::for_secret_key_len(secret_key_len).ok_or_else(|| {
+        QraiopError::KeyGenerationFailed("Unknown ML-DSA parameter set".to_string())
+    })?;
+    let mut seeds = Zeroizing::new([0u8; 2 * SEED_BYTES + CRH_BYTES]);
+    let mut shake = Shake256::default();
+    shake.update(seed);
+    shake.update(&[params.k as u8, params.l as u8]);
+    shake.finalize_xof().read(seeds.as_mut());
+    let (rho, rest) = seeds.split_at(SEED_BYTES);
+    let (rho_prime, key) = rest.split_at(CRH_BYTES);
+
+    let s1: Zeroizing<Vec<Poly>> = Zeroizing::new(
+        (0..params.l)
+            .map(|j| sample_eta(rho_prime, j as u16, params.eta))
+            .collect(),
+    );
+    let s2: Zeroizing<Vec<Poly>> = Zeroizing::new(
+        (0..params.k)
+            .map(|i| sample_eta(rho_prime, (params.l + i) as u16, params.eta))
+            .collect(),
+    );
+    let zetas = zetas();
+    let mut s1_hat = s1.clone();
+    for s in s1_hat.iter_mut() {
+        ntt(s, &zetas);
+    }
+
+    let mut pk = Vec::with_capacity(SEED_BYTES + params.k * POLY_T1_BYTES);
+    pk.extend_from_slice(rho);
+    let mut t0 = Zeroizing::new(Vec::with_capacity(params.k * N));
+    for (i, s2) in s2.iter().enumerate() {
+        let mut t = Zeroizing::new([0; N]);
+        for (j, s) in s1_hat.iter().enumerate() {
+            let a = expand_a(rho, i, j);
+            for ((t, a), s) in t.iter_mut().zip(a.iter()).zip(s.iter()) {
+                *t = reduce(*t + a * s);
+            }
+        }
+        inverse_ntt(&mut t, &zetas);
+
+        let mut t1 = [0u16; N];
+        for ((t1, t), s2) in t1.iter_mut().zip(t.iter()).zip(s2.iter()) {
+            let t = reduce(t + s2);
+            let high = (t + (1 << (D - 1)) - 1) >> D;
+            *t1 = high as u16;
+            // t0 = t - t1 * 2^D lies in (-2^(D-1), 2^(D-1)] and is stored
+            // as 2^(D-1) - t0
+            t0.push(((1 << (D - 1)) - (t - (high << D))) as u32);
+        }
+        pack_t1(&t1, &mut pk);
+    }
+
+    let mut tr = [0u8; TR_BYTES];
+    let mut shake = Shake256::default();
+    shake.update(&pk);
+    shake.finalize_xof().read(&mut tr);
+
+    let eta = i64::from(params.eta);
+    let mut sk = Vec::with_capacity(params.secret_key_len());
+    sk.extend_from_slice(rho);
+    sk.extend_from_slice(key);
+    sk.extend_from_slice(&tr);
+    for s in s1.iter().chain(s2.iter()) {
+        // Stored as eta - c
+        let encoded = s.iter().map(|&c| reduce(eta + Q - c) as u32);
+        pack_bits(encoded, params.eta_bits() as u32, &mut sk);
+    }
+    pack_bits(t0.iter().copied(), D, &mut sk);
+    Ok((pk, sk))
+}
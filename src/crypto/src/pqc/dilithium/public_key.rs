@@ -0,0 +1,252 @@
+//! Recomputes an ML-DSA public key from the expanded secret key.
+//!
+//! The secret key carries `rho`, `s1`, `s2` and `tr = H(pk)` but not `t1`,
+//! so the public key has to be rebuilt as in key generation:
+//! `t = A * s1 + s2`, `t1 = Power2Round(t)`, `pk = rho || t1`. This is the
+//! plain-arithmetic counterpart of the reference implementation (no
+//! Montgomery form) and runs only on key loading, so speed is not a concern.
+//! Everything that touches `s1` or `s2` reduces with [`reduce`] rather than
+//! `%`, so the arithmetic has no division and no branch on secret values.
+//! The recomputed key is checked against `tr`, which catches corrupted or
+//! mismatched keys.
+
+use crate::{QraiopError, Result};
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Shake128, Shake256};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
+
+pub(super) const N: usize = 256;
+pub(super) const Q: i64 = 8_380_417;
+/// Bits dropped from `t` by Power2Round.
+pub(super) const D: u32 = 13;
+/// Primitive 512th root of unity modulo `Q`.
+const ROOT_OF_UNITY: i64 = 1753;
+pub(super) const SEED_BYTES: usize = 32;
+pub(super) const TR_BYTES: usize = 64;
+const POLY_T0_BYTES: usize = 416;
+pub(super) const POLY_T1_BYTES: usize = 320;
+
+pub(super) type Poly = [i64; N];
+
+/// Dimensions of one parameter set: `A` is `k x l`, and the coefficients of
+/// `s1` and `s2` lie in `[-eta, eta]`.
+pub(super) struct Params {
+    pub(super) k: usize,
+    pub(super) l: usize,
+    pub(super) eta: u32,
+}
+
+impl Params {
+    pub(super) fn for_secret_key_len(len: usize) -> Option<Self> {
+        [(4, 4, 2), (6, 5, 4), (8, 7, 2)]
+            .into_iter()
+            .map(|(k, l, eta)| Params { k, l, eta })
+            .find(|p| p.secret_key_len() == len)
+    }
+
+    pub(super) fn eta_bits(&self) -> usize {
+        if self.eta == 2 {
+            3
+        } else {
+            4
+        }
+    }
+
+    fn poly_eta_bytes(&self) -> usize {
+        N * self.eta_bits() / 8
+    }
+
+    pub(super) fn secret_key_len(&self) -> usize {
+        2 * SEED_BYTES
+            + TR_BYTES
+            + (self.l + self.k) * self.poly_eta_bytes()
+            + self.k * POLY_T0_BYTES
+    }
+}
+
+/// `floor(2^64 / Q)`, the Barrett constant for [`reduce`].
+const BARRETT: i128 = (1 << 64) / Q as i128;
+
+/// `a mod Q` for `0 <= a < 2^62`, by Barrett reduction and a masked final
+/// subtraction instead of a division or a branch on `a`.
+pub(super) fn reduce(a: i64) -> i64 {
+    // The quotient is exact or one short, leaving a value in [0, 2Q)
+    let quotient = ((i128::from(a) * BARRETT) >> 64) as i64;
+    let r = a - quotient * Q - Q;
+    r + ((r >> 63) & Q)
+}
+
+fn pow_mod(mut base: i64, mut exp: u32) -> i64 {
+    let mut result = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % Q;
+        }
+        base = base * base % Q;
+        exp >>= 1;
+    }
+    result
+}
+
+/// `ROOT_OF_UNITY^brv8(i)`, in the order the reference NTT consumes them.
+pub(super) fn zetas() -> Poly {
+    let mut zetas = [0; N];
+    for (i, zeta) in zetas.iter_mut().enumerate() {
+        *zeta = pow_mod(ROOT_OF_UNITY, u32::from((i as u8).reverse_bits()));
+    }
+    zetas
+}
+
+pub(super) fn ntt(a: &mut Poly, zetas: &Poly) {
+    let mut k = 0;
+    let mut len = 128;
+    while len > 0 {
+        for start in (0..N).step_by(2 * len) {
+            k += 1;
+            let zeta = zetas[k];
+            for j in start..start + len {
+                let t = reduce(zeta * a[j + len]);
+                a[j + len] = reduce(a[j] + Q - t);
+                a[j] = reduce(a[j] + t);
+            }
+        }
+        len >>= 1;
+    }
+}
+
+pub(super) fn inverse_ntt(a: &mut Poly, zetas: &Poly) {
+    let mut k = N;
+    let mut len = 1;
+    while len < N {
+        for start in (0..N).step_by(2 * len) {
+            k -= 1;
+            let zeta = Q - zetas[k];
+            for j in start..start + len {
+                let t = a[j];
+                a[j] = reduce(t + a[j + len]);
+                a[j + len] = reduce(zeta * reduce(t + Q - a[j + len]));
+            }
+        }
+        len <<= 1;
+    }
+    let n_inv = pow_mod(N as i64, (Q - 2) as u32);
+    for coefficient in a.iter_mut() {
+        *coefficient = reduce(*coefficient * n_inv);
+    }
+}
+
+/// ExpandA entry `A[i][j]`, sampled directly in the NTT domain by rejection
+/// from SHAKE128(rho || j || i).
+pub(super) fn expand_a(rho: &[u8], i: usize, j: usize) -> Poly {
+    let mut shake = Shake128::default();
+    shake.update(rho);
+    shake.update(&[j as u8, i as u8]);
+    let mut reader = shake.finalize_xof();
+
+    let mut a = [0; N];
+    let mut filled = 0;
+    let mut bytes = [0u8; 3];
+    while filled < N {
+        reader.read(&mut bytes);
+        let t = (i64::from(bytes[0]) | i64::from(bytes[1]) << 8 | i64::from(bytes[2]) << 16)
+            & 0x7f_ffff;
+        if t < Q {
+            a[filled] = t;
+            filled += 1;
+        }
+    }
+    a
+}
+
+/// Unpacks a polynomial with coefficients in `[-eta, eta]`, stored as
+/// `eta - c` in little-endian bit fields, reduced into `[0, Q)`.
+fn unpack_eta(bytes: &[u8], params: &Params) -> Result<Poly> {
+    let bits = params.eta_bits();
+    let mask = (1u32 << bits) - 1;
+    let mut a = [0; N];
+    for (i, coefficient) in a.iter_mut().enumerate() {
+        let bit = i * bits;
+        let window =
+            u32::from(bytes[bit / 8]) | bytes.get(bit / 8 + 1).map_or(0, |&b| u32::from(b) << 8);
+        let value = (window >> (bit % 8)) & mask;
+        if value > 2 * params.eta {
+            return Err(QraiopError::InvalidKey(
+                "ML-DSA secret key has a coefficient out of range".to_string(),
+            ));
+        }
+        *coefficient = reduce(i64::from(params.eta) + Q - i64::from(value));
+    }
+    Ok(a)
+}
+
+/// Packs the 10-bit high parts of `t`, four coefficients per five bytes.
+pub(super) fn pack_t1(t1: &[u16; N], out: &mut Vec<u8>) {
+    for c in t1.chunks_exact(4) {
+        out.push(c[0] as u8);
+        out.push((c[0] >> 8 | c[1] << 2) as u8);
+        out.push((c[1] >> 6 | c[2] << 4) as u8);
+        out.push((c[2] >> 4 | c[3] << 6) as u8);
+        out.push((c[3] >> 2) as u8);
+    }
+}
+
+/// Returns the encoded public key for the expanded secret key `sk`, or
+/// `InvalidKey` if `sk` is malformed or its `tr` does not match the result.
+pub(super) fn derive(sk: &[u8]) -> Result<Vec<u8>> {
+    let params = Params::for_secret_key_len(sk.len()).ok_or_else(|| {
+        QraiopError::InvalidKey("ML-DSA secret key has an unexpected length".to_string())
+    })?;
+    let rho = &sk[..SEED_BYTES];
+    let tr = &sk[2 * SEED_BYTES..2 * SEED_BYTES + TR_BYTES];
+    let eta_bytes = params.poly_eta_bytes();
+    let mut secrets = sk[2 * SEED_BYTES + TR_BYTES..]
+        .chunks_exact(eta_bytes)
+        .take(params.l + params.k)
+        .map(|bytes| unpack_eta(bytes, &params));
+
+    let zetas = zetas();
+    let mut s1_hat = Zeroizing::new(Vec::with_capacity(params.l));
+    for s in secrets.by_ref().take(params.l) {
+        let mut s = s?;
+        ntt(&mut s, &zetas);
+        s1_hat.push(s);
+    }
+
+    let mut pk = Vec::with_capacity(SEED_BYTES + params.k * POLY_T1_BYTES);
+    pk.extend_from_slice(rho);
+    for i in 0..params.k {
+        let mut t = Zeroizing::new([0; N]);
+        for (j, s) in s1_hat.iter().enumerate() {
+            let a = expand_a(rho, i, j);
+            for ((t, a), s) in t.iter_mut().zip(a.iter()).zip(s.iter()) {
+                *t = reduce(*t + a * s);
+            }
+        }
+        inverse_ntt(&mut t, &zetas);
+
+        let s2 = Zeroizing::new(secrets.next().ok_or_else(|| {
+            QraiopError::InvalidKey("ML-DSA secret key is truncated".to_string())
+        })??);
+        let mut t1 = [0u16; N];
+        for ((t1, t), s2) in t1.iter_mut().zip(t.iter()).zip(s2.iter()) {
+            let t = reduce(t + s2);
+            *t1 = ((t + (1 << (D - 1)) - 1) >> D) as u16;
+        }
+        pack_t1(&t1, &mut pk);
+    }
+
+    let mut expected_tr = [0u8; TR_BYTES];
+    let mut shake = Shake256::default();
+    shake.update(&pk);
+    shake.finalize_xof().read(&mut expected_tr);
+    if !bool::from(expected_tr.ct_eq(tr)) {
+        return Err(QraiopError::InvalidKey(
+            "ML-DSA secret key is inconsistent: tr does not match the derived public key"
+                .to_string(),
+        ));
+    }
+    Ok(pk)
+}
@@ -0,0 +1,64 @@
+//! Canonical form of an ML-DSA signature encoding.
+//!
+//! A signature is `c~ || z || h`. Every bit pattern of `c~` and `z` decodes
+//! to a different value, so those parts cannot be re-encoded differently.
+//! The hint `h` can: it lists, for each of the `k` rows, the positions of
+//! its set bits in `omega` bytes, followed by `k` bytes of running counts.
+//! Only one listing is canonical, with each row's positions strictly
+//! increasing and every unused position byte zero.
+//!
+//! ```text
+//! h = positions[0..omega] || counts[0..k]
+//!     row i is positions[counts[i - 1]..counts[i]], counts[-1] = 0
+//! ```
+
+use super::public_key::N;
+use alloc::vec;
+
+/// `(k, omega)` of the parameter set with signatures of `len` bytes: the
+/// rows of the hint and the most bits it may set.
+fn hint_params(len: usize) -> Option<(usize, usize)> {
+    use crate::utils::key_sizes::{ml_dsa_44, ml_dsa_65, ml_dsa_87};
+    match len {
+        ml_dsa_44::SIGNATURE_SIZE => Some((4, 80)),
+        ml_dsa_65::SIGNATURE_SIZE => Some((6, 55)),
+        ml_dsa_87::SIGNATURE_SIZE => Some((8, 75)),
+        _ => None,
+    }
+}
+
+/// Whether the hint of `signature` is in canonical form. Decodes it as
+/// loosely as possible, re-encodes the decoded bits and compares; a hint
+/// that cannot be decoded at all is not canonical either.
+pub(super) fn is_canonical(signature: &[u8]) -> bool {
+    let Some((k, omega)) = hint_params(signature.len()) else {
+        return false;
+    };
+    let hint = &signature[signature.len() - omega - k..];
+    let (positions, counts) = hint.split_at(omega);
+
+    let mut rows = vec![[false; N]; k];
+    let mut start = 0;
+    for (row, &end) in rows.iter_mut().zip(counts) {
+        let end = usize::from(end);
+        if end < start || end > omega {
+            return false;
+        }
+        for &position in &positions[start..end] {
+            row[usize::from(position)] = true;
+        }
+        start = end;
+    }
+
+    // Never more bits than listed positions, so this stays within omega
+    let mut canonical = vec![0u8; omega + k];
+    let mut filled = 0;
+    for (i, row) in rows.iter().enumerate() {
+        for position in (0..N).filter(|&j| row[j]) {
+            canonical[filled] = position as u8;
+            filled += 1;
+        }
+        canonical[omega + i] = filled as u8;
+    }
+    canonical == hint
+}
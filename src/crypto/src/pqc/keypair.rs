@@ -0,0 +1,147 @@
+//! Key pairs kept together as one value
+//!
+//! `keypair()` returns a `(PublicKey, SecretKey)` tuple, which is easy to
+//! destructure in the wrong order once the two types coincide, as they do
+//! for byte-oriented APIs. [`KeyPair`] and [`SigningKeyPair`] name the
+//! halves instead.
+//!
+//! Serializing a pair writes only its public key, so a pair can be logged
+//! or sent as a peer's identity without leaking the secret. With the
+//! `serde_secret` feature the secret key is written too, and the pair can
+//! be deserialized; this needs a secret key type that supports serde.
+
+use super::{DigitalSignature, KeyEncapsulation};
+use crate::Result;
+use core::fmt;
+#[cfg(feature = "serde_secret")]
+use serde::Deserialize;
+use serde::Serialize;
+
+macro_rules! key_pair {
+    ($(#[$meta:meta])* $name:ident, $scheme:ident) => {
+        $(#[$meta])*
+        #[derive(Serialize)]
+        #[cfg_attr(feature = "serde_secret", derive(Deserialize))]
+        pub struct $name<A: $scheme> {
+            public_key: A::PublicKey,
+            #[cfg_attr(not(feature = "serde_secret"), serde(skip))]
+            secret_key: A::SecretKey,
+        }
+
+        impl<A: $scheme> $name<A> {
+            /// Generates a fresh key pair.
+            pub fn generate() -> Result<Self> {
+                let (public_key, secret_key) = A::keypair()?;
+                Ok(Self {
+                    public_key,
+                    secret_key,
+                })
+            }
+
+            /// Pairs keys generated elsewhere. Nothing checks that they
+            /// belong together.
+            pub fn from_parts(public_key: A::PublicKey, secret_key: A::SecretKey) -> Self {
+                Self {
+                    public_key,
+                    secret_key,
+                }
+            }
+
+            pub fn public(&self) -> &A::PublicKey {
+                &self.public_key
+            }
+
+            pub fn secret(&self) -> &A::SecretKey {
+                &self.secret_key
+            }
+
+            /// Splits the pair into `(public_key, secret_key)`.
+            pub fn into_parts(self) -> (A::PublicKey, A::SecretKey) {
+                (self.public_key, self.secret_key)
+            }
+        }
+
+        // Written out to avoid the `A: Clone` bound a derive would add
+        impl<A: $scheme> Clone for $name<A>
+        where
+            A::PublicKey: Clone,
+            A::SecretKey: Clone,
+        {
+            fn clone(&self) -> Self {
+                Self {
+                    public_key: self.public_key.clone(),
+                    secret_key: self.secret_key.clone(),
+                }
+            }
+        }
+
+        // Leaves formatting the secret key to its own `Debug`, which redacts
+        impl<A: $scheme> fmt::Debug for $name<A>
+        where
+            A::PublicKey: fmt::Debug,
+            A::SecretKey: fmt::Debug,
+        {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("public_key", &self.public_key)
+                    .field("secret_key", &self.secret_key)
+                    .finish()
+            }
+        }
+    };
+}
+
+key_pair!(
+    /// A KEM public key with its secret key, e.g. `KeyPair<MlKem768>`.
+    KeyPair,
+    KeyEncapsulation
+);
+key_pair!(
+    /// A signature public key with its secret key, e.g.
+    /// `SigningKeyPair<MlDsa65>`.
+    SigningKeyPair,
+    DigitalSignature
+);
+
+#[cfg(all(test, feature = "kyber", feature = "dilithium"))]
+mod tests {
+    use super::*;
+    use crate::pqc::dilithium::MlDsa65;
+    use crate::pqc::kyber::MlKem768;
+
+    #[test]
+    fn test_key_pair_roundtrip() {
+        let pair = KeyPair::<MlKem768>::generate().unwrap();
+        let (ct, ss) = MlKem768::encapsulate(pair.public()).unwrap();
+        assert_eq!(MlKem768::decapsulate(pair.secret(), &ct).unwrap(), ss);
+        let (pk, sk) = pair.clone().into_parts();
+        assert_eq!(&pk, pair.public());
+        assert_eq!(sk.as_bytes(), pair.secret().as_bytes());
+
+        let signing = SigningKeyPair::<MlDsa65>::generate().unwrap();
+        let signature = MlDsa65::sign(signing.secret(), b"message").unwrap();
+        assert!(MlDsa65::verify(signing.public(), b"message", &signature).unwrap());
+        assert!(format!("{:?}", signing).contains("REDACTED"));
+    }
+
+    #[cfg(not(feature = "serde_secret"))]
+    #[test]
+    fn test_key_pair_serializes_public_key_only() {
+        let pair = KeyPair::<MlKem768>::generate().unwrap();
+        let json = serde_json::to_value(&pair).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "public_key": serde_json::to_value(pair.public()).unwrap() })
+        );
+    }
+
+    #[cfg(feature = "serde_secret")]
+    #[test]
+    fn test_key_pair_serde_secret() {
+        let pair = KeyPair::<MlKem768>::generate().unwrap();
+        let decoded: KeyPair<MlKem768> =
+            bincode::deserialize(&bincode::serialize(&pair).unwrap()).unwrap();
+        assert_eq!(decoded.public(), pair.public());
+        assert_eq!(decoded.secret().as_bytes(), pair.secret().as_bytes());
+    }
+}
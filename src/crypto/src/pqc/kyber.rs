@@ -1,61 +1,1819 @@
-//! ML-KEM implementation based on CRYSTALS-Kyber
-
-use crate::pqc::KeyEncapsulation;
-use crate::Result;
-
-// Concrete types - direct imports
-use pqcrypto_kyber::kyber512::{
-    decapsulate, encapsulate, keypair, Ciphertext, PublicKey, SecretKey, SharedSecret,
-};
-
-pub struct MlKem512;
-
-impl KeyEncapsulation for MlKem512 {
-    type PublicKey = PublicKey;
-    type SecretKey = SecretKey;
-    type Ciphertext = Ciphertext;
-    type SharedSecret = SharedSecret;
-
-    fn keypair() -> Result<(Self::PublicKey, Self::SecretKey)> {
-        Ok(keypair())
-    }
-
-    fn encapsulate(public_key: &Self::PublicKey) -> Result<(Self::Ciphertext, Self::SharedSecret)> {
-        let (shared_secret, ciphertext) = encapsulate(public_key);
-        Ok((ciphertext, shared_secret))
-    }
-
-    fn decapsulate(
-        secret_key: &Self::SecretKey,
-        ciphertext: &Self::Ciphertext,
-    ) -> Result<Self::SharedSecret> {
-        Ok(decapsulate(ciphertext, secret_key))
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_ml_kem_512_roundtrip() {
-        let (pk, sk) = MlKem512::keypair().unwrap();
-        let (ct, ss1) = MlKem512::encapsulate(&pk).unwrap();
-        let ss2 = MlKem512::decapsulate(&sk, &ct).unwrap();
-
-        // Compare shared secrets using constant-time comparison
-        let ss1_ptr = &ss1 as *const SharedSecret as *const u8;
-        let ss2_ptr = &ss2 as *const SharedSecret as *const u8;
-        let len = std::mem::size_of::<SharedSecret>();
-
-        let equal = unsafe {
-            libc::memcmp(
-                ss1_ptr as *const libc::c_void,
-                ss2_ptr as *const libc::c_void,
-                len,
-            ) == 0
-        };
-
-        assert!(equal, "Shared secrets should match");
-    }
-}
+//! ML-KEM implementation based on CRYSTALS-Kyber
+
+use crate::pqc::KeyEncapsulation;
+use crate::utils::key_sizes::{ml_kem_1024, ml_kem_512, ml_kem_768};
+use crate::utils::secret::Secret;
+use crate::utils::{self, passphrase};
+use crate::{QraiopError, Result};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+use pqcrypto_mlkem::{mlkem1024, mlkem512, mlkem768};
+use pqcrypto_traits::kem::{Ciphertext as _, PublicKey as _, SecretKey as _, SharedSecret as _};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+const PUBLIC_KEY_SIZES: [usize; 3] = [
+    ml_kem_512::PUBLIC_KEY_SIZE,
+    ml_kem_768::PUBLIC_KEY_SIZE,
+    ml_kem_1024::PUBLIC_KEY_SIZE,
+];
+const SECRET_KEY_SIZES: [usize; 3] = [
+    ml_kem_512::SECRET_KEY_SIZE,
+    ml_kem_768::SECRET_KEY_SIZE,
+    ml_kem_1024::SECRET_KEY_SIZE,
+];
+const CIPHERTEXT_SIZES: [usize; 3] = [
+    ml_kem_512::CIPHERTEXT_SIZE,
+    ml_kem_768::CIPHERTEXT_SIZE,
+    ml_kem_1024::CIPHERTEXT_SIZE,
+];
+const SHARED_SECRET_SIZES: [usize; 3] = [
+    ml_kem_512::SHARED_SECRET_SIZE,
+    ml_kem_768::SHARED_SECRET_SIZE,
+    ml_kem_1024::SHARED_SECRET_SIZE,
+];
+
+fn check_length(
+    what: &str,
+    actual: usize,
+    valid: &[usize],
+    error: fn(String) -> QraiopError,
+) -> Result<()> {
+    if valid.contains(&actual) {
+        Ok(())
+    } else {
+        Err(error(format!(
+            "{} must be one of {:?} bytes, got {}",
+            what, valid, actual
+        )))
+    }
+}
+
+/// The ML-KEM modulus
+const Q: u16 = 3329;
+
+/// FIPS 203 encapsulation key check: every 12-bit coefficient of `t_hat`,
+/// which is all of `ek` but the trailing 32-byte `rho`, must be below `Q`.
+fn check_modulus(ek: &[u8]) -> Result<()> {
+    let t_hat = &ek[..ek.len() - 32];
+    for (i, chunk) in t_hat.chunks_exact(3).enumerate() {
+        let a = u16::from(chunk[0]) | (u16::from(chunk[1] & 0x0f) << 8);
+        let b = u16::from(chunk[1] >> 4) | (u16::from(chunk[2]) << 4);
+        if a >= Q || b >= Q {
+            return Err(QraiopError::InvalidKey(format!(
+                "ML-KEM public key coefficient {} is not reduced modulo {}",
+                if a >= Q { 2 * i } else { 2 * i + 1 },
+                Q
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// ML-KEM encapsulation (public) key.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "Vec<u8>")]
+pub struct PublicKey(Vec<u8>);
+
+impl PublicKey {
+    /// Parses a raw FIPS 203 encapsulation key of any ML-KEM parameter set.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_length(
+            "ML-KEM public key",
+            bytes.len(),
+            &PUBLIC_KEY_SIZES,
+            QraiopError::InvalidKey,
+        )?;
+        Ok(Self(bytes.to_vec()))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Runs the FIPS 203 encapsulation key check: the length must match a
+    /// parameter set and every encoded coefficient must be reduced modulo
+    /// `q`. Run this on keys received from elsewhere before encapsulating
+    /// to them. Fails with [`QraiopError::InvalidKey`].
+    pub fn validate(&self) -> Result<()> {
+        check_length(
+            "ML-KEM public key",
+            self.0.len(),
+            &PUBLIC_KEY_SIZES,
+            QraiopError::InvalidKey,
+        )?;
+        check_modulus(&self.0)
+    }
+
+    /// Short fingerprint for display and pinning: the first 8 bytes of
+    /// SHA3-256 over the key, as colon-separated hex.
+    pub fn fingerprint(&self) -> String {
+        utils::fingerprint(&self.0)
+    }
+
+    /// Full SHA3-256 digest of the key.
+    pub fn fingerprint_full(&self) -> [u8; 32] {
+        utils::fingerprint_full(&self.0)
+    }
+
+    /// Borrows the key as a [`PublicKeyRef`].
+    pub fn as_ref_key(&self) -> PublicKeyRef<'_> {
+        PublicKeyRef(&self.0)
+    }
+
+    /// Parameter set, inferred from the key length.
+    fn algorithm_name(&self) -> &'static str {
+        match self.0.len() {
+            ml_kem_512::PUBLIC_KEY_SIZE => "ML-KEM-512",
+            ml_kem_768::PUBLIC_KEY_SIZE => "ML-KEM-768",
+            ml_kem_1024::PUBLIC_KEY_SIZE => "ML-KEM-1024",
+            _ => "ML-KEM",
+        }
+    }
+}
+
+impl TryFrom<Vec<u8>> for PublicKey {
+    type Error = QraiopError;
+
+    /// Like [`PublicKey::from_bytes`], but takes ownership instead of copying.
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        check_length(
+            "ML-KEM public key",
+            bytes.len(),
+            &PUBLIC_KEY_SIZES,
+            QraiopError::InvalidKey,
+        )?;
+        Ok(Self(bytes))
+    }
+}
+
+impl From<PublicKey> for Vec<u8> {
+    fn from(value: PublicKey) -> Self {
+        value.0
+    }
+}
+
+/// Algorithm and short fingerprint, e.g. `ML-KEM-768 pk:3f:a0:1c:77:09:be:42:d5`.
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} pk:{}", self.algorithm_name(), self.fingerprint())
+    }
+}
+
+// Short like `Display` rather than a dump of a multi-kilobyte key.
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PublicKey({})", self)
+    }
+}
+
+/// Borrowed ML-KEM encapsulation key, for encapsulating to keys that live
+/// in a larger buffer, such as a memory-mapped key list, without copying
+/// each into a [`PublicKey`].
+///
+/// The `*_with_rng` and `*_into` encapsulation functions of each parameter
+/// set accept it in place of a `&PublicKey`. Deserializing one borrows from
+/// the input, which only works for formats that store bytes contiguously,
+/// such as bincode; the encoding is the same as [`PublicKey`]'s there.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct PublicKeyRef<'a>(#[serde(borrow)] &'a [u8]);
+
+impl<'a> PublicKeyRef<'a> {
+    /// Checks that `bytes` has the length of an ML-KEM encapsulation key
+    /// and borrows it.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self> {
+        check_length(
+            "ML-KEM public key",
+            bytes.len(),
+            &PUBLIC_KEY_SIZES,
+            QraiopError::InvalidKey,
+        )?;
+        Ok(Self(bytes))
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+
+    /// The FIPS 203 encapsulation key check; see [`PublicKey::validate`].
+    pub fn validate(&self) -> Result<()> {
+        check_length(
+            "ML-KEM public key",
+            self.0.len(),
+            &PUBLIC_KEY_SIZES,
+            QraiopError::InvalidKey,
+        )?;
+        check_modulus(self.0)
+    }
+
+    /// Copies the key into an owned [`PublicKey`].
+    pub fn to_owned_key(&self) -> PublicKey {
+        PublicKey(self.0.to_vec())
+    }
+}
+
+impl<'a> From<&'a PublicKey> for PublicKeyRef<'a> {
+    fn from(public_key: &'a PublicKey) -> Self {
+        public_key.as_ref_key()
+    }
+}
+
+impl fmt::Debug for PublicKeyRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PublicKeyRef({})", utils::fingerprint(self.0))
+    }
+}
+
+/// ML-KEM decapsulation (secret) key, wiped from memory on drop.
+///
+/// With the `serde_secret` feature this also implements `Serialize` and
+/// `Deserialize`, which write the raw key in the clear. Only enable it when
+/// the serialized form is itself protected; otherwise store keys with
+/// [`SecretKey::to_encrypted_bytes`].
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop)]
+#[cfg_attr(feature = "serde_secret", derive(Serialize, Deserialize))]
+pub struct SecretKey(Secret);
+
+impl SecretKey {
+    /// Parses a raw FIPS 203 decapsulation key of any ML-KEM parameter set.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_length(
+            "ML-KEM secret key",
+            bytes.len(),
+            &SECRET_KEY_SIZES,
+            QraiopError::InvalidKey,
+        )?;
+        Ok(Self(bytes.into()))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.expose_secret()
+    }
+
+    /// Copies the key out into a buffer that is wiped when dropped, unlike
+    /// a copy of [`Self::as_bytes`].
+    pub fn to_zeroizing_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(self.0.expose_secret().to_vec())
+    }
+
+    /// Loads a hex- or base64-encoded decapsulation key from the
+    /// environment variable `name`, failing with
+    /// [`QraiopError::InvalidKey`] if it is unset, undecodable or the wrong
+    /// length. Read [`utils::secret_from_env`] on the risks first.
+    #[cfg(feature = "std")]
+    pub fn from_env(name: &str) -> Result<Self> {
+        Self::from_bytes(&utils::secret_from_env(name)?)
+    }
+
+    /// Runs the FIPS 203 decapsulation key check: the length must match a
+    /// parameter set and the embedded hash `H(ek)` must match the embedded
+    /// encapsulation key. Run this on keys loaded from outside storage
+    /// before decapsulating with them. Fails with
+    /// [`QraiopError::InvalidKey`].
+    pub fn validate(&self) -> Result<()> {
+        check_length(
+            "ML-KEM secret key",
+            self.0.len(),
+            &SECRET_KEY_SIZES,
+            QraiopError::InvalidKey,
+        )?;
+        // dk = dk_pke || ek || H(ek) || z, with |dk_pke| = 384k and
+        // |ek| = 384k + 32
+        let k384 = (self.0.len() - 96) / 2;
+        let ek = &self.0.expose_secret()[k384..2 * k384 + 32];
+        let hash = &self.0.expose_secret()[2 * k384 + 32..2 * k384 + 64];
+        let digest = Sha3_256::digest(ek);
+        if !bool::from(digest.as_slice().ct_eq(hash)) {
+            return Err(QraiopError::InvalidKey(
+                "ML-KEM secret key hash check failed: H(ek) does not match".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Extracts the encapsulation key embedded in this decapsulation key,
+    /// after running [`Self::validate`] and the public key check on it.
+    pub fn public_key(&self) -> Result<PublicKey> {
+        self.validate()?;
+        let k384 = (self.0.len() - 96) / 2;
+        let public_key = PublicKey(self.0.expose_secret()[k384..2 * k384 + 32].to_vec());
+        public_key.validate()?;
+        Ok(public_key)
+    }
+
+    /// Encrypts the key under `passphrase` for storage, using Argon2id and
+    /// AES-256-GCM as described in [`crate::utils::passphrase`].
+    pub fn to_encrypted_bytes(&self, passphrase: &[u8]) -> Result<Vec<u8>> {
+        passphrase::encrypt(passphrase, self.0.expose_secret())
+    }
+
+    /// Decrypts a key stored with [`Self::to_encrypted_bytes`]. A wrong
+    /// passphrase yields `QraiopError::SerializationError`.
+    pub fn from_encrypted_bytes(data: &[u8], passphrase: &[u8]) -> Result<Self> {
+        let bytes = passphrase::decrypt(passphrase, data)?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+/// Takes ownership of the key bytes, wiping them if the length is wrong.
+///
+/// There is deliberately no conversion back into a plain `Vec<u8>`, which
+/// would escape zeroization; copy out through [`SecretKey::as_bytes`] if
+/// the raw key is really needed.
+impl TryFrom<Vec<u8>> for SecretKey {
+    type Error = QraiopError;
+
+    fn try_from(mut bytes: Vec<u8>) -> Result<Self> {
+        if let Err(error) = check_length(
+            "ML-KEM secret key",
+            bytes.len(),
+            &SECRET_KEY_SIZES,
+            QraiopError::InvalidKey,
+        ) {
+            bytes.zeroize();
+            return Err(error);
+        }
+        Ok(Self(Secret::new(bytes)))
+    }
+}
+
+/// ML-KEM ciphertext.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "Vec<u8>")]
+pub struct Ciphertext(Vec<u8>);
+
+impl Ciphertext {
+    /// Parses a raw FIPS 203 ciphertext of any ML-KEM parameter set.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_length(
+            "ML-KEM ciphertext",
+            bytes.len(),
+            &CIPHERTEXT_SIZES,
+            QraiopError::InvalidCiphertext,
+        )?;
+        Ok(Self(bytes.to_vec()))
+    }
+
+    /// Like [`Self::from_bytes`], but takes ownership of `bytes` instead of
+    /// copying them.
+    pub fn from_vec(bytes: Vec<u8>) -> Result<Self> {
+        check_length(
+            "ML-KEM ciphertext",
+            bytes.len(),
+            &CIPHERTEXT_SIZES,
+            QraiopError::InvalidCiphertext,
+        )?;
+        Ok(Self(bytes))
+    }
+
+    /// [`Self::from_bytes`] for callers that decode every wire value
+    /// strictly, as with `dilithium::Signature::from_bytes_strict`.
+    ///
+    /// An ML-KEM ciphertext of the right length is always canonical: it
+    /// packs compressed coefficients, every bit pattern is a valid
+    /// compressed value, and decompressing then compressing returns it
+    /// unchanged. No two byte strings decode to the same ciphertext, so
+    /// there is nothing further to reject.
+    pub fn from_bytes_strict(bytes: &[u8]) -> Result<Self> {
+        Self::from_bytes(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl TryFrom<Vec<u8>> for Ciphertext {
+    type Error = QraiopError;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self> {
+        Self::from_vec(bytes)
+    }
+}
+
+impl From<Ciphertext> for Vec<u8> {
+    fn from(value: Ciphertext) -> Self {
+        value.0
+    }
+}
+
+/// Shared secret agreed through ML-KEM, wiped from memory on drop.
+///
+/// Every ML-KEM parameter set agrees on a 32-byte secret, so secrets from
+/// different parameter sets compare by content like any other two.
+///
+/// Like [`SecretKey`], serializable in the clear only with the
+/// `serde_secret` feature.
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop)]
+#[cfg_attr(feature = "serde_secret", derive(Serialize, Deserialize))]
+pub struct SharedSecret(Secret);
+
+/// Compares in constant time. Secrets of different lengths cannot both be
+/// ML-KEM secrets, so comparing them is a bug in the caller; debug builds
+/// panic instead of quietly answering `false`.
+impl PartialEq for SharedSecret {
+    fn eq(&self, other: &Self) -> bool {
+        debug_assert_eq!(
+            self.0.len(),
+            other.0.len(),
+            "compared ML-KEM shared secrets of different lengths"
+        );
+        self.0 == other.0
+    }
+}
+
+impl Eq for SharedSecret {}
+
+impl SharedSecret {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_length(
+            "ML-KEM shared secret",
+            bytes.len(),
+            &SHARED_SECRET_SIZES,
+            QraiopError::InvalidKey,
+        )?;
+        Ok(Self(bytes.into()))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.expose_secret()
+    }
+
+    /// Raw shared secret bytes, e.g. to key an external AEAD.
+    ///
+    /// The name is a reminder that these bytes are secret. The returned
+    /// slice borrows from `self`, so the secret is still zeroized when the
+    /// `SharedSecret` is dropped; copies the caller makes are its own
+    /// responsibility.
+    pub fn expose_bytes(&self) -> &[u8] {
+        self.0.expose_secret()
+    }
+
+    /// Copies the secret out into a buffer that is wiped when dropped.
+    pub fn to_zeroizing_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(self.0.expose_secret().to_vec())
+    }
+
+    /// Derives an AES-256 key from the secret and consumes it.
+    ///
+    /// The KEM output is never used as a cipher key directly: it goes
+    /// through HKDF-SHA256 with a label naming the cipher, so a secret that
+    /// ends up keying two ciphers gives each an unrelated key. The secret
+    /// is wiped when it is dropped here.
+    pub fn into_aes256_key(self) -> Result<Zeroizing<[u8; 32]>> {
+        utils::derive_key_32(&self, AES256_KEY_LABEL)
+    }
+
+    /// Like [`into_aes256_key`](Self::into_aes256_key), for a
+    /// ChaCha20-Poly1305 key.
+    pub fn into_chacha_key(self) -> Result<Zeroizing<[u8; 32]>> {
+        utils::derive_key_32(&self, CHACHA_KEY_LABEL)
+    }
+}
+
+const AES256_KEY_LABEL: &[u8] = b"QRAIOP-ML-KEM-key-v1 AES-256";
+const CHACHA_KEY_LABEL: &[u8] = b"QRAIOP-ML-KEM-key-v1 ChaCha20-Poly1305";
+
+impl AsRef<[u8]> for PublicKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for SecretKey {
+    fn as_ref(&self) -> &[u8] {
+        self.0.expose_secret()
+    }
+}
+
+impl AsRef<[u8]> for Ciphertext {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for SharedSecret {
+    fn as_ref(&self) -> &[u8] {
+        self.0.expose_secret()
+    }
+}
+
+fn check_buffer(buffer: &[u8], needed: usize) -> Result<()> {
+    if buffer.len() < needed {
+        return Err(QraiopError::BufferTooSmall {
+            needed,
+            got: buffer.len(),
+        });
+    }
+    Ok(())
+}
+
+// Public values are compared in constant time too, so code that compares
+// them next to secrets cannot leak through an early-exit comparison.
+macro_rules! constant_time_eq {
+    ($($name:ident),*) => {$(
+        impl ConstantTimeEq for $name {
+            fn ct_eq(&self, other: &Self) -> Choice {
+                self.0.ct_eq(&other.0)
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.ct_eq(other).into()
+            }
+        }
+
+        impl Eq for $name {}
+    )*};
+}
+
+constant_time_eq!(PublicKey, Ciphertext);
+
+/// Lets keys index a `HashMap` or `HashSet`. Hashing is not constant time,
+/// which is fine for public material; there is deliberately no `Hash` for
+/// secret keys or shared secrets.
+impl Hash for PublicKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+/// Lexicographic by bytes, for `BTreeSet`s and sorted lists. Like `Hash`
+/// this is not constant time and is only offered for public keys.
+impl Ord for PublicKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl PartialOrd for PublicKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Derandomized entry points exported by the PQClean sources bundled with
+// `pqcrypto-mlkem`. The portable "clean" build is always compiled and yields
+// the same bytes as the AVX2 variant, so these are safe to mix with the
+// backend's randomized functions.
+extern "C" {
+    fn PQCLEAN_MLKEM512_CLEAN_crypto_kem_keypair_derand(
+        pk: *mut u8,
+        sk: *mut u8,
+        coins: *const u8,
+    ) -> i32;
+    fn PQCLEAN_MLKEM512_CLEAN_crypto_kem_enc_derand(
+        ct: *mut u8,
+        ss: *mut u8,
+        pk: *const u8,
+        coins: *const u8,
+    ) -> i32;
+    fn PQCLEAN_MLKEM768_CLEAN_crypto_kem_keypair_derand(
+        pk: *mut u8,
+        sk: *mut u8,
+        coins: *const u8,
+    ) -> i32;
+    fn PQCLEAN_MLKEM768_CLEAN_crypto_kem_enc_derand(
+        ct: *mut u8,
+        ss: *mut u8,
+        pk: *const u8,
+        coins: *const u8,
+    ) -> i32;
+    fn PQCLEAN_MLKEM1024_CLEAN_crypto_kem_keypair_derand(
+        pk: *mut u8,
+        sk: *mut u8,
+        coins: *const u8,
+    ) -> i32;
+    fn PQCLEAN_MLKEM1024_CLEAN_crypto_kem_enc_derand(
+        ct: *mut u8,
+        ss: *mut u8,
+        pk: *const u8,
+        coins: *const u8,
+    ) -> i32;
+}
+
+/// `From` the backend's native type and `TryFrom` a reference back to it.
+/// These tie callers to the exact backend version this crate depends on,
+/// hence the opt-in `pqcrypto_interop` feature.
+#[cfg(feature = "pqcrypto_interop")]
+macro_rules! native_conversions {
+    ($backend:ident, $algorithm:literal, $($ours:ident => $native:ident, $error:ident);*) => {$(
+        impl From<$backend::$native> for $ours {
+            fn from(native: $backend::$native) -> Self {
+                Self(native.as_bytes().into())
+            }
+        }
+
+        impl TryFrom<&$ours> for $backend::$native {
+            type Error = QraiopError;
+
+            fn try_from(value: &$ours) -> Result<Self> {
+                $backend::$native::from_bytes(value.as_bytes())
+                    .map_err(|e| QraiopError::$error(e.to_string()).with_algorithm($algorithm))
+            }
+        }
+    )*};
+}
+
+macro_rules! ml_kem {
+    (
+        $(#[$meta:meta])* $name:ident,
+        $backend:ident,
+        $sizes:ident,
+        $algorithm:literal,
+        $oid:literal,
+        $keypair_derand:ident,
+        $enc_derand:ident
+    ) => {
+        $(#[$meta])*
+        pub struct $name;
+
+        impl $name {
+            /// Deterministically derives a keypair from a 64-byte seed.
+            ///
+            /// The seed is split as `d || z`: `seed[..32]` is `d`, the seed
+            /// expanded into the matrix and secret vectors, and `seed[32..]` is
+            /// `z`, the implicit-rejection value stored at the end of the secret
+            /// key. The same seed always produces the same `(pk, sk)`, so it must
+            /// be kept as secret as the secret key itself.
+            pub fn keypair_from_seed(seed: &[u8; 64]) -> Result<(PublicKey, SecretKey)> {
+                let mut pk = vec![0u8; $sizes::PUBLIC_KEY_SIZE];
+                let mut sk = vec![0u8; $sizes::SECRET_KEY_SIZE];
+                // SAFETY: the buffers are sized per the parameter set and the
+                // seed is exactly the 2 * 32 bytes the C routine reads.
+                let status =
+                    unsafe { $keypair_derand(pk.as_mut_ptr(), sk.as_mut_ptr(), seed.as_ptr()) };
+                if status != 0 {
+                    sk.zeroize();
+                    return Err(QraiopError::KeyGenerationFailed(format!(
+                        "Deterministic key generation failed with status {}",
+                        status
+                    ))
+                    .with_algorithm($algorithm));
+                }
+                Ok((PublicKey(pk), SecretKey(Secret::new(sk))))
+            }
+
+            /// Generates a key pair with all randomness drawn from `rng`.
+            ///
+            /// Draws the 64-byte `d || z` seed from `rng` and expands it with
+            /// [`Self::keypair_from_seed`], so a seeded RNG reproduces the
+            /// same sequence of keys. [`KeyEncapsulation::keypair`] is this
+            /// with `OsRng`. If `rng` fails, this returns
+            /// [`QraiopError::KeyGenerationFailed`] instead of panicking.
+            pub fn keypair_with_rng<R: RngCore + CryptoRng>(
+                rng: &mut R,
+            ) -> Result<(PublicKey, SecretKey)> {
+                let mut seed = [0u8; 64];
+                let keypair = utils::keygen_seed(rng, &mut seed)
+                    .map_err(|e| e.with_algorithm($algorithm))
+                    .and_then(|()| Self::keypair_from_seed(&seed));
+                seed.zeroize();
+                keypair
+            }
+
+            /// Encapsulates with the 32-byte message `m` drawn from `rng`.
+            ///
+            /// Takes a [`PublicKey`] or a borrowed [`PublicKeyRef`], so keys
+            /// parsed out of a larger buffer need not be copied first. If
+            /// `rng` fails, this returns [`QraiopError::EncapsulationFailed`]
+            /// instead of panicking.
+            pub fn encapsulate_with_rng<'a, R: RngCore + CryptoRng>(
+                public_key: impl Into<PublicKeyRef<'a>>,
+                rng: &mut R,
+            ) -> Result<(Ciphertext, SharedSecret)> {
+                let mut m = [0u8; 32];
+                let encapsulated =
+                    utils::fill_random(rng, &mut m, QraiopError::EncapsulationFailed)
+                        .map_err(|e| e.with_algorithm($algorithm))
+                        .and_then(|()| Self::encapsulate_bytes(public_key.into().0, &m));
+                m.zeroize();
+                encapsulated
+            }
+
+            /// Encapsulates with the caller's 32-byte message `m` instead of
+            /// sampling it, so known-answer tests (such as the ACVP
+            /// encapsulation vectors) can reproduce exact outputs.
+            ///
+            /// **Never use this in production.** Anyone who knows or can
+            /// guess `m` can recompute the shared secret from the public key
+            /// alone, and reusing `m` with the same key repeats the shared
+            /// secret. Use [`KeyEncapsulation::encapsulate`] or
+            /// [`Self::encapsulate_with_rng`] instead.
+            pub fn encapsulate_deterministic(
+                public_key: &PublicKey,
+                m: &[u8; 32],
+            ) -> Result<(Ciphertext, SharedSecret)> {
+                Self::encapsulate_bytes(&public_key.0, m)
+            }
+
+            fn encapsulate_bytes(
+                public_key: &[u8],
+                m: &[u8; 32],
+            ) -> Result<(Ciphertext, SharedSecret)> {
+                let mut ct = vec![0u8; $sizes::CIPHERTEXT_SIZE];
+                let mut ss = vec![0u8; $sizes::SHARED_SECRET_SIZE];
+                Self::encapsulate_deterministic_into(public_key, m, &mut ct, &mut ss)?;
+                Ok((Ciphertext(ct), SharedSecret(Secret::new(ss))))
+            }
+
+            /// Like [`KeyEncapsulation::encapsulate`], but writes the
+            /// ciphertext and shared secret into caller-provided buffers
+            /// instead of allocating.
+            ///
+            /// The buffers must hold at least
+            /// [`KeyEncapsulation::ciphertext_size`] and
+            /// [`KeyEncapsulation::shared_secret_size`] bytes, or this fails
+            /// with [`QraiopError::BufferTooSmall`]. Only that many leading
+            /// bytes are written. Zeroizing `shared_secret` afterwards is up
+            /// to the caller.
+            pub fn encapsulate_into<'a>(
+                public_key: impl Into<PublicKeyRef<'a>>,
+                ciphertext: &mut [u8],
+                shared_secret: &mut [u8],
+            ) -> Result<()> {
+                Self::encapsulate_into_with_rng(
+                    public_key,
+                    ciphertext,
+                    shared_secret,
+                    &mut utils::SystemRng,
+                )
+            }
+
+            /// [`Self::encapsulate_into`] with the 32-byte message `m` drawn
+            /// from `rng`; the buffer counterpart of
+            /// [`Self::encapsulate_with_rng`].
+            pub fn encapsulate_into_with_rng<'a, R: RngCore + CryptoRng>(
+                public_key: impl Into<PublicKeyRef<'a>>,
+                ciphertext: &mut [u8],
+                shared_secret: &mut [u8],
+                rng: &mut R,
+            ) -> Result<()> {
+                let mut m = [0u8; 32];
+                let result = utils::fill_random(rng, &mut m, QraiopError::EncapsulationFailed)
+                    .map_err(|e| e.with_algorithm($algorithm))
+                    .and_then(|()| {
+                        Self::encapsulate_deterministic_into(
+                            public_key.into().0,
+                            &m,
+                            ciphertext,
+                            shared_secret,
+                        )
+                    });
+                m.zeroize();
+                result
+            }
+
+            fn encapsulate_deterministic_into(
+                public_key: &[u8],
+                m: &[u8; 32],
+                ciphertext: &mut [u8],
+                shared_secret: &mut [u8],
+            ) -> Result<()> {
+                if public_key.len() != $sizes::PUBLIC_KEY_SIZE {
+                    return Err(QraiopError::InvalidKey(format!(
+                        "Public key must be {} bytes, got {}",
+                        $sizes::PUBLIC_KEY_SIZE,
+                        public_key.len()
+                    ))
+                    .with_algorithm($algorithm));
+                }
+                check_buffer(ciphertext, $sizes::CIPHERTEXT_SIZE)?;
+                check_buffer(shared_secret, $sizes::SHARED_SECRET_SIZE)?;
+                // SAFETY: the public key length was checked above and the
+                // output buffers are at least as large as the parameter set
+                // requires.
+                let status = unsafe {
+                    $enc_derand(
+                        ciphertext.as_mut_ptr(),
+                        shared_secret.as_mut_ptr(),
+                        public_key.as_ptr(),
+                        m.as_ptr(),
+                    )
+                };
+                if status != 0 {
+                    shared_secret[..$sizes::SHARED_SECRET_SIZE].zeroize();
+                    return Err(QraiopError::EncapsulationFailed(format!(
+                        "Deterministic encapsulation failed with status {}",
+                        status
+                    ))
+                    .with_algorithm($algorithm));
+                }
+                Ok(())
+            }
+
+            /// Like [`KeyEncapsulation::decapsulate`], but writes the shared
+            /// secret into `shared_secret`, which must hold at least
+            /// [`KeyEncapsulation::shared_secret_size`] bytes.
+            ///
+            /// Only the ciphertext length, which is public, is checked up
+            /// front. A ciphertext of the right length always decapsulates:
+            /// if re-encryption does not reproduce it, FIPS 203 implicit
+            /// rejection returns a pseudorandom secret instead of an error.
+            /// The backend compares the ciphertexts and selects the secret
+            /// in constant time, so the timing does not reveal which
+            /// happened.
+            pub fn decapsulate_into(
+                secret_key: &SecretKey,
+                ciphertext: &Ciphertext,
+                shared_secret: &mut [u8],
+            ) -> Result<()> {
+                check_buffer(shared_secret, $sizes::SHARED_SECRET_SIZE)?;
+                let ss = Self::decapsulate_backend(secret_key, &ciphertext.0)?;
+                shared_secret[..$sizes::SHARED_SECRET_SIZE].copy_from_slice(ss.as_bytes());
+                Ok(())
+            }
+
+            /// Like [`KeyEncapsulation::decapsulate`], but consumes the
+            /// ciphertext and reuses its heap buffer for the shared secret,
+            /// so nothing is allocated. The returned secret keeps the
+            /// ciphertext's capacity; the spare bytes are wiped with it on
+            /// drop.
+            pub fn decapsulate_owned(
+                secret_key: &SecretKey,
+                ciphertext: Ciphertext,
+            ) -> Result<SharedSecret> {
+                let mut buffer = ciphertext.0;
+                let ss = Self::decapsulate_backend(secret_key, &buffer)?;
+                buffer.truncate($sizes::SHARED_SECRET_SIZE);
+                buffer.copy_from_slice(ss.as_bytes());
+                Ok(SharedSecret(Secret::new(buffer)))
+            }
+
+            fn decapsulate_backend(
+                secret_key: &SecretKey,
+                ciphertext: &[u8],
+            ) -> Result<$backend::SharedSecret> {
+                // Checked up front so a truncated or mismatched ciphertext is
+                // reported with both lengths rather than as a backend error
+                if ciphertext.len() != $sizes::CIPHERTEXT_SIZE {
+                    return Err(QraiopError::InvalidCiphertext(format!(
+                        "{} ciphertext must be {} bytes, got {}",
+                        $algorithm,
+                        $sizes::CIPHERTEXT_SIZE,
+                        ciphertext.len()
+                    )));
+                }
+                let sk = $backend::SecretKey::from_bytes(secret_key.0.expose_secret())
+                    .map_err(|e| QraiopError::InvalidKey(e.to_string()).with_algorithm($algorithm))?;
+                let ct = $backend::Ciphertext::from_bytes(ciphertext).map_err(|e| {
+                    QraiopError::InvalidCiphertext(e.to_string()).with_algorithm($algorithm)
+                })?;
+                Ok($backend::decapsulate(&ct, &sk))
+            }
+        }
+
+        impl KeyEncapsulation for $name {
+            type PublicKey = PublicKey;
+            type SecretKey = SecretKey;
+            type Ciphertext = Ciphertext;
+            type SharedSecret = SharedSecret;
+
+            // The backend's own `keypair` panics if the OS RNG fails
+            fn keypair() -> Result<(Self::PublicKey, Self::SecretKey)> {
+                Self::keypair_with_rng(&mut utils::SystemRng)
+            }
+
+            fn encapsulate(
+                public_key: &Self::PublicKey,
+            ) -> Result<(Self::Ciphertext, Self::SharedSecret)> {
+                let pk = $backend::PublicKey::from_bytes(&public_key.0)
+                    .map_err(|e| QraiopError::InvalidKey(e.to_string()).with_algorithm($algorithm))?;
+                let (shared_secret, ciphertext) = $backend::encapsulate(&pk);
+                Ok((
+                    Ciphertext(ciphertext.as_bytes().to_vec()),
+                    SharedSecret(Secret::new(shared_secret.as_bytes().to_vec())),
+                ))
+            }
+
+            fn decapsulate(
+                secret_key: &Self::SecretKey,
+                ciphertext: &Self::Ciphertext,
+            ) -> Result<Self::SharedSecret> {
+                let mut shared_secret = vec![0u8; $sizes::SHARED_SECRET_SIZE];
+                Self::decapsulate_into(secret_key, ciphertext, &mut shared_secret)?;
+                Ok(SharedSecret(Secret::new(shared_secret)))
+            }
+
+            fn algorithm_name() -> &'static str {
+                $algorithm
+            }
+
+            fn algorithm_oid() -> Option<&'static str> {
+                Some($oid)
+            }
+
+            fn public_key_size() -> usize {
+                $sizes::PUBLIC_KEY_SIZE
+            }
+
+            fn secret_key_size() -> usize {
+                $sizes::SECRET_KEY_SIZE
+            }
+
+            fn ciphertext_size() -> usize {
+                $sizes::CIPHERTEXT_SIZE
+            }
+
+            fn shared_secret_size() -> usize {
+                $sizes::SHARED_SECRET_SIZE
+            }
+        }
+
+        #[cfg(feature = "pqcrypto_interop")]
+        native_conversions!(
+            $backend,
+            $algorithm,
+            PublicKey => PublicKey, InvalidKey;
+            SecretKey => SecretKey, InvalidKey;
+            Ciphertext => Ciphertext, InvalidCiphertext
+        );
+    };
+}
+
+ml_kem!(
+    /// ML-KEM-512 (NIST security category 1).
+    MlKem512,
+    mlkem512,
+    ml_kem_512,
+    "ML-KEM-512",
+    "2.16.840.1.101.3.4.4.1",
+    PQCLEAN_MLKEM512_CLEAN_crypto_kem_keypair_derand,
+    PQCLEAN_MLKEM512_CLEAN_crypto_kem_enc_derand
+);
+ml_kem!(
+    /// ML-KEM-768 (NIST security category 3).
+    MlKem768,
+    mlkem768,
+    ml_kem_768,
+    "ML-KEM-768",
+    "2.16.840.1.101.3.4.4.2",
+    PQCLEAN_MLKEM768_CLEAN_crypto_kem_keypair_derand,
+    PQCLEAN_MLKEM768_CLEAN_crypto_kem_enc_derand
+);
+ml_kem!(
+    /// ML-KEM-1024 (NIST security category 5).
+    MlKem1024,
+    mlkem1024,
+    ml_kem_1024,
+    "ML-KEM-1024",
+    "2.16.840.1.101.3.4.4.3",
+    PQCLEAN_MLKEM1024_CLEAN_crypto_kem_keypair_derand,
+    PQCLEAN_MLKEM1024_CLEAN_crypto_kem_enc_derand
+);
+
+/// ML-KEM parameter set chosen at runtime, e.g. from a configuration file.
+///
+/// The key wrappers are shared by all parameter sets, so the dispatching
+/// methods hand back the same zeroizing types as the static API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KemAlgorithm {
+    MlKem512,
+    MlKem768,
+    MlKem1024,
+}
+
+impl KemAlgorithm {
+    pub fn name(&self) -> &'static str {
+        match self {
+            KemAlgorithm::MlKem512 => "ML-KEM-512",
+            KemAlgorithm::MlKem768 => "ML-KEM-768",
+            KemAlgorithm::MlKem1024 => "ML-KEM-1024",
+        }
+    }
+
+    pub fn keypair(&self) -> Result<(PublicKey, SecretKey)> {
+        match self {
+            KemAlgorithm::MlKem512 => MlKem512::keypair(),
+            KemAlgorithm::MlKem768 => MlKem768::keypair(),
+            KemAlgorithm::MlKem1024 => MlKem1024::keypair(),
+        }
+    }
+
+    pub fn encapsulate(&self, public_key: &PublicKey) -> Result<(Ciphertext, SharedSecret)> {
+        match self {
+            KemAlgorithm::MlKem512 => MlKem512::encapsulate(public_key),
+            KemAlgorithm::MlKem768 => MlKem768::encapsulate(public_key),
+            KemAlgorithm::MlKem1024 => MlKem1024::encapsulate(public_key),
+        }
+    }
+
+    pub fn decapsulate(
+        &self,
+        secret_key: &SecretKey,
+        ciphertext: &Ciphertext,
+    ) -> Result<SharedSecret> {
+        match self {
+            KemAlgorithm::MlKem512 => MlKem512::decapsulate(secret_key, ciphertext),
+            KemAlgorithm::MlKem768 => MlKem768::decapsulate(secret_key, ciphertext),
+            KemAlgorithm::MlKem1024 => MlKem1024::decapsulate(secret_key, ciphertext),
+        }
+    }
+}
+
+impl fmt::Display for KemAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for KemAlgorithm {
+    type Err = QraiopError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "ML-KEM-512" => Ok(KemAlgorithm::MlKem512),
+            "ML-KEM-768" => Ok(KemAlgorithm::MlKem768),
+            "ML-KEM-1024" => Ok(KemAlgorithm::MlKem1024),
+            other => Err(QraiopError::UnsupportedAlgorithm(other.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ml_kem_512_roundtrip() {
+        let (pk, sk) = MlKem512::keypair().unwrap();
+        let (ct, ss1) = MlKem512::encapsulate(&pk).unwrap();
+        let ss2 = MlKem512::decapsulate(&sk, &ct).unwrap();
+
+        // Shared secrets are compared in constant time
+        assert!(ss1 == ss2, "Shared secrets should match");
+    }
+
+    #[test]
+    fn test_ml_kem_768_roundtrip() {
+        let (pk, sk) = MlKem768::keypair().unwrap();
+        let (ct, ss1) = MlKem768::encapsulate(&pk).unwrap();
+        let ss2 = MlKem768::decapsulate(&sk, &ct).unwrap();
+        assert!(ss1 == ss2, "Shared secrets should match");
+    }
+
+    #[test]
+    fn test_ml_kem_1024_roundtrip() {
+        let (pk, sk) = MlKem1024::keypair().unwrap();
+        let (ct, ss1) = MlKem1024::encapsulate(&pk).unwrap();
+        let ss2 = MlKem1024::decapsulate(&sk, &ct).unwrap();
+        assert!(ss1 == ss2, "Shared secrets should match");
+    }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let (pk, sk) = MlKem768::keypair().unwrap();
+        let (ct, ss1) = MlKem768::encapsulate(&pk).unwrap();
+
+        let pk = PublicKey::from_bytes(pk.as_bytes()).unwrap();
+        let sk = SecretKey::from_bytes(sk.as_bytes()).unwrap();
+        let ct = Ciphertext::from_bytes(ct.as_bytes()).unwrap();
+        let ss1 = SharedSecret::from_bytes(ss1.as_bytes()).unwrap();
+
+        assert_eq!(pk.as_bytes().len(), ml_kem_768::PUBLIC_KEY_SIZE);
+        assert_eq!(sk.as_bytes().len(), ml_kem_768::SECRET_KEY_SIZE);
+        assert!(ss1 == MlKem768::decapsulate(&sk, &ct).unwrap());
+    }
+
+    #[test]
+    fn test_keypair_from_seed_is_deterministic() {
+        let seed = [7u8; 64];
+        let (pk1, sk1) = MlKem768::keypair_from_seed(&seed).unwrap();
+        let (pk2, sk2) = MlKem768::keypair_from_seed(&seed).unwrap();
+        assert_eq!(pk1.as_bytes(), pk2.as_bytes());
+        assert_eq!(sk1.as_bytes(), sk2.as_bytes());
+
+        // z is stored verbatim at the end of the secret key
+        assert_eq!(
+            &sk1.as_bytes()[ml_kem_768::SECRET_KEY_SIZE - 32..],
+            &seed[32..]
+        );
+
+        let mut other = seed;
+        other[0] ^= 1;
+        let (pk3, _) = MlKem768::keypair_from_seed(&other).unwrap();
+        assert_ne!(pk1.as_bytes(), pk3.as_bytes());
+
+        let (ct, ss1) = MlKem768::encapsulate(&pk1).unwrap();
+        assert!(ss1 == MlKem768::decapsulate(&sk1, &ct).unwrap());
+    }
+
+    #[test]
+    fn test_keypair_from_seed_all_parameter_sets() {
+        let seed = [42u8; 64];
+        let (pk, sk) = MlKem512::keypair_from_seed(&seed).unwrap();
+        assert_eq!(pk.as_bytes().len(), ml_kem_512::PUBLIC_KEY_SIZE);
+        assert_eq!(sk.as_bytes().len(), ml_kem_512::SECRET_KEY_SIZE);
+        let (pk, sk) = MlKem1024::keypair_from_seed(&seed).unwrap();
+        assert_eq!(pk.as_bytes().len(), ml_kem_1024::PUBLIC_KEY_SIZE);
+        assert_eq!(sk.as_bytes().len(), ml_kem_1024::SECRET_KEY_SIZE);
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_wrong_length() {
+        let short = vec![0u8; ml_kem_768::PUBLIC_KEY_SIZE - 1];
+        assert!(matches!(
+            PublicKey::from_bytes(&short),
+            Err(QraiopError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            SecretKey::from_bytes(&short),
+            Err(QraiopError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            Ciphertext::from_bytes(&short),
+            Err(QraiopError::InvalidCiphertext(_))
+        ));
+        assert!(matches!(
+            SharedSecret::from_bytes(&[0u8; 31]),
+            Err(QraiopError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_decapsulate_rejects_wrong_ciphertext_length() {
+        let (pk, sk) = MlKem768::keypair().unwrap();
+        let (ct, _) = MlKem768::encapsulate(&pk).unwrap();
+
+        let short = Ciphertext(ct.as_bytes()[1..].to_vec());
+        let long = Ciphertext([ct.as_bytes(), &[0]].concat());
+        for bad in [short, long] {
+            match MlKem768::decapsulate(&sk, &bad) {
+                Err(QraiopError::InvalidCiphertext(message)) => {
+                    assert!(message.contains("1088"), "{}", message);
+                    assert!(message.contains(&bad.as_bytes().len().to_string()));
+                }
+                other => panic!("expected InvalidCiphertext, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_errors_name_the_algorithm() {
+        let (pk, _) = MlKem512::keypair().unwrap();
+        let (_, sk768) = MlKem768::keypair().unwrap();
+        let (ct, _) = MlKem512::encapsulate(&pk).unwrap();
+        match MlKem512::decapsulate(&sk768, &ct) {
+            Err(err @ QraiopError::InvalidKey(_)) => {
+                assert!(err.to_string().contains("ML-KEM-512"), "{}", err)
+            }
+            other => panic!("expected InvalidKey, got {:?}", other),
+        }
+
+        // A message that already names the algorithm is left as it is
+        let short = Ciphertext(ct.as_bytes()[1..].to_vec());
+        let err = MlKem512::decapsulate(&sk768, &short).unwrap_err();
+        assert_eq!(err.to_string().matches("ML-KEM-512").count(), 1, "{}", err);
+        assert!(matches!(
+            QraiopError::SignatureVerificationFailed.with_algorithm("ML-KEM-512"),
+            QraiopError::SignatureVerificationFailed
+        ));
+    }
+
+    #[test]
+    fn test_encapsulate_to_borrowed_key() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha20Rng;
+
+        let (pk, sk) = MlKem768::keypair().unwrap();
+        // Several keys back to back, as in one mapped file
+        let buffer = [pk.as_bytes(), pk.as_bytes()].concat();
+        let borrowed = PublicKeyRef::from_bytes(&buffer[ml_kem_768::PUBLIC_KEY_SIZE..]).unwrap();
+        borrowed.validate().unwrap();
+        assert_eq!(borrowed.to_owned_key(), pk);
+
+        let owned = MlKem768::encapsulate_with_rng(&pk, &mut ChaCha20Rng::seed_from_u64(5));
+        let from_ref = MlKem768::encapsulate_with_rng(borrowed, &mut ChaCha20Rng::seed_from_u64(5));
+        let ((ct, ss), (ct_ref, ss_ref)) = (owned.unwrap(), from_ref.unwrap());
+        assert_eq!(ct, ct_ref);
+        assert_eq!(ss, ss_ref);
+        assert_eq!(MlKem768::decapsulate(&sk, &ct_ref).unwrap(), ss_ref);
+
+        let mut ct_buf = [0u8; ml_kem_768::CIPHERTEXT_SIZE];
+        let mut ss_buf = [0u8; 32];
+        MlKem768::encapsulate_into(pk.as_ref_key(), &mut ct_buf, &mut ss_buf).unwrap();
+        let ct = Ciphertext::from_bytes(&ct_buf).unwrap();
+        assert_eq!(MlKem768::decapsulate(&sk, &ct).unwrap().as_bytes(), &ss_buf);
+
+        // Deserializes without copying out of the input
+        let encoded = bincode::serialize(&pk).unwrap();
+        let decoded: PublicKeyRef = bincode::deserialize(&encoded).unwrap();
+        assert_eq!(decoded.as_bytes(), pk.as_bytes());
+        assert!(encoded
+            .as_ptr_range()
+            .contains(&decoded.as_bytes().as_ptr()));
+
+        assert!(matches!(
+            PublicKeyRef::from_bytes(&buffer[1..]),
+            Err(QraiopError::InvalidKey(_))
+        ));
+        assert!(MlKem512::encapsulate_with_rng(borrowed, &mut utils::SystemRng).is_err());
+    }
+
+    #[cfg(feature = "pqcrypto_interop")]
+    #[test]
+    fn test_native_type_conversions() {
+        let (native_pk, native_sk) = mlkem768::keypair();
+        let pk = PublicKey::from(native_pk);
+        let sk = SecretKey::from(native_sk);
+        assert_eq!(pk.as_bytes(), native_pk.as_bytes());
+
+        // Keys go back to the backend, and its ciphertexts come in
+        let back = mlkem768::PublicKey::try_from(&pk).unwrap();
+        assert_eq!(back.as_bytes(), pk.as_bytes());
+        let (native_ss, native_ct) = mlkem768::encapsulate(&back);
+        let ct = Ciphertext::from(native_ct);
+        assert_eq!(
+            MlKem768::decapsulate(&sk, &ct).unwrap().as_bytes(),
+            native_ss.as_bytes()
+        );
+        let native_sk = mlkem768::SecretKey::try_from(&sk).unwrap();
+        let native_ct = mlkem768::Ciphertext::try_from(&ct).unwrap();
+        assert_eq!(
+            mlkem768::decapsulate(&native_ct, &native_sk).as_bytes(),
+            native_ss.as_bytes()
+        );
+
+        match mlkem512::PublicKey::try_from(&pk) {
+            Err(QraiopError::InvalidKey(message)) => assert!(message.contains("ML-KEM-512")),
+            other => panic!("expected InvalidKey, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_public_key_as_set_member() {
+        use std::collections::HashSet;
+
+        let (pk1, _) = MlKem768::keypair().unwrap();
+        let (pk2, _) = MlKem768::keypair().unwrap();
+        let mut keys = HashSet::new();
+        assert!(keys.insert(pk1.clone()));
+        assert!(keys.insert(pk2));
+        assert!(!keys.insert(pk1.clone()));
+        assert_eq!(keys.len(), 2);
+        assert!(keys.contains(&PublicKey::from_bytes(pk1.as_bytes()).unwrap()));
+    }
+
+    #[test]
+    fn test_kem_algorithm_from_str() {
+        for name in ["ML-KEM-512", "ML-KEM-768", "ML-KEM-1024"] {
+            let algorithm: KemAlgorithm = name.parse().unwrap();
+            assert_eq!(algorithm.to_string(), name);
+        }
+        assert!(matches!(
+            "ML-KEM-2048".parse::<KemAlgorithm>(),
+            Err(QraiopError::UnsupportedAlgorithm(_))
+        ));
+    }
+
+    #[test]
+    fn test_kem_algorithm_dispatch() {
+        let algorithm: KemAlgorithm = "ML-KEM-1024".parse().unwrap();
+        let (pk, sk) = algorithm.keypair().unwrap();
+        assert_eq!(pk.as_bytes().len(), ml_kem_1024::PUBLIC_KEY_SIZE);
+
+        let (ct, ss1) = algorithm.encapsulate(&pk).unwrap();
+        let ss2 = algorithm.decapsulate(&sk, &ct).unwrap();
+        assert!(ss1 == ss2);
+
+        // Keys from a different parameter set are rejected
+        assert!(KemAlgorithm::MlKem512.encapsulate(&pk).is_err());
+    }
+
+    #[test]
+    fn test_injected_rng_is_reproducible() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha20Rng;
+
+        let mut rng = ChaCha20Rng::seed_from_u64(7);
+        let (pk1, sk1) = MlKem768::keypair_with_rng(&mut rng).unwrap();
+        let (ct1, ss1) = MlKem768::encapsulate_with_rng(&pk1, &mut rng).unwrap();
+
+        let mut rng = ChaCha20Rng::seed_from_u64(7);
+        let (pk2, sk2) = MlKem768::keypair_with_rng(&mut rng).unwrap();
+        let (ct2, ss2) = MlKem768::encapsulate_with_rng(&pk2, &mut rng).unwrap();
+
+        assert_eq!(pk1.as_bytes(), pk2.as_bytes());
+        assert_eq!(sk1.as_bytes(), sk2.as_bytes());
+        assert_eq!(ct1.as_bytes(), ct2.as_bytes());
+        assert!(ss1 == ss2);
+        assert!(MlKem768::decapsulate(&sk1, &ct1).unwrap() == ss1);
+
+        // The RNG state advances, so the next key differs
+        let (pk3, _) = MlKem768::keypair_with_rng(&mut rng).unwrap();
+        assert_ne!(pk3.as_bytes(), pk1.as_bytes());
+    }
+
+    #[test]
+    fn test_keypair_with_failing_rng() {
+        let err = MlKem768::keypair_with_rng(&mut utils::FailingRng).unwrap_err();
+        assert!(
+            matches!(err, QraiopError::KeyGenerationFailed(ref m) if m.starts_with("ML-KEM-768: ")),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_encapsulate_with_failing_rng() {
+        let (pk, _) = MlKem768::keypair().unwrap();
+        let err = MlKem768::encapsulate_with_rng(&pk, &mut utils::FailingRng).unwrap_err();
+        assert!(
+            matches!(err, QraiopError::EncapsulationFailed(ref m) if m.starts_with("ML-KEM-768: ")),
+            "{:?}",
+            err
+        );
+
+        let mut ct = [0u8; 1088];
+        let mut ss = [0u8; 32];
+        assert!(matches!(
+            MlKem768::encapsulate_into_with_rng(&pk, &mut ct, &mut ss, &mut utils::FailingRng),
+            Err(QraiopError::EncapsulationFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_buffer_api_matches_allocating_api() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha20Rng;
+
+        let (pk, sk) = MlKem1024::keypair().unwrap();
+        let (ct, ss) =
+            MlKem1024::encapsulate_with_rng(&pk, &mut ChaCha20Rng::seed_from_u64(1)).unwrap();
+
+        // Oversized buffers are fine; only the leading bytes are written
+        let mut ct_buf = [0u8; ml_kem_1024::CIPHERTEXT_SIZE + 8];
+        let mut ss_buf = [0u8; ml_kem_1024::SHARED_SECRET_SIZE];
+        MlKem1024::encapsulate_into_with_rng(
+            &pk,
+            &mut ct_buf,
+            &mut ss_buf,
+            &mut ChaCha20Rng::seed_from_u64(1),
+        )
+        .unwrap();
+        assert_eq!(&ct_buf[..ml_kem_1024::CIPHERTEXT_SIZE], ct.as_bytes());
+        assert_eq!(ct_buf[ml_kem_1024::CIPHERTEXT_SIZE..], [0; 8]);
+        assert_eq!(ss_buf, ss.as_bytes());
+
+        let mut decapsulated = [0u8; ml_kem_1024::SHARED_SECRET_SIZE];
+        MlKem1024::decapsulate_into(&sk, &ct, &mut decapsulated).unwrap();
+        assert_eq!(decapsulated, ss_buf);
+
+        MlKem1024::encapsulate_into(&pk, &mut ct_buf, &mut ss_buf).unwrap();
+        let ct = Ciphertext::from_bytes(&ct_buf[..ml_kem_1024::CIPHERTEXT_SIZE]).unwrap();
+        assert_eq!(MlKem1024::decapsulate(&sk, &ct).unwrap().as_bytes(), ss_buf);
+    }
+
+    #[test]
+    fn test_buffer_api_rejects_small_buffers() {
+        let (pk, sk) = MlKem512::keypair().unwrap();
+        let (ct, _) = MlKem512::encapsulate(&pk).unwrap();
+        let mut ct_buf = [0u8; ml_kem_512::CIPHERTEXT_SIZE];
+        let mut ss_buf = [0u8; ml_kem_512::SHARED_SECRET_SIZE - 1];
+
+        assert!(matches!(
+            MlKem512::encapsulate_into(&pk, &mut ct_buf[..100], &mut [0u8; 32]),
+            Err(QraiopError::BufferTooSmall {
+                needed: 768,
+                got: 100
+            })
+        ));
+        assert!(matches!(
+            MlKem512::encapsulate_into(&pk, &mut ct_buf, &mut ss_buf),
+            Err(QraiopError::BufferTooSmall {
+                needed: 32,
+                got: 31
+            })
+        ));
+        assert!(matches!(
+            MlKem512::decapsulate_into(&sk, &ct, &mut ss_buf),
+            Err(QraiopError::BufferTooSmall {
+                needed: 32,
+                got: 31
+            })
+        ));
+    }
+
+    #[test]
+    fn test_decapsulate_owned_matches_decapsulate() {
+        let (pk, sk) = MlKem768::keypair().unwrap();
+        let (ct, ss) = MlKem768::encapsulate(&pk).unwrap();
+        let owned = Ciphertext::from_vec(ct.as_bytes().to_vec()).unwrap();
+        assert_eq!(MlKem768::decapsulate_owned(&sk, owned).unwrap(), ss);
+
+        let (ct512, _) = MlKem512::encapsulate(&MlKem512::keypair().unwrap().0).unwrap();
+        assert!(matches!(
+            MlKem768::decapsulate_owned(&sk, ct512),
+            Err(QraiopError::InvalidCiphertext(_))
+        ));
+        assert!(matches!(
+            Ciphertext::from_vec(vec![0u8; 1000]),
+            Err(QraiopError::InvalidCiphertext(_))
+        ));
+    }
+
+    #[test]
+    fn test_vec_conversions() {
+        let (pk, sk) = MlKem768::keypair().unwrap();
+        let (ct, ss) = MlKem768::encapsulate(&pk).unwrap();
+
+        let pk2: PublicKey = pk.as_bytes().to_vec().try_into().unwrap();
+        let ct2: Ciphertext = Vec::from(ct).try_into().unwrap();
+        let sk2: SecretKey = sk.as_bytes().to_vec().try_into().unwrap();
+        assert_eq!(Vec::<u8>::from(pk2), pk.as_bytes());
+        assert_eq!(MlKem768::decapsulate(&sk2, &ct2).unwrap(), ss);
+
+        assert!(matches!(
+            PublicKey::try_from(vec![0u8; 1000]),
+            Err(QraiopError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            SecretKey::try_from(vec![0u8; 1000]),
+            Err(QraiopError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            Ciphertext::try_from(vec![0u8; 1000]),
+            Err(QraiopError::InvalidCiphertext(_))
+        ));
+    }
+
+    #[test]
+    fn test_key_validation() {
+        for (pk, sk) in [
+            MlKem512::keypair().unwrap(),
+            MlKem768::keypair().unwrap(),
+            MlKem1024::keypair().unwrap(),
+        ] {
+            pk.validate().unwrap();
+            sk.validate().unwrap();
+
+            // First coefficient set to 0xfff, which is not below q
+            let mut bytes = pk.as_bytes().to_vec();
+            bytes[0] = 0xff;
+            bytes[1] |= 0x0f;
+            let tweaked = PublicKey::from_bytes(&bytes).unwrap();
+            assert!(matches!(
+                tweaked.validate(),
+                Err(QraiopError::InvalidKey(_))
+            ));
+
+            // A byte of the embedded ek no longer matches H(ek)
+            let mut bytes = sk.as_bytes().to_vec();
+            bytes[sk.as_bytes().len() / 2] ^= 1;
+            let tweaked = SecretKey::from_bytes(&bytes).unwrap();
+            assert!(matches!(
+                tweaked.validate(),
+                Err(QraiopError::InvalidKey(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_public_key_from_secret_key() {
+        for (pk, sk) in [
+            MlKem512::keypair().unwrap(),
+            MlKem768::keypair().unwrap(),
+            MlKem1024::keypair().unwrap(),
+        ] {
+            assert_eq!(sk.public_key().unwrap(), pk);
+
+            let mut bytes = sk.as_bytes().to_vec();
+            bytes[sk.as_bytes().len() / 2] ^= 1;
+            let tweaked = SecretKey::from_bytes(&bytes).unwrap();
+            assert!(matches!(
+                tweaked.public_key(),
+                Err(QraiopError::InvalidKey(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_public_value_equality() {
+        let (pk1, _) = MlKem512::keypair_from_seed(&[1; 64]).unwrap();
+        let (pk2, _) = MlKem512::keypair_from_seed(&[1; 64]).unwrap();
+        let (pk3, _) = MlKem512::keypair_from_seed(&[2; 64]).unwrap();
+        assert_eq!(pk1, pk2);
+        assert_ne!(pk1, pk3);
+        assert!(bool::from(pk1.ct_eq(&pk2)));
+        assert!(!bool::from(pk1.ct_eq(&pk3)));
+
+        let (ct1, _) = MlKem512::encapsulate_deterministic(&pk1, &[3; 32]).unwrap();
+        let (ct2, _) = MlKem512::encapsulate_deterministic(&pk1, &[3; 32]).unwrap();
+        let (ct3, _) = MlKem512::encapsulate_deterministic(&pk1, &[4; 32]).unwrap();
+        assert_eq!(ct1, ct2);
+        assert_ne!(ct1, ct3);
+
+        // Different parameter sets never compare equal
+        let (pk4, _) = MlKem768::keypair_from_seed(&[1; 64]).unwrap();
+        assert_ne!(pk1, pk4);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_secret_key_from_env() {
+        let (pk, sk) = MlKem512::keypair().unwrap();
+        std::env::set_var("QRAIOP_TEST_MLKEM_SK", hex::encode(sk.as_bytes()));
+        let loaded = SecretKey::from_env("QRAIOP_TEST_MLKEM_SK").unwrap();
+        let (ct, ss) = MlKem512::encapsulate(&pk).unwrap();
+        assert_eq!(MlKem512::decapsulate(&loaded, &ct).unwrap(), ss);
+
+        std::env::set_var("QRAIOP_TEST_MLKEM_SK_GARBAGE", "c2hvcnQ=");
+        assert!(matches!(
+            SecretKey::from_env("QRAIOP_TEST_MLKEM_SK_GARBAGE"),
+            Err(QraiopError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_manual_zeroize() {
+        let (pk, mut sk) = MlKem768::keypair().unwrap();
+        let (ct, mut ss) = MlKem768::encapsulate(&pk).unwrap();
+        let exported = sk.to_zeroizing_bytes();
+        assert_eq!(&exported[..], sk.as_bytes());
+        assert_eq!(&ss.to_zeroizing_bytes()[..], ss.expose_bytes());
+
+        sk.zeroize();
+        ss.zeroize();
+        assert!(sk.as_bytes().is_empty());
+        assert!(ss.expose_bytes().is_empty());
+        // A wiped key is rejected rather than used as all zeros
+        assert!(matches!(
+            MlKem768::decapsulate(&sk, &ct),
+            Err(QraiopError::InvalidKey(_))
+        ));
+        assert!(matches!(sk.validate(), Err(QraiopError::InvalidKey(_))));
+    }
+
+    #[test]
+    fn test_public_keys_sort_by_bytes() {
+        use alloc::collections::BTreeSet;
+
+        let keys: Vec<PublicKey> = [[3; 64], [1; 64], [2; 64]]
+            .iter()
+            .map(|seed| MlKem512::keypair_from_seed(seed).unwrap().0)
+            .collect();
+        let forward: BTreeSet<&PublicKey> = keys.iter().collect();
+        let backward: BTreeSet<&PublicKey> = keys.iter().rev().collect();
+        assert!(forward.iter().eq(backward.iter()));
+        assert!(forward
+            .iter()
+            .zip(forward.iter().skip(1))
+            .all(|(a, b)| a.as_bytes() < b.as_bytes()));
+    }
+
+    #[test]
+    fn test_shared_secret_is_eq() {
+        fn assert_eq_impl<T: Eq>() {}
+        assert_eq_impl::<SharedSecret>();
+
+        let (pk, _) = MlKem768::keypair().unwrap();
+        let (_, ss) = MlKem768::encapsulate(&pk).unwrap();
+        assert_eq!(ss, ss.clone());
+        assert_ne!(ss, SharedSecret::from_bytes(&[0; 32]).unwrap());
+    }
+
+    #[test]
+    fn test_shared_secrets_are_32_bytes() {
+        fn check<K: KeyEncapsulation<SharedSecret = SharedSecret>>() -> SharedSecret {
+            let (pk, _) = K::keypair().unwrap();
+            let (_, ss) = K::encapsulate(&pk).unwrap();
+            assert_eq!(K::shared_secret_size(), 32);
+            assert_eq!(ss.as_bytes().len(), 32);
+            ss
+        }
+        assert_eq!(SHARED_SECRET_SIZES, [32; 3]);
+        let secrets = [
+            check::<MlKem512>(),
+            check::<MlKem768>(),
+            check::<MlKem1024>(),
+        ];
+
+        // Across parameter sets the comparison is by content
+        assert_ne!(secrets[0], secrets[1]);
+        let copy = SharedSecret::from_bytes(secrets[2].as_bytes()).unwrap();
+        assert_eq!(copy, secrets[2]);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "different lengths")]
+    fn test_shared_secret_length_mismatch_panics() {
+        let short = SharedSecret(Secret::new(vec![0; 16]));
+        let _ = short == SharedSecret::from_bytes(&[0; 32]).unwrap();
+    }
+
+    #[test]
+    fn test_shared_secret_into_cipher_keys() {
+        let (pk, sk) = MlKem768::keypair().unwrap();
+        let (ct, ss) = MlKem768::encapsulate(&pk).unwrap();
+        let raw = ss.to_zeroizing_bytes();
+        let aes = ss.clone().into_aes256_key().unwrap();
+        let chacha = ss.into_chacha_key().unwrap();
+        assert_ne!(*aes, *chacha);
+        assert_ne!(&aes[..], &raw[..]);
+        assert_ne!(&chacha[..], &raw[..]);
+
+        // Both sides derive the same key from their copy of the secret
+        let decapsulated = MlKem768::decapsulate(&sk, &ct).unwrap();
+        assert_eq!(*decapsulated.into_aes256_key().unwrap(), *aes);
+    }
+
+    #[test]
+    fn test_ciphertext_from_bytes_strict() {
+        let (pk, _) = MlKem512::keypair().unwrap();
+        let (ct, _) = MlKem512::encapsulate(&pk).unwrap();
+        assert_eq!(Ciphertext::from_bytes_strict(ct.as_bytes()).unwrap(), ct);
+        let arbitrary = vec![0xff; ml_kem_512::CIPHERTEXT_SIZE];
+        assert!(Ciphertext::from_bytes_strict(&arbitrary).is_ok());
+        assert!(matches!(
+            Ciphertext::from_bytes_strict(&arbitrary[1..]),
+            Err(QraiopError::InvalidCiphertext(_))
+        ));
+    }
+
+    #[test]
+    fn test_encapsulate_deterministic() {
+        let (pk, sk) = MlKem768::keypair().unwrap();
+        let m = [0x5a; 32];
+        let (ct1, ss1) = MlKem768::encapsulate_deterministic(&pk, &m).unwrap();
+        let (ct2, ss2) = MlKem768::encapsulate_deterministic(&pk, &m).unwrap();
+        assert_eq!(ct1.as_bytes(), ct2.as_bytes());
+        assert!(ss1 == ss2);
+        assert!(MlKem768::decapsulate(&sk, &ct1).unwrap() == ss1);
+    }
+
+    #[test]
+    fn test_trait_sizes_match_key_sizes() {
+        fn check<K: KeyEncapsulation>(pk: usize, sk: usize, ct: usize, ss: usize) {
+            assert_eq!(K::public_key_size(), pk);
+            assert_eq!(K::secret_key_size(), sk);
+            assert_eq!(K::ciphertext_size(), ct);
+            assert_eq!(K::shared_secret_size(), ss);
+        }
+        check::<MlKem512>(
+            ml_kem_512::PUBLIC_KEY_SIZE,
+            ml_kem_512::SECRET_KEY_SIZE,
+            ml_kem_512::CIPHERTEXT_SIZE,
+            ml_kem_512::SHARED_SECRET_SIZE,
+        );
+        check::<MlKem768>(
+            ml_kem_768::PUBLIC_KEY_SIZE,
+            ml_kem_768::SECRET_KEY_SIZE,
+            ml_kem_768::CIPHERTEXT_SIZE,
+            ml_kem_768::SHARED_SECRET_SIZE,
+        );
+        check::<MlKem1024>(
+            ml_kem_1024::PUBLIC_KEY_SIZE,
+            ml_kem_1024::SECRET_KEY_SIZE,
+            ml_kem_1024::CIPHERTEXT_SIZE,
+            ml_kem_1024::SHARED_SECRET_SIZE,
+        );
+    }
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let (pk, sk) = MlKem512::keypair_from_seed(&[0xab; 64]).unwrap();
+        let (_, ss) = MlKem512::encapsulate(&pk).unwrap();
+
+        let formatted = format!("{:?}", sk);
+        assert_eq!(formatted, "SecretKey([REDACTED; 1632])");
+        assert!(!formatted.contains(&format!("{:?}", &sk.as_bytes()[..4])));
+        assert_eq!(format!("{:?}", ss), "SharedSecret([REDACTED; 32])");
+    }
+
+    #[test]
+    fn test_encrypted_secret_key() {
+        let (_, sk) = MlKem512::keypair().unwrap();
+        let data = passphrase::encrypt_with_params(
+            b"correct horse",
+            sk.as_bytes(),
+            passphrase::test_params(),
+        )
+        .unwrap();
+        assert!(!data
+            .windows(sk.as_bytes().len())
+            .any(|w| w == sk.as_bytes()));
+
+        let restored = SecretKey::from_encrypted_bytes(&data, b"correct horse").unwrap();
+        assert_eq!(restored.as_bytes(), sk.as_bytes());
+        assert!(matches!(
+            SecretKey::from_encrypted_bytes(&data, b"wrong horse"),
+            Err(QraiopError::SerializationError(_))
+        ));
+
+        // The public entry point with the default (slow) Argon2id cost
+        let data = sk.to_encrypted_bytes(b"correct horse").unwrap();
+        let restored = SecretKey::from_encrypted_bytes(&data, b"correct horse").unwrap();
+        assert_eq!(restored.as_bytes(), sk.as_bytes());
+    }
+
+    #[cfg(feature = "serde_secret")]
+    #[test]
+    fn test_serde_secret() {
+        let (_, sk) = MlKem512::keypair().unwrap();
+        let encoded = bincode::serialize(&sk).unwrap();
+        let decoded: SecretKey = bincode::deserialize(&encoded).unwrap();
+        assert_eq!(decoded.as_bytes(), sk.as_bytes());
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let (pk1, _) = MlKem768::keypair().unwrap();
+        let (pk2, _) = MlKem768::keypair().unwrap();
+
+        let fingerprint = pk1.fingerprint();
+        assert_eq!(fingerprint.len(), 8 * 3 - 1);
+        assert_eq!(fingerprint.split(':').count(), 8);
+        assert_eq!(pk1.clone().fingerprint(), fingerprint);
+        assert_eq!(pk1.clone().fingerprint_full(), pk1.fingerprint_full());
+        assert_ne!(pk2.fingerprint(), fingerprint);
+        assert!(fingerprint.starts_with(&format!("{:02x}", pk1.fingerprint_full()[0])));
+    }
+
+    #[test]
+    fn test_expose_bytes_length() {
+        fn exposed_len<K: KeyEncapsulation<SharedSecret = SharedSecret>>() -> usize {
+            let (pk, sk) = K::keypair().unwrap();
+            let (ct, ss) = K::encapsulate(&pk).unwrap();
+            assert_eq!(
+                K::decapsulate(&sk, &ct).unwrap().expose_bytes(),
+                ss.expose_bytes()
+            );
+            ss.expose_bytes().len()
+        }
+
+        assert_eq!(exposed_len::<MlKem512>(), 32);
+        assert_eq!(exposed_len::<MlKem768>(), 32);
+        assert_eq!(exposed_len::<MlKem1024>(), 32);
+    }
+
+    #[cfg(all(unix, feature = "locked"))]
+    #[test]
+    fn test_locked_secret_key_decapsulates() {
+        let (pk, sk) = MlKem768::keypair().unwrap();
+        let (ct, ss) = MlKem768::encapsulate(&pk).unwrap();
+        assert_eq!(MlKem768::decapsulate(&sk, &ct).unwrap(), ss);
+
+        // Cloning and decoding lock fresh buffers holding the same bytes
+        let copies = [sk.clone(), SecretKey::from_bytes(sk.as_bytes()).unwrap()];
+        drop(sk);
+        for sk in &copies {
+            assert_eq!(MlKem768::decapsulate(sk, &ct).unwrap(), ss);
+        }
+    }
+
+    #[test]
+    fn test_public_key_display() {
+        let (pk, _) = MlKem768::keypair().unwrap();
+        let shown = pk.to_string();
+        let fingerprint = shown.strip_prefix("ML-KEM-768 pk:").unwrap();
+        // 8 bytes as colon-separated hex
+        assert_eq!(fingerprint.len(), 8 * 3 - 1);
+        assert!(fingerprint
+            .split(':')
+            .all(|b| b.len() == 2 && u8::from_str_radix(b, 16).is_ok()));
+        assert_eq!(format!("{:?}", pk), format!("PublicKey({})", shown));
+
+        let (pk, _) = MlKem512::keypair().unwrap();
+        assert!(pk.to_string().starts_with("ML-KEM-512 pk:"));
+        let (pk, _) = MlKem1024::keypair().unwrap();
+        assert!(pk.to_string().starts_with("ML-KEM-1024 pk:"));
+    }
+
+    #[test]
+    fn test_bad_ciphertext_is_invalid_ciphertext() {
+        // A well-formed ciphertext for another parameter set
+        let (pk, _) = MlKem512::keypair().unwrap();
+        let (_, sk) = MlKem768::keypair().unwrap();
+        let (ct, _) = MlKem512::encapsulate(&pk).unwrap();
+        assert!(matches!(
+            MlKem768::decapsulate(&sk, &ct),
+            Err(QraiopError::InvalidCiphertext(_))
+        ));
+    }
+
+    #[test]
+    fn test_deserialize_checks_length() {
+        let (pk, _) = MlKem768::keypair().unwrap();
+        let (ct, _) = MlKem768::encapsulate(&pk).unwrap();
+        let decoded: PublicKey = bincode::deserialize(&bincode::serialize(&pk).unwrap()).unwrap();
+        assert_eq!(decoded.as_bytes(), pk.as_bytes());
+        let decoded: Ciphertext = bincode::deserialize(&bincode::serialize(&ct).unwrap()).unwrap();
+        assert_eq!(decoded, ct);
+
+        let short = bincode::serialize(&vec![0u8; 100]).unwrap();
+        assert!(bincode::deserialize::<PublicKey>(&short).is_err());
+        assert!(bincode::deserialize::<Ciphertext>(&short).is_err());
+        assert!(serde_json::from_str::<PublicKey>("[]").is_err());
+    }
+}
@@ -0,0 +1,394 @@
+//! Round-3 CRYSTALS-Kyber, for peers that predate FIPS 203
+//!
+//! This is the Kyber submitted to the third round of the NIST competition,
+//! not ML-KEM. The two use keys and ciphertexts of the same sizes but
+//! derive different shared secrets, so a round-3 peer and an ML-KEM peer
+//! silently disagree on the key instead of failing to parse each other's
+//! messages. To keep them from being mixed up these types are entirely
+//! separate from [`crate::pqc::kyber`]: nothing converts between the two,
+//! and the parameter sets are not listed in [`crate::ALGORITHMS`].
+//!
+//! Round-3 Kyber is non-standard. Use it only to talk to deployments that
+//! have not migrated yet, and plan to remove it once they have; new
+//! protocols should use [`crate::pqc::kyber`].
+//!
+//! # Construction
+//!
+//! FIPS 203 kept the round-3 public-key encryption scheme (K-PKE) and key
+//! formats and changed only the transform around it, so this module reuses
+//! the ML-KEM backend for the lattice arithmetic and applies the round-3
+//! transform on top:
+//!
+//! ```text
+//! encapsulate:  m = SHA3-256(random)
+//!               (ct, K) = ML-KEM.Encaps_internal(pk, m)
+//!               ss = SHAKE256(K || SHA3-256(ct))[..32]
+//! decapsulate:  m' = K-PKE.Decrypt(sk, ct)
+//!               (ct', K') = ML-KEM.Encaps_internal(pk, m')
+//!               ss = SHAKE256((ct' == ct ? K' : z) || SHA3-256(ct))[..32]
+//! ```
+//!
+//! Key generation is ML-KEM's. Round 3 derives the matrix seed from the
+//! random seed without ML-KEM's domain-separation byte, which changes which
+//! key a given seed yields but not the distribution or layout of keys, so
+//! freshly generated keys are valid round-3 keys.
+
+use crate::pqc::kyber::{self as ml_kem, MlKem1024, MlKem512, MlKem768};
+use crate::pqc::KeyEncapsulation;
+use crate::utils;
+use crate::utils::key_sizes::{ml_kem_1024, ml_kem_512, ml_kem_768};
+use crate::utils::secret::Secret;
+use crate::{QraiopError, Result};
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use serde::{Deserialize, Serialize};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Digest, Sha3_256, Shake256};
+use subtle::{ConditionallySelectable, ConstantTimeEq};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+const PUBLIC_KEY_SIZES: [usize; 3] = [
+    ml_kem_512::PUBLIC_KEY_SIZE,
+    ml_kem_768::PUBLIC_KEY_SIZE,
+    ml_kem_1024::PUBLIC_KEY_SIZE,
+];
+const SECRET_KEY_SIZES: [usize; 3] = [
+    ml_kem_512::SECRET_KEY_SIZE,
+    ml_kem_768::SECRET_KEY_SIZE,
+    ml_kem_1024::SECRET_KEY_SIZE,
+];
+const CIPHERTEXT_SIZES: [usize; 3] = [
+    ml_kem_512::CIPHERTEXT_SIZE,
+    ml_kem_768::CIPHERTEXT_SIZE,
+    ml_kem_1024::CIPHERTEXT_SIZE,
+];
+
+// K-PKE decryption from the PQClean sources bundled with `pqcrypto-mlkem`,
+// unchanged since round 3.
+extern "C" {
+    fn PQCLEAN_MLKEM512_CLEAN_indcpa_dec(m: *mut u8, c: *const u8, sk: *const u8);
+    fn PQCLEAN_MLKEM768_CLEAN_indcpa_dec(m: *mut u8, c: *const u8, sk: *const u8);
+    fn PQCLEAN_MLKEM1024_CLEAN_indcpa_dec(m: *mut u8, c: *const u8, sk: *const u8);
+}
+
+/// Round-3 key derivation: SHAKE256 over the pre-key and the ciphertext
+/// hash.
+fn kdf(pre_key: &[u8], ciphertext: &[u8]) -> SharedSecret {
+    let mut shake = Shake256::default();
+    shake.update(pre_key);
+    shake.update(&Sha3_256::digest(ciphertext));
+    let mut ss = vec![0u8; 32];
+    shake.finalize_xof().read(&mut ss);
+    SharedSecret(Secret::new(ss))
+}
+
+fn check_length(what: &str, len: usize, valid: &[usize]) -> Result<()> {
+    if valid.contains(&len) {
+        return Ok(());
+    }
+    Err(QraiopError::InvalidKey(format!(
+        "{} must be one of {:?} bytes, got {}",
+        what, valid, len
+    )))
+}
+
+/// Round-3 Kyber public key.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicKey(Vec<u8>);
+
+impl PublicKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_length("Kyber public key", bytes.len(), &PUBLIC_KEY_SIZES)?;
+        Ok(Self(bytes.to_vec()))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Algorithm and short fingerprint, e.g. `Kyber768-r3 pk:3f:a0:1c:77:09:be:42:d5`.
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let algorithm = match self.0.len() {
+            len if len == PUBLIC_KEY_SIZES[0] => Kyber512::algorithm_name(),
+            len if len == PUBLIC_KEY_SIZES[1] => Kyber768::algorithm_name(),
+            len if len == PUBLIC_KEY_SIZES[2] => Kyber1024::algorithm_name(),
+            _ => "Kyber-r3",
+        };
+        write!(f, "{} pk:{}", algorithm, utils::fingerprint(&self.0))
+    }
+}
+
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PublicKey({})", self)
+    }
+}
+
+/// Round-3 Kyber secret key, wiped from memory on drop.
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SecretKey(Secret);
+
+impl SecretKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_length("Kyber secret key", bytes.len(), &SECRET_KEY_SIZES)?;
+        Ok(Self(bytes.into()))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0.expose_secret()
+    }
+}
+
+/// Round-3 Kyber ciphertext.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ciphertext(Vec<u8>);
+
+impl Ciphertext {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        check_length("Kyber ciphertext", bytes.len(), &CIPHERTEXT_SIZES)
+            .map_err(|e| QraiopError::InvalidCiphertext(e.to_string()))?;
+        Ok(Self(bytes.to_vec()))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Shared secret agreed through round-3 Kyber, wiped from memory on drop.
+#[derive(Debug, Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct SharedSecret(Secret);
+
+impl SharedSecret {
+    /// Raw shared secret bytes. As with ML-KEM, prefer running them through
+    /// a KDF such as [`utils::derive_keys`] to using them as a key directly.
+    pub fn expose_bytes(&self) -> &[u8] {
+        self.0.expose_secret()
+    }
+}
+
+impl AsRef<[u8]> for SharedSecret {
+    fn as_ref(&self) -> &[u8] {
+        self.0.expose_secret()
+    }
+}
+
+macro_rules! kyber_r3 {
+    (
+        $(#[$meta:meta])* $name:ident,
+        $kem:ident,
+        $sizes:ident,
+        $indcpa_dec:ident,
+        $rank:literal,
+        $algorithm:literal
+    ) => {
+        $(#[$meta])*
+        pub struct $name;
+
+        impl KeyEncapsulation for $name {
+            type PublicKey = PublicKey;
+            type SecretKey = SecretKey;
+            type Ciphertext = Ciphertext;
+            type SharedSecret = SharedSecret;
+
+            fn keypair() -> Result<(Self::PublicKey, Self::SecretKey)> {
+                let (pk, sk) = $kem::keypair()?;
+                Ok((
+                    PublicKey(pk.as_bytes().to_vec()),
+                    SecretKey(sk.as_bytes().into()),
+                ))
+            }
+
+            fn encapsulate(
+                public_key: &Self::PublicKey,
+            ) -> Result<(Self::Ciphertext, Self::SharedSecret)> {
+                let pk = ml_kem::PublicKey::from_bytes(&public_key.0)
+                    .map_err(|e| e.with_algorithm($algorithm))?;
+                let mut coins = Zeroizing::new([0u8; 32]);
+                utils::secure_random_into(coins.as_mut())?;
+                // Round 3 never lets raw RNG output into the ciphertext
+                let m = Zeroizing::new(<[u8; 32]>::from(Sha3_256::digest(coins.as_ref())));
+                let (ct, pre_key) = $kem::encapsulate_deterministic(&pk, &m)?;
+                let ss = kdf(pre_key.expose_bytes(), ct.as_bytes());
+                Ok((Ciphertext(ct.as_bytes().to_vec()), ss))
+            }
+
+            fn decapsulate(
+                secret_key: &Self::SecretKey,
+                ciphertext: &Self::Ciphertext,
+            ) -> Result<Self::SharedSecret> {
+                let sk = secret_key.0.expose_secret();
+                if sk.len() != $sizes::SECRET_KEY_SIZE {
+                    return Err(QraiopError::InvalidKey(format!(
+                        "Secret key must be {} bytes, got {}",
+                        $sizes::SECRET_KEY_SIZE,
+                        sk.len()
+                    ))
+                    .with_algorithm($algorithm));
+                }
+                let ct = &ciphertext.0;
+                if ct.len() != $sizes::CIPHERTEXT_SIZE {
+                    return Err(QraiopError::InvalidCiphertext(format!(
+                        "Ciphertext must be {} bytes, got {}",
+                        $sizes::CIPHERTEXT_SIZE,
+                        ct.len()
+                    ))
+                    .with_algorithm($algorithm));
+                }
+
+                // The secret key is the K-PKE key, then pk, H(pk) and z
+                let pk_offset = 384 * $rank;
+                let pk = ml_kem::PublicKey::from_bytes(
+                    &sk[pk_offset..pk_offset + $sizes::PUBLIC_KEY_SIZE],
+                )?;
+                let z = &sk[sk.len() - 32..];
+
+                let mut m = Zeroizing::new([0u8; 32]);
+                // SAFETY: both lengths were checked above, and the K-PKE
+                // secret key is a prefix of the KEM secret key.
+                unsafe { $indcpa_dec(m.as_mut_ptr(), ct.as_ptr(), sk.as_ptr()) };
+                let (reencrypted, pre_key) = $kem::encapsulate_deterministic(&pk, &m)?;
+
+                // Implicit rejection: a ciphertext that does not re-encrypt
+                // to itself yields a key derived from z, chosen in constant
+                // time
+                let valid = reencrypted.as_bytes().ct_eq(ct);
+                let mut key = Zeroizing::new([0u8; 32]);
+                for (k, (&p, &z)) in key.iter_mut().zip(pre_key.expose_bytes().iter().zip(z)) {
+                    *k = u8::conditional_select(&z, &p, valid);
+                }
+                Ok(kdf(key.as_ref(), ct))
+            }
+
+            fn algorithm_name() -> &'static str {
+                $algorithm
+            }
+
+            /// Round-3 Kyber never had a registered identifier.
+            fn algorithm_oid() -> Option<&'static str> {
+                None
+            }
+
+            fn public_key_size() -> usize {
+                $sizes::PUBLIC_KEY_SIZE
+            }
+
+            fn secret_key_size() -> usize {
+                $sizes::SECRET_KEY_SIZE
+            }
+
+            fn ciphertext_size() -> usize {
+                $sizes::CIPHERTEXT_SIZE
+            }
+
+            fn shared_secret_size() -> usize {
+                $sizes::SHARED_SECRET_SIZE
+            }
+        }
+    };
+}
+
+kyber_r3!(
+    /// Round-3 Kyber512 (NIST security category 1). Not ML-KEM-512.
+    Kyber512,
+    MlKem512,
+    ml_kem_512,
+    PQCLEAN_MLKEM512_CLEAN_indcpa_dec,
+    2,
+    "Kyber512-r3"
+);
+kyber_r3!(
+    /// Round-3 Kyber768 (NIST security category 3). Not ML-KEM-768.
+    Kyber768,
+    MlKem768,
+    ml_kem_768,
+    PQCLEAN_MLKEM768_CLEAN_indcpa_dec,
+    3,
+    "Kyber768-r3"
+);
+kyber_r3!(
+    /// Round-3 Kyber1024 (NIST security category 5). Not ML-KEM-1024.
+    Kyber1024,
+    MlKem1024,
+    ml_kem_1024,
+    PQCLEAN_MLKEM1024_CLEAN_indcpa_dec,
+    4,
+    "Kyber1024-r3"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip<K>()
+    where
+        K: KeyEncapsulation<
+            PublicKey = PublicKey,
+            SecretKey = SecretKey,
+            Ciphertext = Ciphertext,
+            SharedSecret = SharedSecret,
+        >,
+    {
+        let (pk, sk) = K::keypair().unwrap();
+        assert_eq!(pk.as_bytes().len(), K::public_key_size());
+        assert_eq!(sk.as_bytes().len(), K::secret_key_size());
+
+        // Through bytes, as a peer on the wire would see them
+        let pk = PublicKey::from_bytes(pk.as_bytes()).unwrap();
+        let (ct, ss) = K::encapsulate(&pk).unwrap();
+        assert_eq!(ct.as_bytes().len(), K::ciphertext_size());
+        let ct = Ciphertext::from_bytes(ct.as_bytes()).unwrap();
+        let sk = SecretKey::from_bytes(sk.as_bytes()).unwrap();
+        assert_eq!(K::decapsulate(&sk, &ct).unwrap(), ss);
+        assert_eq!(ss.expose_bytes().len(), K::shared_secret_size());
+    }
+
+    #[test]
+    fn test_kyber_r3_roundtrip() {
+        roundtrip::<Kyber512>();
+        roundtrip::<Kyber768>();
+        roundtrip::<Kyber1024>();
+    }
+
+    #[test]
+    fn test_kyber_r3_rejects_bad_lengths() {
+        assert!(matches!(
+            PublicKey::from_bytes(&[0; 100]),
+            Err(QraiopError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            Ciphertext::from_bytes(&[0; 100]),
+            Err(QraiopError::InvalidCiphertext(_))
+        ));
+        assert_eq!(Kyber768::algorithm_name(), "Kyber768-r3");
+        assert_eq!(Kyber768::algorithm_oid(), None);
+    }
+
+    #[test]
+    fn test_kyber_r3_implicit_rejection() {
+        let (pk, sk) = Kyber768::keypair().unwrap();
+        let (ct, ss) = Kyber768::encapsulate(&pk).unwrap();
+        let mut tampered = ct.as_bytes().to_vec();
+        tampered[0] ^= 1;
+        let tampered = Ciphertext::from_bytes(&tampered).unwrap();
+        let rejected = Kyber768::decapsulate(&sk, &tampered).unwrap();
+        assert_ne!(rejected, ss);
+        assert_eq!(Kyber768::decapsulate(&sk, &tampered).unwrap(), rejected);
+    }
+
+    #[test]
+    fn test_kyber_r3_differs_from_ml_kem() {
+        // The encodings are the same size, but the shared secrets disagree
+        let (pk, sk) = Kyber768::keypair().unwrap();
+        let (ct, ss) = Kyber768::encapsulate(&pk).unwrap();
+        let ml_kem_sk = ml_kem::SecretKey::from_bytes(sk.as_bytes()).unwrap();
+        let ml_kem_ct = ml_kem::Ciphertext::from_bytes(ct.as_bytes()).unwrap();
+        let ml_kem_ss = MlKem768::decapsulate(&ml_kem_sk, &ml_kem_ct).unwrap();
+        assert_ne!(ml_kem_ss.expose_bytes(), ss.expose_bytes());
+    }
+}
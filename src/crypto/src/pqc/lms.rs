@@ -0,0 +1,640 @@
+//! LMS stateful hash-based signatures
+//!
+//! Leighton-Micali signatures (RFC 8554, NIST SP 800-208) with SHA-256,
+//! 32-byte hashes and Winternitz parameter 4, as a single-level HSS tree.
+//! Keys are backed by the `hbs-lms` crate.
+//!
+//! Unlike SLH-DSA, LMS is stateful: every signature consumes a one-time
+//! key, identified by a leaf index, and signing twice with the same index
+//! breaks the scheme. A [`SecretKey`] therefore tracks the next unused
+//! index and refuses to sign once all `2^h` leaves are spent, returning
+//! [`QraiopError::KeyExhausted`]. Indices are reserved atomically before
+//! signing, so signing from several threads never reuses one; a reserved
+//! index is spent even if signing then fails.
+//!
+//! The secret key deliberately does not implement `Clone`: two copies
+//! would each sign with the same indices. To keep a key across restarts,
+//! persist it with [`SecretKey::save_state`] and [`SecretKey::load_state`]
+//! (requires `std`).
+//!
+//! `hbs-lms` keeps its working buffers on the stack, sized for its largest
+//! parameter sets. Signing in an unoptimized build needs more than the
+//! 2 MiB Rust gives spawned threads by default; give signing threads 8 MiB.
+
+use crate::pqc::{DigitalSignature, HashBasedSignature};
+use crate::utils;
+use crate::utils::key_sizes::{lms_sha256_h10, lms_sha256_h5};
+use crate::utils::secret::Secret;
+use crate::{QraiopError, Result, SecurityLevel};
+use alloc::format;
+#[cfg(feature = "std")]
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+use core::sync::atomic::{AtomicU32, Ordering};
+use hbs_lms::{HssParameter, LmotsAlgorithm, LmsAlgorithm, Seed, Sha256_256};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use sha2::{Digest, Sha256};
+#[cfg(feature = "std")]
+use std::path::Path;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Bytes of the `hbs-lms` private key holding the leaf index.
+const INDEX_SIZE: usize = 8;
+
+/// RFC 8554 type codes of the parameter sets this module generates.
+const LMOTS_SHA256_N32_W4: u32 = 3;
+const LMS_SHA256_M32_H5: u32 = 5;
+const LMS_SHA256_M32_H10: u32 = 6;
+/// Hash size `n = m` and Winternitz chain count `p` of LMOTS_SHA256_N32_W4.
+const HASH_SIZE: usize = 32;
+const LMOTS_CHAINS: usize = 67;
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let field = bytes.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes(field.try_into().ok()?))
+}
+
+/// Tree height of a single-level HSS public key with LM-OTS W4, or `None`
+/// for any other layout:
+///
+/// ```text
+/// levels (4) = 1 || lms_type (4) || lmots_type (4) || I (16) || T[1] (32)
+/// ```
+fn tree_height(public_key: &[u8]) -> Option<usize> {
+    if read_u32(public_key, 0)? != 1 || read_u32(public_key, 8)? != LMOTS_SHA256_N32_W4 {
+        return None;
+    }
+    match read_u32(public_key, 4)? {
+        LMS_SHA256_M32_H5 => Some(5),
+        LMS_SHA256_M32_H10 => Some(10),
+        _ => None,
+    }
+}
+
+/// Whether `signature` is laid out like a signature under `public_key`:
+///
+/// ```text
+/// signed_keys (4) = 0 || q (4) || lmots_type (4) || C (32) || y (67 * 32)
+///   || lms_type (4) || path (height * 32)
+/// ```
+///
+/// `hbs-lms` panics on some malformed signatures instead of rejecting them,
+/// so only signatures passing this check are handed to it.
+fn is_well_formed(signature: &[u8], public_key: &[u8], height: usize) -> bool {
+    let lms_type_offset = 12 + HASH_SIZE * (1 + LMOTS_CHAINS);
+    signature.len() == lms_type_offset + 4 + height * HASH_SIZE
+        && read_u32(signature, 0) == Some(0)
+        && read_u32(signature, 8) == Some(LMOTS_SHA256_N32_W4)
+        && read_u32(signature, lms_type_offset) == read_u32(public_key, 4)
+}
+
+/// HSS verification (public) key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicKey(Vec<u8>);
+
+impl PublicKey {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// LMS signing (secret) key and the index of its next unused leaf.
+///
+/// The key material is wiped from memory on drop.
+pub struct SecretKey {
+    /// `hbs-lms` private key; its leading index bytes are ignored in favour
+    /// of `next_index`.
+    key: Secret,
+    next_index: AtomicU32,
+    max_signatures: u32,
+}
+
+impl SecretKey {
+    fn new(key: &[u8], max_signatures: u32) -> Self {
+        Self {
+            key: key.into(),
+            next_index: AtomicU32::new(0),
+            max_signatures,
+        }
+    }
+
+    /// Claims the next unused leaf, or fails if none are left.
+    fn reserve_index(&self) -> Result<u32> {
+        self.next_index
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |index| {
+                (index < self.max_signatures).then_some(index + 1)
+            })
+            .map_err(|_| QraiopError::KeyExhausted)
+    }
+
+    fn signatures_remaining(&self) -> u64 {
+        u64::from(self.max_signatures - self.next_index.load(Ordering::SeqCst))
+    }
+
+    /// Writes the key and its next unused index to `path`, replacing any
+    /// previous state there, and retires this in-memory copy.
+    ///
+    /// The write is atomic: the state goes to a temporary file next to
+    /// `path`, which is flushed to disk and then renamed over `path`, so a
+    /// crash leaves either the old state or the new one. Once the file is
+    /// written this copy signs nothing more, failing with
+    /// [`QraiopError::KeyExhausted`] instead, so it cannot make signatures
+    /// the file does not record; call [`Self::load_state`] to continue
+    /// signing. While the write is in progress, concurrent signing fails
+    /// the same way.
+    ///
+    /// If the write fails, the key is left as it was and can keep signing
+    /// or be saved again. That includes a temporary file left over from a
+    /// crash, which is never written through: this fails with
+    /// [`QraiopError::IoError`] until it is removed. A leftover file only
+    /// ever holds an earlier state of some key, so once no other process
+    /// is saving to `path`, deleting it is safe.
+    ///
+    /// The file holds the secret key unencrypted, so protect it like one.
+    #[cfg(feature = "std")]
+    pub fn save_state<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        // Claim every remaining index, so nothing is signed past the saved one
+        let next_index = self.next_index.swap(self.max_signatures, Ordering::SeqCst);
+        let written = write_state(path.as_ref(), &self.encode_state(next_index, false));
+        if written.is_err() {
+            self.next_index.store(next_index, Ordering::SeqCst);
+        }
+        written
+    }
+
+    /// Loads a key saved with [`Self::save_state`].
+    ///
+    /// Before returning, the file is atomically marked as in use. A file
+    /// that is already marked belongs to a key that was loaded and never
+    /// saved back, for example because its process crashed, and may have
+    /// signed past the recorded index. Loading it again could reuse an
+    /// index, so it is refused with [`QraiopError::SerializationError`], as
+    /// is a file that is truncated or fails its checksum. The mark is
+    /// written like [`Self::save_state`] writes, so a leftover temporary
+    /// file makes this fail with [`QraiopError::IoError`] too.
+    #[cfg(feature = "std")]
+    pub fn load_state<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let secret_key = Self::decode_state(&Zeroizing::new(std::fs::read(path)?))?;
+        let next_index = secret_key.next_index.load(Ordering::SeqCst);
+        write_state(path, &secret_key.encode_state(next_index, true))?;
+        Ok(secret_key)
+    }
+
+    #[cfg(feature = "std")]
+    fn encode_state(&self, next_index: u32, in_use: bool) -> Zeroizing<Vec<u8>> {
+        let mut state = Zeroizing::new(Vec::with_capacity(STATE_SIZE));
+        state.extend_from_slice(STATE_MAGIC);
+        state.extend_from_slice(&self.max_signatures.to_be_bytes());
+        state.extend_from_slice(&next_index.to_be_bytes());
+        state.push(u8::from(in_use));
+        state.extend_from_slice(self.key.expose_secret());
+        let checksum = Sha256::digest(&state[..]);
+        state.extend_from_slice(&checksum);
+        state
+    }
+
+    #[cfg(feature = "std")]
+    fn decode_state(state: &[u8]) -> Result<Self> {
+        let corrupt = |what: &str| {
+            QraiopError::SerializationError(format!("LMS state file is corrupt: {}", what))
+        };
+        if state.len() != STATE_SIZE {
+            return Err(corrupt("wrong length"));
+        }
+        let (body, checksum) = state.split_at(STATE_SIZE - 32);
+        if Sha256::digest(body).as_slice() != checksum {
+            return Err(corrupt("checksum mismatch"));
+        }
+        let (magic, rest) = body.split_at(STATE_MAGIC.len());
+        if magic != STATE_MAGIC {
+            return Err(corrupt("not an LMS state file"));
+        }
+        let max_signatures = u32::from_be_bytes(rest[0..4].try_into().unwrap());
+        let next_index = u32::from_be_bytes(rest[4..8].try_into().unwrap());
+        if ![LmsSha256H5::MAX_SIGNATURES, LmsSha256H10::MAX_SIGNATURES].contains(&max_signatures)
+            || next_index > max_signatures
+            || rest[8] > 1
+        {
+            return Err(corrupt("invalid header"));
+        }
+        if rest[8] == 1 {
+            return Err(QraiopError::SerializationError(
+                "LMS state file is still marked in use: the key was loaded and never saved \
+                 back, so it may have signed past the recorded index"
+                    .to_string(),
+            ));
+        }
+        let mut secret_key = Self::new(&rest[9..], max_signatures);
+        *secret_key.next_index.get_mut() = next_index;
+        Ok(secret_key)
+    }
+}
+
+#[cfg(feature = "std")]
+const STATE_MAGIC: &[u8; 8] = b"QRLMSST1";
+#[cfg(feature = "std")]
+const STATE_SIZE: usize = STATE_MAGIC.len() + 4 + 4 + 1 + lms_sha256_h5::SECRET_KEY_SIZE + 32;
+
+/// Atomically replaces `path` with `contents`: temporary file, fsync,
+/// rename, then fsync of the directory so the rename itself is durable.
+///
+/// The temporary file must not exist yet, so a symlink planted in its
+/// place is never followed, and on Unix it is created owner-only, since it
+/// holds the secret key.
+#[cfg(feature = "std")]
+fn write_state(path: &Path, contents: &[u8]) -> Result<()> {
+    use std::fs::{self, File, OpenOptions};
+    use std::io::Write;
+
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let temporary = Path::new(&temporary);
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(temporary)?;
+    let written = file.write_all(contents).and_then(|()| file.sync_all());
+    drop(file);
+    if let Err(e) = written {
+        let _ = fs::remove_file(temporary);
+        return Err(e.into());
+    }
+    fs::rename(temporary, path)?;
+    #[cfg(unix)]
+    if let Some(directory) = path.parent() {
+        let directory = if directory.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            directory
+        };
+        File::open(directory)?.sync_all()?;
+    }
+    Ok(())
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SecretKey([REDACTED; {}], {} of {} signatures left)",
+            self.key.len(),
+            self.signatures_remaining(),
+            self.max_signatures
+        )
+    }
+}
+
+/// Detached HSS signature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Signature(Vec<u8>);
+
+impl Signature {
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self(bytes.to_vec())
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+macro_rules! lms {
+    (
+        $(#[$meta:meta])* $name:ident,
+        $tree:ident,
+        $height:literal,
+        $sizes:ident,
+        $algorithm:literal
+    ) => {
+        $(#[$meta])*
+        pub struct $name;
+
+        impl $name {
+            const MAX_SIGNATURES: u32 = 1 << $height;
+        }
+
+        impl DigitalSignature for $name {
+            type PublicKey = PublicKey;
+            type SecretKey = SecretKey;
+            type Signature = Signature;
+
+            fn keypair() -> Result<(Self::PublicKey, Self::SecretKey)> {
+                let parameters = [HssParameter::<Sha256_256>::new(
+                    LmotsAlgorithm::LmotsW4,
+                    LmsAlgorithm::$tree,
+                )];
+                let mut seed = Seed::default();
+                let keypair = utils::keygen_seed(&mut utils::SystemRng, seed.as_mut_slice())
+                    .map_err(|e| e.with_algorithm($algorithm))
+                    .and_then(|()| {
+                        hbs_lms::keygen::<Sha256_256>(&parameters, &seed, None).map_err(|_| {
+                            QraiopError::KeyGenerationFailed(format!(
+                                "{} key generation failed",
+                                $algorithm
+                            ))
+                        })
+                    });
+                seed.as_mut_slice().zeroize();
+                let (mut signing_key, verifying_key) = keypair?;
+                let secret_key = SecretKey::new(signing_key.as_slice(), Self::MAX_SIGNATURES);
+                signing_key.as_mut_slice().zeroize();
+                Ok((PublicKey(verifying_key.as_slice().to_vec()), secret_key))
+            }
+
+            fn sign(secret_key: &Self::SecretKey, message: &[u8]) -> Result<Self::Signature> {
+                if secret_key.max_signatures != Self::MAX_SIGNATURES {
+                    return Err(QraiopError::InvalidKey(format!(
+                        "Secret key is not an {} key",
+                        $algorithm
+                    )));
+                }
+                let index = secret_key.reserve_index()?;
+                let mut private_key = Zeroizing::new(secret_key.key.expose_secret().to_vec());
+                private_key[..INDEX_SIZE].copy_from_slice(&u64::from(index).to_be_bytes());
+                // The index is tracked by `secret_key`, so the backend's
+                // updated private key is not needed.
+                let signature =
+                    hbs_lms::sign::<Sha256_256>(message, &private_key, &mut |_| Ok(()), None)
+                        .map_err(|_| {
+                            QraiopError::CryptoError(format!("{} signing failed", $algorithm))
+                        })?;
+                Ok(Signature(signature.as_ref().to_vec()))
+            }
+
+            fn verify(
+                public_key: &Self::PublicKey,
+                message: &[u8],
+                signature: &Self::Signature,
+            ) -> Result<bool> {
+                if public_key.0.len() != $sizes::PUBLIC_KEY_SIZE {
+                    return Err(QraiopError::InvalidKey(format!(
+                        "{} public key must be {} bytes, got {}",
+                        $algorithm,
+                        $sizes::PUBLIC_KEY_SIZE,
+                        public_key.0.len()
+                    )));
+                }
+                let height = tree_height(&public_key.0).ok_or_else(|| {
+                    QraiopError::InvalidKey(format!("Malformed {} public key", $algorithm))
+                })?;
+                if !is_well_formed(&signature.0, &public_key.0, height) {
+                    return Ok(false);
+                }
+                Ok(hbs_lms::verify::<Sha256_256>(message, &signature.0, &public_key.0).is_ok())
+            }
+
+            fn algorithm_name() -> &'static str {
+                $algorithm
+            }
+
+            /// `id-alg-hss-lms-hashsig` (RFC 8708), shared by every HSS/LMS
+            /// parameter set; the key encodes which one it is.
+            fn algorithm_oid() -> Option<&'static str> {
+                Some("1.2.840.113549.1.9.16.3.17")
+            }
+
+            /// SHA-256 with 32-byte outputs is category 5 per SP 800-208.
+            fn security_level() -> SecurityLevel {
+                SecurityLevel::Level5
+            }
+
+            fn public_key_size() -> usize {
+                $sizes::PUBLIC_KEY_SIZE
+            }
+
+            fn secret_key_size() -> usize {
+                $sizes::SECRET_KEY_SIZE
+            }
+
+            fn signature_size() -> usize {
+                $sizes::SIGNATURE_SIZE
+            }
+        }
+
+        impl HashBasedSignature for $name {
+            fn max_signatures() -> u64 {
+                u64::from(Self::MAX_SIGNATURES)
+            }
+
+            fn signatures_remaining(secret_key: &Self::SecretKey) -> u64 {
+                secret_key.signatures_remaining()
+            }
+        }
+    };
+}
+
+lms!(
+    /// LMS_SHA256_M32_H5 with LMOTS_SHA256_N32_W4: 32 signatures per key.
+    LmsSha256H5,
+    LmsH5,
+    5,
+    lms_sha256_h5,
+    "LMS-SHA256-H5"
+);
+lms!(
+    /// LMS_SHA256_M32_H10 with LMOTS_SHA256_N32_W4: 1024 signatures per key.
+    LmsSha256H10,
+    LmsH10,
+    10,
+    lms_sha256_h10,
+    "LMS-SHA256-H10"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs `f` with a main-thread-sized stack; see the module docs.
+    fn on_large_stack<F: FnOnce() + Send + 'static>(f: F) {
+        std::thread::Builder::new()
+            .stack_size(8 << 20)
+            .spawn(f)
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_sign_until_exhausted() {
+        on_large_stack(sign_until_exhausted);
+    }
+
+    fn sign_until_exhausted() {
+        let (pk, sk) = LmsSha256H5::keypair().unwrap();
+        assert_eq!(LmsSha256H5::max_signatures(), 32);
+        assert_eq!(pk.as_bytes().len(), lms_sha256_h5::PUBLIC_KEY_SIZE);
+
+        let mut signatures = Vec::new();
+        for signed in 0..32 {
+            assert_eq!(LmsSha256H5::signatures_remaining(&sk), 32 - signed);
+            let message = format!("message {}", signed);
+            let signature = LmsSha256H5::sign(&sk, message.as_bytes()).unwrap();
+            assert_eq!(signature.as_bytes().len(), lms_sha256_h5::SIGNATURE_SIZE);
+            assert!(LmsSha256H5::verify(&pk, message.as_bytes(), &signature).unwrap());
+            assert!(!LmsSha256H5::verify(&pk, b"other message", &signature).unwrap());
+            signatures.push(signature);
+        }
+        assert_eq!(LmsSha256H5::signatures_remaining(&sk), 0);
+        assert!(matches!(
+            LmsSha256H5::sign(&sk, b"one too many"),
+            Err(QraiopError::KeyExhausted)
+        ));
+
+        // Every signature used its own leaf; the index follows the 4-byte
+        // HSS level count
+        let mut indices: Vec<&[u8]> = signatures.iter().map(|s| &s.as_bytes()[4..8]).collect();
+        indices.sort_unstable();
+        indices.dedup();
+        assert_eq!(indices.len(), 32);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_state_survives_reload_without_reusing_indices() {
+        on_large_stack(state_survives_reload_without_reusing_indices);
+    }
+
+    #[cfg(feature = "std")]
+    fn state_survives_reload_without_reusing_indices() {
+        let path =
+            std::env::temp_dir().join(format!("qraiop-lms-state-{}.bin", std::process::id()));
+        let leaf = |signature: &Signature| signature.as_bytes()[4..8].to_vec();
+
+        let (pk, sk) = LmsSha256H5::keypair().unwrap();
+        let mut leaves: Vec<Vec<u8>> = (0..3)
+            .map(|_| leaf(&LmsSha256H5::sign(&sk, b"before").unwrap()))
+            .collect();
+        sk.save_state(&path).unwrap();
+
+        let sk = SecretKey::load_state(&path).unwrap();
+        assert_eq!(LmsSha256H5::signatures_remaining(&sk), 29);
+        // The file is marked in use until saved again
+        assert!(matches!(
+            SecretKey::load_state(&path),
+            Err(QraiopError::SerializationError(_))
+        ));
+        for _ in 0..2 {
+            let signature = LmsSha256H5::sign(&sk, b"after").unwrap();
+            assert!(LmsSha256H5::verify(&pk, b"after", &signature).unwrap());
+            leaves.push(leaf(&signature));
+        }
+        sk.save_state(&path).unwrap();
+        // The saved copy is retired
+        assert!(matches!(
+            LmsSha256H5::sign(&sk, b"after"),
+            Err(QraiopError::KeyExhausted)
+        ));
+        assert_eq!(LmsSha256H5::signatures_remaining(&sk), 0);
+        let sk = SecretKey::load_state(&path).unwrap();
+        leaves.push(leaf(&LmsSha256H5::sign(&sk, b"again").unwrap()));
+        sk.save_state(&path).unwrap();
+
+        let count = leaves.len();
+        leaves.sort();
+        leaves.dedup();
+        assert_eq!(leaves.len(), count);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // Flipping any byte fails the checksum
+        let mut state = std::fs::read(&path).unwrap();
+        state[20] ^= 1;
+        std::fs::write(&path, &state).unwrap();
+        assert!(matches!(
+            SecretKey::load_state(&path),
+            Err(QraiopError::SerializationError(_))
+        ));
+        std::fs::write(&path, &state[..40]).unwrap();
+        assert!(matches!(
+            SecretKey::load_state(&path),
+            Err(QraiopError::SerializationError(_))
+        ));
+
+        // A leftover temporary file is never written through, and the key
+        // survives the failed save
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        std::fs::write(&temporary, b"stale").unwrap();
+        let (pk, sk) = LmsSha256H5::keypair().unwrap();
+        LmsSha256H5::sign(&sk, b"before").unwrap();
+        assert!(matches!(sk.save_state(&path), Err(QraiopError::IoError(_))));
+        assert_eq!(std::fs::read(&temporary).unwrap(), b"stale");
+        assert_eq!(LmsSha256H5::signatures_remaining(&sk), 31);
+        let signature = LmsSha256H5::sign(&sk, b"after").unwrap();
+        assert!(LmsSha256H5::verify(&pk, b"after", &signature).unwrap());
+
+        // Once it is removed, saving succeeds
+        std::fs::remove_file(&temporary).unwrap();
+        sk.save_state(&path).unwrap();
+        let sk = SecretKey::load_state(&path).unwrap();
+        assert_eq!(LmsSha256H5::signatures_remaining(&sk), 30);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_malformed_input_is_rejected() {
+        on_large_stack(malformed_input_is_rejected);
+    }
+
+    fn malformed_input_is_rejected() {
+        let (pk, sk) = LmsSha256H5::keypair().unwrap();
+        let signature = LmsSha256H5::sign(&sk, b"message").unwrap();
+        let reject = |bytes: &[u8]| {
+            assert!(!LmsSha256H5::verify(&pk, b"message", &Signature::from_bytes(bytes)).unwrap());
+        };
+
+        // Inputs like these make `hbs-lms` panic if they reach it
+        reject(&[]);
+        reject(&[0xff; lms_sha256_h5::SIGNATURE_SIZE]);
+        let mut bad_type = signature.as_bytes().to_vec();
+        bad_type[11] = 0x42;
+        reject(&bad_type);
+        reject(&signature.as_bytes()[..100]);
+        let mut bad_leaf = signature.as_bytes().to_vec();
+        bad_leaf[4] = 0xff;
+        reject(&bad_leaf);
+
+        let mut bytes = bincode::serialize(&pk).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last - 52] ^= 0x01;
+        let bad_pk: PublicKey = bincode::deserialize(&bytes).unwrap();
+        assert!(matches!(
+            LmsSha256H5::verify(&bad_pk, b"message", &signature),
+            Err(QraiopError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_metadata() {
+        on_large_stack(metadata);
+    }
+
+    fn metadata() {
+        assert_eq!(LmsSha256H5::algorithm_name(), "LMS-SHA256-H5");
+        assert_eq!(LmsSha256H10::algorithm_name(), "LMS-SHA256-H10");
+        assert_eq!(LmsSha256H10::max_signatures(), 1024);
+        assert_eq!(LmsSha256H5::security_level(), SecurityLevel::Level5);
+        assert_eq!(
+            LmsSha256H10::signature_size(),
+            lms_sha256_h10::SIGNATURE_SIZE
+        );
+
+        let (_, sk) = LmsSha256H5::keypair().unwrap();
+        assert_eq!(sk.key.len(), LmsSha256H5::secret_key_size());
+        assert!(format!("{:?}", sk).contains("32 of 32 signatures left"));
+        // A key is bound to its parameter set
+        assert!(matches!(
+            LmsSha256H10::sign(&sk, b"message"),
+            Err(QraiopError::InvalidKey(_))
+        ));
+    }
+}
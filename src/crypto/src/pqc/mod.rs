@@ -1,6 +1,32 @@
 //! Post-Quantum Cryptography implementations
 
+#[cfg(feature = "dilithium")]
+pub mod dilithium;
+mod keypair;
+#[cfg(feature = "kyber")]
 pub mod kyber;
+#[cfg(feature = "legacy_kyber")]
+pub mod legacy_kyber;
+pub mod lms;
+#[cfg(feature = "sphincs")]
+pub mod sphincs;
+
+pub use keypair::{KeyPair, SigningKeyPair};
+
+use crate::{QraiopError, SecurityLevel};
+use alloc::boxed::Box;
+#[cfg(feature = "std")]
+use alloc::format;
+#[cfg(feature = "std")]
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use std::time::Instant;
+use zeroize::Zeroizing;
 
 // Make the trait public so it can be used in main.rs
 pub trait KeyEncapsulation {
@@ -17,12 +43,838 @@ pub trait KeyEncapsulation {
         secret_key: &Self::SecretKey,
         ciphertext: &Self::Ciphertext,
     ) -> crate::Result<Self::SharedSecret>;
+
+    fn algorithm_name() -> &'static str;
+    /// Dotted ASN.1 object identifier registered for the algorithm, e.g. in
+    /// the NIST CSOR arc for ML-KEM and ML-DSA, for X.509 and PKCS#8
+    /// interop. `None` for constructions with no registered identifier.
+    fn algorithm_oid() -> Option<&'static str>;
+
+    /// Length in bytes of an encoded public key.
+    fn public_key_size() -> usize;
+    /// Length in bytes of an encoded secret key.
+    fn secret_key_size() -> usize;
+    /// Length in bytes of a ciphertext.
+    fn ciphertext_size() -> usize;
+    /// Length in bytes of the shared secret.
+    fn shared_secret_size() -> usize;
+}
+
+/// Object-safe form of [`KeyEncapsulation`] over encoded bytes, for
+/// choosing a KEM at runtime and storing it as `Box<dyn DynKem>`.
+///
+/// Every `KeyEncapsulation` whose key and ciphertext types convert to and
+/// from bytes gets this through a blanket impl. Secret keys and shared
+/// secrets come back as [`Zeroizing`] buffers; inputs are parsed with the
+/// same length checks as the typed API.
+pub trait DynKem {
+    fn algorithm_name(&self) -> &'static str;
+    /// Returns `(public_key, secret_key)`.
+    fn keypair(&self) -> crate::Result<(Vec<u8>, Zeroizing<Vec<u8>>)>;
+    /// Returns `(ciphertext, shared_secret)`.
+    fn encapsulate(&self, public_key: &[u8]) -> crate::Result<(Vec<u8>, Zeroizing<Vec<u8>>)>;
+    fn decapsulate(
+        &self,
+        secret_key: &[u8],
+        ciphertext: &[u8],
+    ) -> crate::Result<Zeroizing<Vec<u8>>>;
+}
+
+impl<K> DynKem for K
+where
+    K: KeyEncapsulation,
+    K::PublicKey: AsRef<[u8]> + TryFrom<Vec<u8>, Error = QraiopError>,
+    K::SecretKey: AsRef<[u8]> + TryFrom<Vec<u8>, Error = QraiopError>,
+    K::Ciphertext: AsRef<[u8]> + TryFrom<Vec<u8>, Error = QraiopError>,
+    K::SharedSecret: AsRef<[u8]>,
+{
+    fn algorithm_name(&self) -> &'static str {
+        K::algorithm_name()
+    }
+
+    fn keypair(&self) -> crate::Result<(Vec<u8>, Zeroizing<Vec<u8>>)> {
+        let (pk, sk) = K::keypair()?;
+        Ok((pk.as_ref().to_vec(), Zeroizing::new(sk.as_ref().to_vec())))
+    }
+
+    fn encapsulate(&self, public_key: &[u8]) -> crate::Result<(Vec<u8>, Zeroizing<Vec<u8>>)> {
+        let pk = K::PublicKey::try_from(public_key.to_vec())?;
+        let (ct, ss) = K::encapsulate(&pk)?;
+        Ok((ct.as_ref().to_vec(), Zeroizing::new(ss.as_ref().to_vec())))
+    }
+
+    fn decapsulate(
+        &self,
+        secret_key: &[u8],
+        ciphertext: &[u8],
+    ) -> crate::Result<Zeroizing<Vec<u8>>> {
+        // `try_from` takes ownership of the copy and wipes it on failure
+        let sk = K::SecretKey::try_from(secret_key.to_vec())?;
+        let ct = K::Ciphertext::try_from(ciphertext.to_vec())?;
+        let ss = K::decapsulate(&sk, &ct)?;
+        Ok(Zeroizing::new(ss.as_ref().to_vec()))
+    }
+}
+
+/// Looks up a KEM by its algorithm name, e.g. `"ML-KEM-768"`. Returns `None`
+/// for unknown names and for families compiled out by feature flags.
+pub fn kem_by_name(name: &str) -> Option<Box<dyn DynKem>> {
+    match name {
+        #[cfg(feature = "kyber")]
+        "ML-KEM-512" => Some(Box::new(kyber::MlKem512)),
+        #[cfg(feature = "kyber")]
+        "ML-KEM-768" => Some(Box::new(kyber::MlKem768)),
+        #[cfg(feature = "kyber")]
+        "ML-KEM-1024" => Some(Box::new(kyber::MlKem1024)),
+        _ => None,
+    }
 }
 
 pub trait DigitalSignature {
-    // Placeholder for future implementation
+    type PublicKey;
+    type SecretKey;
+    type Signature;
+
+    fn keypair() -> crate::Result<(Self::PublicKey, Self::SecretKey)>;
+    fn sign(secret_key: &Self::SecretKey, message: &[u8]) -> crate::Result<Self::Signature>;
+    /// Returns `Ok(false)` for a signature that does not verify; `Err` is
+    /// kept for malformed inputs. Treating any `Ok` as success accepts
+    /// forgeries, so prefer [`Self::verify_strict`] unless the boolean is
+    /// really what you want.
+    ///
+    /// Like every boolean verification in this crate this is `#[must_use]`,
+    /// so discarding the result, e.g. with a bare `?`, is a warning.
+    #[must_use = "verification result must be checked"]
+    fn verify(
+        public_key: &Self::PublicKey,
+        message: &[u8],
+        signature: &Self::Signature,
+    ) -> crate::Result<bool>;
+
+    /// Like [`Self::verify`], but an invalid signature is an error:
+    /// `Ok(())` means the signature is valid, anything else fails with
+    /// [`crate::QraiopError::SignatureVerificationFailed`] or the error
+    /// `verify` reported.
+    fn verify_strict(
+        public_key: &Self::PublicKey,
+        message: &[u8],
+        signature: &Self::Signature,
+    ) -> crate::Result<()> {
+        if Self::verify(public_key, message, signature)? {
+            Ok(())
+        } else {
+            Err(crate::QraiopError::SignatureVerificationFailed)
+        }
+    }
+    fn algorithm_name() -> &'static str;
+    /// Dotted ASN.1 object identifier registered for the algorithm, e.g. in
+    /// the NIST CSOR arc for ML-DSA, for X.509 and PKCS#8 interop. `None`
+    /// for constructions with no registered identifier, and for backends
+    /// that do not implement the standard the identifier names.
+    fn algorithm_oid() -> Option<&'static str>;
+    fn security_level() -> SecurityLevel;
+
+    /// Length in bytes of an encoded public key.
+    fn public_key_size() -> usize;
+    /// Length in bytes of an encoded secret key.
+    fn secret_key_size() -> usize;
+    /// Length in bytes of a signature. For SLH-DSA this runs to tens of
+    /// kilobytes, so prefer heap buffers when sizing from it.
+    fn signature_size() -> usize;
 }
 
+/// Hash-based signature schemes, which may bound how many messages a single
+/// key is allowed to sign.
 pub trait HashBasedSignature: DigitalSignature {
-    // Placeholder for future implementation
+    /// Total number of signatures a single key may ever produce.
+    fn max_signatures() -> u64;
+    /// Number of signatures the given key can still produce.
+    fn signatures_remaining(secret_key: &Self::SecretKey) -> u64;
+}
+
+/// Generates `count` independent key pairs of `K` across the rayon thread
+/// pool. If any key generation fails, one of the errors is returned and the
+/// remaining pairs are discarded.
+///
+/// Only available with the `rayon` feature.
+#[cfg(feature = "rayon")]
+pub fn generate_keypairs_parallel<K>(
+    count: usize,
+) -> crate::Result<Vec<(K::PublicKey, K::SecretKey)>>
+where
+    K: KeyEncapsulation,
+    K::PublicKey: Send,
+    K::SecretKey: Send,
+{
+    (0..count).into_par_iter().map(|_| K::keypair()).collect()
+}
+
+#[cfg(feature = "std")]
+/// Summary of repeated timings of one operation, in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimingStats {
+    pub mean_ms: f64,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    /// Sample standard deviation, 0 for a single sample.
+    #[serde(default)]
+    pub std_dev_ms: f64,
+}
+
+#[cfg(feature = "std")]
+impl TimingStats {
+    fn from_samples(mut samples: Vec<f64>) -> Self {
+        samples.sort_by(f64::total_cmp);
+        let n = samples.len();
+        let median_ms = if n.is_multiple_of(2) {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2.0
+        } else {
+            samples[n / 2]
+        };
+        let mean_ms = samples.iter().sum::<f64>() / n as f64;
+        let std_dev_ms = if n > 1 {
+            let squares = samples.iter().map(|x| (x - mean_ms).powi(2)).sum::<f64>();
+            (squares / (n - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+        TimingStats {
+            mean_ms,
+            min_ms: samples[0],
+            median_ms,
+            std_dev_ms,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+/// Timings and encoded sizes (in bytes) from benchmarking an algorithm's
+/// operations. Fields that don't apply to the algorithm's kind are `None`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceMetrics {
+    pub algorithm: String,
+    /// Timed runs of each operation.
+    pub iterations: usize,
+    /// Untimed runs made before measuring.
+    #[serde(default)]
+    pub warmup: usize,
+    pub keypair: TimingStats,
+    pub encapsulate: Option<TimingStats>,
+    pub decapsulate: Option<TimingStats>,
+    pub sign: Option<TimingStats>,
+    pub verify: Option<TimingStats>,
+    pub public_key_size: usize,
+    pub secret_key_size: usize,
+    pub ciphertext_size: Option<usize>,
+    pub signature_size: Option<usize>,
+    /// KEMs only: encoded ciphertext length over shared secret length,
+    /// i.e. the bytes sent per byte of key agreed (34.0 for ML-KEM-768).
+    #[serde(default)]
+    pub ciphertext_overhead_ratio: Option<f64>,
+    /// KEMs only: encoded public key length over shared secret length.
+    #[serde(default)]
+    pub public_key_to_secret_ratio: Option<f64>,
+}
+
+#[cfg(feature = "std")]
+impl PerformanceMetrics {
+    pub fn to_json(&self) -> crate::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> crate::Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Lists the operations whose median time grew by more than
+    /// `threshold_pct` percent over `baseline`, one line per operation
+    /// naming it and both medians. Operations missing from either run are
+    /// skipped, and an empty list means no regression.
+    pub fn regression_against(
+        &self,
+        baseline: &PerformanceMetrics,
+        threshold_pct: f64,
+    ) -> Vec<String> {
+        let operations = [
+            ("keypair", Some(self.keypair), Some(baseline.keypair)),
+            ("encapsulate", self.encapsulate, baseline.encapsulate),
+            ("decapsulate", self.decapsulate, baseline.decapsulate),
+            ("sign", self.sign, baseline.sign),
+            ("verify", self.verify, baseline.verify),
+        ];
+        operations
+            .into_iter()
+            .filter_map(|(name, current, baseline)| {
+                let (current, baseline) = (current?.median_ms, baseline?.median_ms);
+                let change_pct = (current - baseline) / baseline * 100.0;
+                (change_pct > threshold_pct).then(|| {
+                    format!(
+                        "{}: median {:.3} ms -> {:.3} ms (+{:.1}%)",
+                        name, baseline, current, change_pct
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "std")]
+/// How many times a benchmark runs each operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchmarkConfig {
+    /// Runs discarded before measuring, so cold caches and first
+    /// allocations don't skew the samples.
+    pub warmup: usize,
+    /// Timed runs; must be at least 1.
+    pub samples: usize,
+}
+
+#[cfg(feature = "std")]
+fn check_iterations(iterations: usize) -> crate::Result<()> {
+    if iterations == 0 {
+        return Err(crate::QraiopError::CryptoError(
+            "Benchmark iterations must be at least 1".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+fn serialized_size<T: Serialize>(value: &T) -> crate::Result<usize> {
+    Ok(bincode::serialize(value)?.len())
+}
+
+#[cfg(feature = "std")]
+/// Runs `f` once, appending its duration in milliseconds to `samples`.
+fn timed<T>(samples: &mut Vec<f64>, f: impl FnOnce() -> crate::Result<T>) -> crate::Result<T> {
+    let start = Instant::now();
+    let result = f()?;
+    samples.push(start.elapsed().as_secs_f64() * 1000.0);
+    Ok(result)
+}
+
+#[cfg(feature = "std")]
+/// Times key generation, encapsulation and decapsulation of `K`, each run
+/// `iterations` times on fresh keys, without warmup.
+pub fn benchmark_kem<K>(iterations: usize) -> crate::Result<PerformanceMetrics>
+where
+    K: KeyEncapsulation,
+    K::PublicKey: Serialize,
+    K::SecretKey: AsRef<[u8]>,
+    K::Ciphertext: Serialize,
+{
+    benchmark_kem_with_config::<K>(BenchmarkConfig {
+        warmup: 0,
+        samples: iterations,
+    })
+}
+
+#[cfg(feature = "std")]
+/// Like [`benchmark_kem`], but first runs all three operations
+/// `config.warmup` times untimed.
+pub fn benchmark_kem_with_config<K>(config: BenchmarkConfig) -> crate::Result<PerformanceMetrics>
+where
+    K: KeyEncapsulation,
+    K::PublicKey: Serialize,
+    K::SecretKey: AsRef<[u8]>,
+    K::Ciphertext: Serialize,
+{
+    let iterations = config.samples;
+    check_iterations(iterations)?;
+    for _ in 0..config.warmup {
+        let (pk, sk) = K::keypair()?;
+        let (ct, _) = K::encapsulate(&pk)?;
+        K::decapsulate(&sk, &ct)?;
+    }
+    let mut keypair = Vec::with_capacity(iterations);
+    let mut encapsulate = Vec::with_capacity(iterations);
+    let mut decapsulate = Vec::with_capacity(iterations);
+    let mut last = None;
+
+    for _ in 0..iterations {
+        let (pk, sk) = timed(&mut keypair, K::keypair)?;
+        let (ct, _) = timed(&mut encapsulate, || K::encapsulate(&pk))?;
+        timed(&mut decapsulate, || K::decapsulate(&sk, &ct))?;
+        last = Some((pk, sk, ct));
+    }
+
+    let (pk, sk, ct) = last.expect("at least one iteration ran");
+    Ok(PerformanceMetrics {
+        algorithm: K::algorithm_name().to_string(),
+        iterations,
+        warmup: config.warmup,
+        keypair: TimingStats::from_samples(keypair),
+        encapsulate: Some(TimingStats::from_samples(encapsulate)),
+        decapsulate: Some(TimingStats::from_samples(decapsulate)),
+        sign: None,
+        verify: None,
+        public_key_size: serialized_size(&pk)?,
+        // Secret keys are never serialized, so measure the raw key material
+        secret_key_size: sk.as_ref().len(),
+        ciphertext_size: Some(serialized_size(&ct)?),
+        signature_size: None,
+        // From the raw encodings, as the sizes above include serde framing
+        ciphertext_overhead_ratio: Some(
+            K::ciphertext_size() as f64 / K::shared_secret_size() as f64,
+        ),
+        public_key_to_secret_ratio: Some(
+            K::public_key_size() as f64 / K::shared_secret_size() as f64,
+        ),
+    })
+}
+
+#[cfg(feature = "std")]
+/// Times key generation, signing and verification of `S` over a short
+/// message, each run `iterations` times on fresh keys, without warmup.
+pub fn benchmark_signature<S>(iterations: usize) -> crate::Result<PerformanceMetrics>
+where
+    S: DigitalSignature,
+    S::PublicKey: Serialize,
+    S::SecretKey: AsRef<[u8]>,
+    S::Signature: Serialize,
+{
+    benchmark_signature_with_config::<S>(BenchmarkConfig {
+        warmup: 0,
+        samples: iterations,
+    })
+}
+
+#[cfg(feature = "std")]
+/// Like [`benchmark_signature`], but first runs all three operations
+/// `config.warmup` times untimed.
+pub fn benchmark_signature_with_config<S>(
+    config: BenchmarkConfig,
+) -> crate::Result<PerformanceMetrics>
+where
+    S: DigitalSignature,
+    S::PublicKey: Serialize,
+    S::SecretKey: AsRef<[u8]>,
+    S::Signature: Serialize,
+{
+    let iterations = config.samples;
+    check_iterations(iterations)?;
+    let message = b"QRAIOP benchmark message";
+    for _ in 0..config.warmup {
+        let (pk, sk) = S::keypair()?;
+        let signature = S::sign(&sk, message)?;
+        S::verify(&pk, message, &signature)?;
+    }
+    let mut keypair = Vec::with_capacity(iterations);
+    let mut sign = Vec::with_capacity(iterations);
+    let mut verify = Vec::with_capacity(iterations);
+    let mut last = None;
+
+    for _ in 0..iterations {
+        let (pk, sk) = timed(&mut keypair, S::keypair)?;
+        let signature = timed(&mut sign, || S::sign(&sk, message))?;
+        timed(&mut verify, || S::verify(&pk, message, &signature))?;
+        last = Some((pk, sk, signature));
+    }
+
+    let (pk, sk, signature) = last.expect("at least one iteration ran");
+    Ok(PerformanceMetrics {
+        algorithm: S::algorithm_name().to_string(),
+        iterations,
+        warmup: config.warmup,
+        keypair: TimingStats::from_samples(keypair),
+        encapsulate: None,
+        decapsulate: None,
+        sign: Some(TimingStats::from_samples(sign)),
+        verify: Some(TimingStats::from_samples(verify)),
+        public_key_size: serialized_size(&pk)?,
+        secret_key_size: sk.as_ref().len(),
+        ciphertext_size: None,
+        signature_size: Some(serialized_size(&signature)?),
+        ciphertext_overhead_ratio: None,
+        public_key_to_secret_ratio: None,
+    })
+}
+
+#[cfg(feature = "std")]
+/// Benchmarks every algorithm in [`crate::ALGORITHMS`], in that order, with
+/// `iterations` runs each.
+///
+/// LMS is left out: it is stateful, and each run would spend one-time
+/// signatures of a fresh key for no benefit.
+pub fn benchmark_all(iterations: usize) -> crate::Result<Vec<PerformanceMetrics>> {
+    let mut metrics = Vec::with_capacity(crate::ALGORITHMS.len());
+    #[cfg(feature = "kyber")]
+    {
+        use kyber::{MlKem1024, MlKem512, MlKem768};
+        metrics.push(benchmark_kem::<MlKem512>(iterations)?);
+        metrics.push(benchmark_kem::<MlKem768>(iterations)?);
+        metrics.push(benchmark_kem::<MlKem1024>(iterations)?);
+    }
+    #[cfg(feature = "dilithium")]
+    {
+        use dilithium::{MlDsa44, MlDsa65, MlDsa87};
+        metrics.push(benchmark_signature::<MlDsa44>(iterations)?);
+        metrics.push(benchmark_signature::<MlDsa65>(iterations)?);
+        metrics.push(benchmark_signature::<MlDsa87>(iterations)?);
+    }
+    #[cfg(feature = "sphincs")]
+    {
+        use sphincs::{SlhDsa128s, SlhDsa192s, SlhDsa256s};
+        metrics.push(benchmark_signature::<SlhDsa128s>(iterations)?);
+        metrics.push(benchmark_signature::<SlhDsa192s>(iterations)?);
+        metrics.push(benchmark_signature::<SlhDsa256s>(iterations)?);
+    }
+    Ok(metrics)
+}
+
+#[cfg(feature = "std")]
+/// Formats `metrics` as a Markdown table with one row per algorithm.
+///
+/// Times are medians in milliseconds and sizes are in bytes. KEMs and
+/// signatures share columns: encapsulate/sign, decapsulate/verify and
+/// ciphertext/signature.
+pub fn to_markdown_table(metrics: &[PerformanceMetrics]) -> String {
+    fn median(stats: Option<TimingStats>) -> String {
+        stats.map_or_else(|| "-".to_string(), |s| format!("{:.3}", s.median_ms))
+    }
+
+    let mut table = String::from(
+        "| Algorithm | Keypair (ms) | Encaps/Sign (ms) | Decaps/Verify (ms) \
+         | Public key (B) | Secret key (B) | Ciphertext/Signature (B) |\n\
+         |---|---:|---:|---:|---:|---:|---:|\n",
+    );
+    for m in metrics {
+        table.push_str(&format!(
+            "| {} | {:.3} | {} | {} | {} | {} | {} |\n",
+            m.algorithm,
+            m.keypair.median_ms,
+            median(m.encapsulate.or(m.sign)),
+            median(m.decapsulate.or(m.verify)),
+            m.public_key_size,
+            m.secret_key_size,
+            m.ciphertext_size
+                .or(m.signature_size)
+                .map_or_else(|| "-".to_string(), |size| size.to_string()),
+        ));
+    }
+    table
+}
+
+#[cfg(all(test, feature = "std", feature = "kyber", feature = "dilithium"))]
+mod tests {
+    use super::*;
+    use crate::pqc::dilithium::MlDsa44;
+    use crate::pqc::kyber::MlKem768;
+    use crate::utils::key_sizes::{ml_dsa_44, ml_kem_768};
+
+    #[test]
+    fn test_dyn_kem_by_name() {
+        let kem = kem_by_name("ML-KEM-1024").unwrap();
+        assert_eq!(kem.algorithm_name(), "ML-KEM-1024");
+
+        let (pk, sk) = kem.keypair().unwrap();
+        let (ct, ss) = kem.encapsulate(&pk).unwrap();
+        assert_eq!(*kem.decapsulate(&sk, &ct).unwrap(), *ss);
+        assert!(matches!(
+            kem.decapsulate(&sk, &ct[1..]),
+            Err(QraiopError::InvalidCiphertext(_))
+        ));
+
+        assert!(kem_by_name("ML-KEM-2048").is_none());
+    }
+
+    #[test]
+    fn test_benchmark_kem_reports_key_material_size() {
+        let metrics = benchmark_kem::<MlKem768>(1).unwrap();
+        assert_eq!(metrics.algorithm, "ML-KEM-768");
+        assert_eq!(metrics.secret_key_size, ml_kem_768::SECRET_KEY_SIZE);
+        assert!(metrics.public_key_size >= ml_kem_768::PUBLIC_KEY_SIZE);
+        assert!(metrics.encapsulate.is_some());
+        assert!(metrics.sign.is_none());
+    }
+
+    #[test]
+    fn test_benchmark_kem_reports_size_ratios() {
+        let metrics = benchmark_kem::<MlKem768>(1).unwrap();
+        // 1088-byte ciphertext and 1184-byte public key for a 32-byte secret
+        assert_eq!(metrics.ciphertext_overhead_ratio, Some(34.0));
+        assert_eq!(metrics.public_key_to_secret_ratio, Some(37.0));
+
+        let metrics = benchmark_signature::<MlDsa44>(1).unwrap();
+        assert_eq!(metrics.ciphertext_overhead_ratio, None);
+        assert_eq!(metrics.public_key_to_secret_ratio, None);
+    }
+
+    #[test]
+    fn test_benchmark_signature_reports_key_material_size() {
+        let metrics = benchmark_signature::<MlDsa44>(1).unwrap();
+        assert_eq!(metrics.algorithm, "ML-DSA-44");
+        assert_eq!(metrics.secret_key_size, ml_dsa_44::SECRET_KEY_SIZE);
+        assert!(metrics.signature_size.unwrap() >= ml_dsa_44::SIGNATURE_SIZE);
+        assert!(metrics.verify.is_some());
+    }
+
+    #[test]
+    fn test_benchmark_iterations() {
+        let metrics = benchmark_kem::<MlKem768>(5).unwrap();
+        assert_eq!(metrics.iterations, 5);
+        let keypair = metrics.keypair;
+        assert!(keypair.min_ms <= keypair.median_ms);
+        assert!(keypair.min_ms <= keypair.mean_ms);
+
+        assert!(matches!(
+            benchmark_kem::<MlKem768>(0),
+            Err(crate::QraiopError::CryptoError(_))
+        ));
+        assert!(matches!(
+            benchmark_signature::<MlDsa44>(0),
+            Err(crate::QraiopError::CryptoError(_))
+        ));
+    }
+
+    #[test]
+    fn test_algorithm_oids() {
+        use crate::hybrid::{HybridSignature, X25519Kem, XWing};
+        use crate::pqc::dilithium::{MlDsa65, MlDsa87};
+        use crate::pqc::kyber::{MlKem1024, MlKem512};
+        use crate::pqc::lms::{LmsSha256H10, LmsSha256H5};
+        use pkcs8::ObjectIdentifier;
+
+        let oids = vec![
+            MlKem512::algorithm_oid(),
+            MlKem768::algorithm_oid(),
+            MlKem1024::algorithm_oid(),
+            MlDsa44::algorithm_oid(),
+            MlDsa65::algorithm_oid(),
+            MlDsa87::algorithm_oid(),
+            LmsSha256H5::algorithm_oid(),
+            XWing::algorithm_oid(),
+            X25519Kem::algorithm_oid(),
+        ];
+        for oid in &oids {
+            let oid = oid.expect("standardized algorithms have an OID");
+            let parsed = ObjectIdentifier::new(oid).unwrap();
+            assert_eq!(parsed.to_string(), oid);
+        }
+        let mut distinct = oids.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        assert_eq!(distinct.len(), oids.len());
+
+        assert_eq!(MlDsa65::algorithm_oid(), Some("2.16.840.1.101.3.4.3.18"));
+        assert_eq!(LmsSha256H10::algorithm_oid(), LmsSha256H5::algorithm_oid());
+        assert_eq!(HybridSignature::algorithm_oid(), None);
+    }
+
+    /// An OID claims the standard, so it must match what the backend does.
+    #[test]
+    fn test_algorithm_oids_match_backends() {
+        use crate::pqc::dilithium::{MlDsa65, MlDsa87};
+
+        // The FIPS 204 sizes, and pure signing is signing under the empty
+        // context, which the FIPS 204 draft did not do
+        macro_rules! check_ml_dsa {
+            ($name:ident, $oid:literal, $sizes:expr) => {
+                assert_eq!($name::algorithm_oid(), Some($oid));
+                let sizes = (
+                    $name::public_key_size(),
+                    $name::secret_key_size(),
+                    $name::signature_size(),
+                );
+                assert_eq!(sizes, $sizes);
+                let (pk, sk) = $name::keypair().unwrap();
+                let signature = $name::sign(&sk, b"message").unwrap();
+                assert!($name::verify_with_context(&pk, b"message", b"", &signature).unwrap());
+            };
+        }
+        check_ml_dsa!(MlDsa44, "2.16.840.1.101.3.4.3.17", (1312, 2560, 2420));
+        check_ml_dsa!(MlDsa65, "2.16.840.1.101.3.4.3.18", (1952, 4032, 3309));
+        check_ml_dsa!(MlDsa87, "2.16.840.1.101.3.4.3.19", (2592, 4896, 4627));
+
+        // The backend is SPHINCS+ as submitted, not FIPS 205 SLH-DSA
+        #[cfg(feature = "sphincs")]
+        {
+            use crate::pqc::sphincs::{SlhDsa128s, SlhDsa192s, SlhDsa256s};
+            assert_eq!(SlhDsa128s::algorithm_oid(), None);
+            assert_eq!(SlhDsa192s::algorithm_oid(), None);
+            assert_eq!(SlhDsa256s::algorithm_oid(), None);
+        }
+    }
+
+    #[test]
+    fn test_benchmark_warmup() {
+        let config = BenchmarkConfig {
+            warmup: 3,
+            samples: 2,
+        };
+        let metrics = benchmark_kem_with_config::<MlKem768>(config).unwrap();
+        assert_eq!((metrics.iterations, metrics.warmup), (2, 3));
+        let metrics = benchmark_signature_with_config::<MlDsa44>(config).unwrap();
+        assert_eq!((metrics.iterations, metrics.warmup), (2, 3));
+        assert_eq!(benchmark_kem::<MlKem768>(2).unwrap().warmup, 0);
+
+        // Warmup alone is not enough
+        let config = BenchmarkConfig {
+            warmup: 3,
+            samples: 0,
+        };
+        assert!(matches!(
+            benchmark_kem_with_config::<MlKem768>(config),
+            Err(crate::QraiopError::CryptoError(_))
+        ));
+    }
+
+    fn timing(median_ms: f64) -> TimingStats {
+        TimingStats {
+            mean_ms: median_ms,
+            min_ms: median_ms,
+            median_ms,
+            std_dev_ms: 0.0,
+        }
+    }
+
+    fn kem_metrics() -> PerformanceMetrics {
+        PerformanceMetrics {
+            algorithm: "ML-KEM-768".to_string(),
+            iterations: 10,
+            warmup: 0,
+            keypair: timing(1.0),
+            encapsulate: Some(timing(1.0)),
+            decapsulate: Some(timing(1.0)),
+            sign: None,
+            verify: None,
+            public_key_size: 1184,
+            secret_key_size: 2400,
+            ciphertext_size: Some(1088),
+            signature_size: None,
+            ciphertext_overhead_ratio: Some(34.0),
+            public_key_to_secret_ratio: Some(37.0),
+        }
+    }
+
+    #[test]
+    fn test_metrics_json_roundtrip() {
+        // Timings chosen to be exact in decimal, as JSON floats need not
+        // round-trip bit for bit
+        let metrics = PerformanceMetrics {
+            keypair: timing(0.125),
+            ..kem_metrics()
+        };
+        let decoded = PerformanceMetrics::from_json(&metrics.to_json().unwrap()).unwrap();
+        assert_eq!(decoded.algorithm, metrics.algorithm);
+        assert_eq!(decoded.keypair, metrics.keypair);
+        assert_eq!(decoded.ciphertext_size, metrics.ciphertext_size);
+        assert_eq!(decoded.ciphertext_overhead_ratio, Some(34.0));
+
+        // Reports saved before the ratios, warmup and deviation existed
+        // still load
+        let mut json: serde_json::Value =
+            serde_json::from_str(&metrics.to_json().unwrap()).unwrap();
+        let object = json.as_object_mut().unwrap();
+        object.remove("ciphertext_overhead_ratio");
+        object.remove("warmup");
+        object["keypair"]
+            .as_object_mut()
+            .unwrap()
+            .remove("std_dev_ms");
+        let decoded = PerformanceMetrics::from_json(&json.to_string()).unwrap();
+        assert_eq!(decoded.ciphertext_overhead_ratio, None);
+        assert_eq!(decoded.warmup, 0);
+        assert_eq!(decoded.keypair.std_dev_ms, 0.0);
+
+        assert!(matches!(
+            PerformanceMetrics::from_json("{}"),
+            Err(crate::QraiopError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_regression_against() {
+        let baseline = kem_metrics();
+        let slow = PerformanceMetrics {
+            keypair: timing(1.5),
+            encapsulate: Some(timing(1.05)),
+            decapsulate: Some(timing(2.0)),
+            sign: Some(timing(9.0)),
+            ..baseline.clone()
+        };
+
+        let regressions = slow.regression_against(&baseline, 10.0);
+        assert_eq!(regressions.len(), 2);
+        assert!(regressions[0].starts_with("keypair:"));
+        assert!(regressions[1].starts_with("decapsulate:"));
+        assert!(regressions[1].contains("+100.0%"));
+
+        assert!(baseline.regression_against(&slow, 10.0).is_empty());
+        assert!(slow.regression_against(&baseline, 200.0).is_empty());
+    }
+
+    #[test]
+    fn test_benchmark_all() {
+        let metrics = benchmark_all(1).unwrap();
+        assert_eq!(metrics.len(), crate::ALGORITHMS.len());
+        for (m, name) in metrics.iter().zip(crate::ALGORITHMS) {
+            assert_eq!(m.algorithm, *name);
+            assert!(m.public_key_size > 0 && m.secret_key_size > 0);
+            assert!(m.ciphertext_size.or(m.signature_size).unwrap() > 0);
+        }
+
+        let table = to_markdown_table(&metrics);
+        assert_eq!(table.lines().count(), 2 + metrics.len());
+        assert!(table.lines().nth(3).unwrap().starts_with("| ML-KEM-768 | "));
+    }
+
+    #[test]
+    fn test_markdown_table() {
+        let table = to_markdown_table(&[kem_metrics()]);
+        let row = table.lines().nth(2).unwrap();
+        assert_eq!(
+            row,
+            "| ML-KEM-768 | 1.000 | 1.000 | 1.000 | 1184 | 2400 | 1088 |"
+        );
+        assert!(table.starts_with("| Algorithm | Keypair (ms) |"));
+    }
+
+    #[test]
+    fn test_timing_stats() {
+        let stats = TimingStats::from_samples(vec![4.0, 1.0, 3.0, 2.0]);
+        assert_eq!(stats.min_ms, 1.0);
+        assert_eq!(stats.median_ms, 2.5);
+        assert_eq!(stats.mean_ms, 2.5);
+
+        // Sample variance of 1, 2, 3, 4 is 5/3
+        assert!((stats.std_dev_ms - (5.0f64 / 3.0).sqrt()).abs() < 1e-12);
+
+        let stats = TimingStats::from_samples(vec![5.0, 1.0, 3.0]);
+        assert_eq!(stats.median_ms, 3.0);
+        assert_eq!(stats.std_dev_ms, 2.0);
+        assert_eq!(TimingStats::from_samples(vec![7.0]).std_dev_ms, 0.0);
+    }
+
+    #[test]
+    fn test_serialization_failure_is_an_error() {
+        struct Unserializable;
+
+        impl Serialize for Unserializable {
+            fn serialize<Ser: serde::Serializer>(&self, _: Ser) -> Result<Ser::Ok, Ser::Error> {
+                Err(serde::ser::Error::custom("not serializable"))
+            }
+        }
+
+        assert!(matches!(
+            serialized_size(&Unserializable),
+            Err(crate::QraiopError::SerializationError(_))
+        ));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_generate_keypairs_parallel() {
+        use crate::pqc::kyber::MlKem512;
+        use std::collections::HashSet;
+
+        let keypairs = generate_keypairs_parallel::<MlKem512>(100).unwrap();
+        assert_eq!(keypairs.len(), 100);
+        let distinct: HashSet<&[u8]> = keypairs.iter().map(|(pk, _)| pk.as_bytes()).collect();
+        assert_eq!(distinct.len(), 100);
+
+        assert!(generate_keypairs_parallel::<MlKem512>(0)
+            .unwrap()
+            .is_empty());
+    }
 }
@@ -0,0 +1,325 @@
+//! SLH-DSA (SPHINCS+) implementation
+//!
+//! Based on the SHAKE "small" SPHINCS+ parameter sets that FIPS 205
+//! standardized. The backend, pqcrypto-sphincsplus 0.7, implements SPHINCS+
+//! as submitted rather than FIPS 205, and its signatures do not verify under
+//! FIPS 205 implementations, so these types claim no object identifier.
+
+use crate::pqc::{DigitalSignature, HashBasedSignature};
+use crate::utils;
+use crate::utils::key_sizes::{slh_dsa_128s, slh_dsa_192s, slh_dsa_256s};
+use crate::utils::secret::Secret;
+use crate::{QraiopError, Result, SecurityLevel};
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use pqcrypto_sphincsplus::{
+    sphincsshake128ssimple, sphincsshake192ssimple, sphincsshake256ssimple,
+};
+use pqcrypto_traits::sign::{DetachedSignature as _, PublicKey as _, SecretKey as _};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// SLH-DSA verification (public) key.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PublicKey(Vec<u8>);
+
+impl PublicKey {
+    /// Parameter set, inferred from the key length.
+    fn algorithm_name(&self) -> &'static str {
+        match self.0.len() {
+            slh_dsa_128s::PUBLIC_KEY_SIZE => "SLH-DSA-128s",
+            slh_dsa_192s::PUBLIC_KEY_SIZE => "SLH-DSA-192s",
+            slh_dsa_256s::PUBLIC_KEY_SIZE => "SLH-DSA-256s",
+            _ => "SLH-DSA",
+        }
+    }
+}
+
+/// Algorithm and short fingerprint, e.g. `SLH-DSA-192s pk:3f:a0:1c:77:09:be:42:d5`.
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} pk:{}",
+            self.algorithm_name(),
+            utils::fingerprint(&self.0)
+        )
+    }
+}
+
+// Same as `Display`, so all public keys log alike.
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PublicKey({})", self)
+    }
+}
+
+/// SLH-DSA signing (secret) key, wiped from memory on drop.
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SecretKey(Secret);
+
+impl SecretKey {
+    /// Copies the key out into a buffer that is wiped when dropped.
+    pub fn to_zeroizing_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(self.0.expose_secret().to_vec())
+    }
+}
+
+impl AsRef<[u8]> for SecretKey {
+    fn as_ref(&self) -> &[u8] {
+        self.0.expose_secret()
+    }
+}
+
+/// Detached SLH-DSA signature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Signature(Vec<u8>);
+
+// Seeded key generation exported by the PQClean sources bundled with
+// `pqcrypto-sphincsplus`. The backend's `keypair` is this fed from
+// `randombytes`, which panics if the OS RNG fails.
+extern "C" {
+    fn PQCLEAN_SPHINCSSHAKE128SSIMPLE_CLEAN_crypto_sign_seed_keypair(
+        pk: *mut u8,
+        sk: *mut u8,
+        seed: *const u8,
+    ) -> i32;
+    fn PQCLEAN_SPHINCSSHAKE192SSIMPLE_CLEAN_crypto_sign_seed_keypair(
+        pk: *mut u8,
+        sk: *mut u8,
+        seed: *const u8,
+    ) -> i32;
+    fn PQCLEAN_SPHINCSSHAKE256SSIMPLE_CLEAN_crypto_sign_seed_keypair(
+        pk: *mut u8,
+        sk: *mut u8,
+        seed: *const u8,
+    ) -> i32;
+}
+
+macro_rules! slh_dsa {
+    (
+        $(#[$meta:meta])* $name:ident,
+        $backend:ident,
+        $seed_keypair:ident,
+        $sizes:ident,
+        $algorithm:literal,
+        $level:expr
+    ) => {
+        $(#[$meta])*
+        pub struct $name;
+
+        impl $name {
+            /// Generates a key pair from `SK.seed || SK.prf || PK.seed`, `n`
+            /// bytes each, drawn from `rng`, so a seeded RNG reproduces the
+            /// same sequence of keys. [`DigitalSignature::keypair`] is this
+            /// with `OsRng`. If `rng` fails, this returns
+            /// [`QraiopError::KeyGenerationFailed`] instead of panicking.
+            pub fn keypair_with_rng<R: RngCore + CryptoRng>(
+                rng: &mut R,
+            ) -> Result<(PublicKey, SecretKey)> {
+                // The public key is `PK.root || PK.seed`, so `n` is half its size
+                let mut seed = Zeroizing::new([0u8; 3 * ($sizes::PUBLIC_KEY_SIZE / 2)]);
+                utils::keygen_seed(rng, seed.as_mut()).map_err(|e| e.with_algorithm($algorithm))?;
+                let mut pk = vec![0u8; $sizes::PUBLIC_KEY_SIZE];
+                let mut sk = vec![0u8; $sizes::SECRET_KEY_SIZE];
+                // SAFETY: the buffers are sized per the parameter set and the
+                // seed is exactly the 3 * n bytes the C routine reads; it
+                // always returns 0.
+                unsafe { $seed_keypair(pk.as_mut_ptr(), sk.as_mut_ptr(), seed.as_ptr()) };
+                Ok((PublicKey(pk), SecretKey(Secret::new(sk))))
+            }
+        }
+
+        impl DigitalSignature for $name {
+            type PublicKey = PublicKey;
+            type SecretKey = SecretKey;
+            type Signature = Signature;
+
+            fn keypair() -> Result<(Self::PublicKey, Self::SecretKey)> {
+                Self::keypair_with_rng(&mut utils::SystemRng)
+            }
+
+            fn sign(secret_key: &Self::SecretKey, message: &[u8]) -> Result<Self::Signature> {
+                let sk = $backend::SecretKey::from_bytes(secret_key.0.expose_secret())
+                    .map_err(|e| QraiopError::InvalidKey(e.to_string()).with_algorithm($algorithm))?;
+                let signature = $backend::detached_sign(message, &sk);
+                Ok(Signature(signature.as_bytes().to_vec()))
+            }
+
+            fn verify(
+                public_key: &Self::PublicKey,
+                message: &[u8],
+                signature: &Self::Signature,
+            ) -> Result<bool> {
+                let pk = $backend::PublicKey::from_bytes(&public_key.0)
+                    .map_err(|e| QraiopError::InvalidKey(e.to_string()).with_algorithm($algorithm))?;
+                // A signature that cannot even be decoded is simply not valid
+                let Ok(signature) = $backend::DetachedSignature::from_bytes(&signature.0) else {
+                    return Ok(false);
+                };
+                Ok($backend::verify_detached_signature(&signature, message, &pk).is_ok())
+            }
+
+            fn algorithm_name() -> &'static str {
+                $algorithm
+            }
+
+            fn algorithm_oid() -> Option<&'static str> {
+                None
+            }
+
+            fn security_level() -> SecurityLevel {
+                $level
+            }
+
+            fn public_key_size() -> usize {
+                $sizes::PUBLIC_KEY_SIZE
+            }
+
+            fn secret_key_size() -> usize {
+                $sizes::SECRET_KEY_SIZE
+            }
+
+            fn signature_size() -> usize {
+                $sizes::SIGNATURE_SIZE
+            }
+        }
+
+        /// SLH-DSA is stateless: every signature picks its few-time leaf
+        /// pseudorandomly instead of consuming a counter, and the parameter
+        /// sets are sized so a key stays secure for up to 2^64 signatures.
+        /// There is no state to exhaust, so both bounds report `u64::MAX`.
+        impl HashBasedSignature for $name {
+            fn max_signatures() -> u64 {
+                u64::MAX
+            }
+
+            fn signatures_remaining(_secret_key: &Self::SecretKey) -> u64 {
+                u64::MAX
+            }
+        }
+    };
+}
+
+slh_dsa!(
+    /// SLH-DSA-SHAKE-128s (NIST security category 1).
+    SlhDsa128s,
+    sphincsshake128ssimple,
+    PQCLEAN_SPHINCSSHAKE128SSIMPLE_CLEAN_crypto_sign_seed_keypair,
+    slh_dsa_128s,
+    "SLH-DSA-128s",
+    SecurityLevel::Level1
+);
+slh_dsa!(
+    /// SLH-DSA-SHAKE-192s (NIST security category 3).
+    SlhDsa192s,
+    sphincsshake192ssimple,
+    PQCLEAN_SPHINCSSHAKE192SSIMPLE_CLEAN_crypto_sign_seed_keypair,
+    slh_dsa_192s,
+    "SLH-DSA-192s",
+    SecurityLevel::Level3
+);
+slh_dsa!(
+    /// SLH-DSA-SHAKE-256s (NIST security category 5).
+    SlhDsa256s,
+    sphincsshake256ssimple,
+    PQCLEAN_SPHINCSSHAKE256SSIMPLE_CLEAN_crypto_sign_seed_keypair,
+    slh_dsa_256s,
+    "SLH-DSA-256s",
+    SecurityLevel::Level5
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip<S>(public_key_size: usize, secret_key_size: usize, signature_size: usize)
+    where
+        S: DigitalSignature<PublicKey = PublicKey, SecretKey = SecretKey, Signature = Signature>,
+    {
+        let message = b"quantum-resistant message";
+        let (pk, sk) = S::keypair().unwrap();
+        let signature = S::sign(&sk, message).unwrap();
+
+        assert_eq!(pk.0.len(), public_key_size);
+        assert_eq!(sk.0.len(), secret_key_size);
+        assert_eq!(signature.0.len(), signature_size);
+        assert!(S::verify(&pk, message, &signature).unwrap());
+        assert!(!S::verify(&pk, b"tampered message", &signature).unwrap());
+    }
+
+    #[test]
+    fn test_slh_dsa_128s_roundtrip() {
+        roundtrip::<SlhDsa128s>(
+            slh_dsa_128s::PUBLIC_KEY_SIZE,
+            slh_dsa_128s::SECRET_KEY_SIZE,
+            slh_dsa_128s::SIGNATURE_SIZE,
+        );
+    }
+
+    #[test]
+    fn test_slh_dsa_192s_roundtrip() {
+        roundtrip::<SlhDsa192s>(
+            slh_dsa_192s::PUBLIC_KEY_SIZE,
+            slh_dsa_192s::SECRET_KEY_SIZE,
+            slh_dsa_192s::SIGNATURE_SIZE,
+        );
+    }
+
+    #[test]
+    fn test_slh_dsa_256s_roundtrip() {
+        roundtrip::<SlhDsa256s>(
+            slh_dsa_256s::PUBLIC_KEY_SIZE,
+            slh_dsa_256s::SECRET_KEY_SIZE,
+            slh_dsa_256s::SIGNATURE_SIZE,
+        );
+    }
+
+    #[test]
+    fn test_keypair_with_rng() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha20Rng;
+
+        let (pk1, sk1) = SlhDsa128s::keypair_with_rng(&mut ChaCha20Rng::seed_from_u64(9)).unwrap();
+        let (pk2, sk2) = SlhDsa128s::keypair_with_rng(&mut ChaCha20Rng::seed_from_u64(9)).unwrap();
+        assert_eq!(pk1.0, pk2.0);
+        assert_eq!(sk1.as_ref(), sk2.as_ref());
+        // The secret key ends with the public key, `PK.seed || PK.root`
+        assert_eq!(&sk1.as_ref()[slh_dsa_128s::PUBLIC_KEY_SIZE..], &pk1.0[..]);
+
+        assert!(matches!(
+            SlhDsa128s::keypair_with_rng(&mut utils::FailingRng),
+            Err(QraiopError::KeyGenerationFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_metadata() {
+        assert_eq!(SlhDsa128s::algorithm_name(), "SLH-DSA-128s");
+        assert_eq!(SlhDsa192s::algorithm_name(), "SLH-DSA-192s");
+        assert_eq!(SlhDsa256s::algorithm_name(), "SLH-DSA-256s");
+        assert_eq!(SlhDsa128s::security_level(), SecurityLevel::Level1);
+        assert_eq!(SlhDsa192s::security_level(), SecurityLevel::Level3);
+        assert_eq!(SlhDsa256s::security_level(), SecurityLevel::Level5);
+
+        let (_, sk) = SlhDsa128s::keypair().unwrap();
+        assert_eq!(SlhDsa128s::max_signatures(), u64::MAX);
+        assert_eq!(SlhDsa128s::signatures_remaining(&sk), u64::MAX);
+    }
+
+    #[test]
+    fn test_trait_sizes_match_key_sizes() {
+        assert_eq!(SlhDsa128s::public_key_size(), slh_dsa_128s::PUBLIC_KEY_SIZE);
+        assert_eq!(SlhDsa128s::secret_key_size(), slh_dsa_128s::SECRET_KEY_SIZE);
+        assert_eq!(SlhDsa128s::signature_size(), slh_dsa_128s::SIGNATURE_SIZE);
+        assert_eq!(SlhDsa192s::public_key_size(), slh_dsa_192s::PUBLIC_KEY_SIZE);
+        assert_eq!(SlhDsa192s::secret_key_size(), slh_dsa_192s::SECRET_KEY_SIZE);
+        assert_eq!(SlhDsa192s::signature_size(), slh_dsa_192s::SIGNATURE_SIZE);
+        assert_eq!(SlhDsa256s::public_key_size(), slh_dsa_256s::PUBLIC_KEY_SIZE);
+        assert_eq!(SlhDsa256s::secret_key_size(), slh_dsa_256s::SECRET_KEY_SIZE);
+        assert_eq!(SlhDsa256s::signature_size(), slh_dsa_256s::SIGNATURE_SIZE);
+    }
+}
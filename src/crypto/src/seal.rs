@@ -0,0 +1,648 @@
+//! Public-key authenticated encryption in the style of HPKE base mode
+//!
+//! A fresh ML-KEM-768 encapsulation to the recipient yields a shared secret,
+//! HKDF-SHA256 expands it into an AES-256-GCM key and nonce, and the plaintext
+//! is sealed under that key. The wire format is the KEM ciphertext followed
+//! by the AEAD ciphertext and tag:
+//!
+//! ```text
+//! kem_ciphertext (1088 bytes) || aes_gcm_ciphertext || tag (16 bytes)
+//! ```
+//!
+//! Every message uses a new encapsulation, so each key/nonce pair is used
+//! exactly once.
+//!
+//! [`SealConfig`] seals with another ML-KEM parameter set, AEAD or KDF. Its
+//! messages start with a header naming the choices, so
+//! [`SealConfig::open`] needs only the secret key:
+//!
+//! ```text
+//! version (1) || kem (1) || aead (1) || kdf (1)
+//!   || kem_ciphertext || aead_ciphertext || tag (16 bytes)
+//! ```
+//!
+//! with `kem` 1, 2 or 3 for ML-KEM-512, -768 or -1024, `aead` 1 for
+//! AES-256-GCM or 2 for ChaCha20-Poly1305, and `kdf` 1 for HKDF-SHA256 or 2
+//! for HKDF-SHA512. The header is appended to the HKDF `info`, so a message
+//! whose header was altered derives the wrong key and fails to open. The
+//! two formats are not interchangeable: [`open`] cannot read configured
+//! messages, nor [`SealConfig::open`] those from [`seal`].
+//!
+//! [`seal_multi`] encrypts a payload once for many recipients: it is sealed
+//! under a random content key with AES-256-GCM, and the content key is
+//! wrapped for each recipient by a [`seal`]-style ML-KEM-768 encapsulation.
+//! Each wrapped key is labelled with the SHA3-256 fingerprint of its
+//! recipient's public key, so anyone holding the message can tell who it
+//! was sent to, and any recipient could replace the payload for the others:
+//! sign the message when that matters.
+
+use crate::pqc::kyber::{self, KemAlgorithm, MlKem768};
+use crate::pqc::KeyEncapsulation;
+use crate::utils::key_sizes::{ml_kem_1024, ml_kem_512, ml_kem_768};
+use crate::{QraiopError, Result};
+use aes_gcm::aead::{self, Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use chacha20poly1305::ChaCha20Poly1305;
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use sha2::{Sha256, Sha512};
+use zeroize::Zeroizing;
+
+/// HKDF `info` binding derived keys to this construction and version.
+const INFO: &[u8] = b"QRAIOP-seal-v1 ML-KEM-768 HKDF-SHA256 AES-256-GCM";
+/// HKDF `info` for the keys wrapping a multi-recipient content key.
+const WRAP_INFO: &[u8] = b"QRAIOP-seal-multi-v1 ML-KEM-768 HKDF-SHA256 AES-256-GCM";
+/// AAD of a multi-recipient payload. Its content key is never reused, so
+/// the payload nonce is fixed at zero.
+const PAYLOAD_AAD: &[u8] = b"QRAIOP-seal-multi-v1 payload";
+
+const KEY_SIZE: usize = 32;
+const NONCE_SIZE: usize = 12;
+const TAG_SIZE: usize = 16;
+
+const CONFIG_VERSION: u8 = 1;
+/// HKDF `info` prefix for [`SealConfig`]; the message header follows it.
+const CONFIG_INFO: &[u8] = b"QRAIOP-seal-config-v1";
+const HEADER_SIZE: usize = 4;
+
+/// Bytes `seal` adds on top of the plaintext length.
+pub const OVERHEAD: usize = ml_kem_768::CIPHERTEXT_SIZE + TAG_SIZE;
+
+fn cipher(
+    shared_secret: &kyber::SharedSecret,
+    info: &[u8],
+) -> Result<(Aes256Gcm, [u8; NONCE_SIZE])> {
+    let hkdf = Hkdf::<Sha256>::new(None, shared_secret.as_bytes());
+    let mut okm = Zeroizing::new([0u8; KEY_SIZE + NONCE_SIZE]);
+    hkdf.expand(info, okm.as_mut())
+        .map_err(|e| QraiopError::CryptoError(format!("Key derivation failed: {}", e)))?;
+
+    let cipher = Aes256Gcm::new_from_slice(&okm[..KEY_SIZE])
+        .map_err(|e| QraiopError::CryptoError(format!("Invalid AEAD key: {}", e)))?;
+    let mut nonce = [0u8; NONCE_SIZE];
+    nonce.copy_from_slice(&okm[KEY_SIZE..]);
+    Ok((cipher, nonce))
+}
+
+/// Encrypts `plaintext` to the holder of the ML-KEM-768 secret key matching
+/// `recipient_pk`. `aad` is authenticated but not encrypted, and must be
+/// passed unchanged to [`open`].
+pub fn seal(recipient_pk: &kyber::PublicKey, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let (kem_ciphertext, shared_secret) = MlKem768::encapsulate(recipient_pk)?;
+    let (cipher, nonce) = cipher(&shared_secret, INFO)?;
+    let sealed = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| QraiopError::CryptoError("Encryption failed".to_string()))?;
+
+    let mut out = Vec::with_capacity(kem_ciphertext.as_bytes().len() + sealed.len());
+    out.extend_from_slice(kem_ciphertext.as_bytes());
+    out.extend_from_slice(&sealed);
+    Ok(out)
+}
+
+/// Decrypts a message produced by [`seal`]. Any modification of the
+/// ciphertext or `aad`, or the wrong secret key, yields
+/// `QraiopError::CryptoError`.
+pub fn open(recipient_sk: &kyber::SecretKey, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+    if ciphertext.len() < OVERHEAD {
+        return Err(QraiopError::CryptoError(format!(
+            "Sealed message must be at least {} bytes, got {}",
+            OVERHEAD,
+            ciphertext.len()
+        )));
+    }
+    let (kem_ciphertext, sealed) = ciphertext.split_at(ml_kem_768::CIPHERTEXT_SIZE);
+    let kem_ciphertext = kyber::Ciphertext::from_bytes(kem_ciphertext)?;
+    let shared_secret = MlKem768::decapsulate(recipient_sk, &kem_ciphertext)?;
+    let (cipher, nonce) = cipher(&shared_secret, INFO)?;
+    cipher
+        .decrypt(Nonce::from_slice(&nonce), Payload { msg: sealed, aad })
+        .map_err(|_| QraiopError::CryptoError("Decryption failed".to_string()))
+}
+
+/// Content key of a [`MultiRecipientMessage`], wrapped for one recipient.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct WrappedKey {
+    /// SHA3-256 fingerprint of the recipient's public key.
+    key_id: [u8; 32],
+    kem_ciphertext: Vec<u8>,
+    /// The content key, sealed under a key derived from the encapsulated
+    /// secret with `key_id` as AAD.
+    wrapped_key: Vec<u8>,
+}
+
+/// Message sealed once to several recipients by [`seal_multi`]. Send it in
+/// any serde format; the payload is the same size whatever the number of
+/// recipients, and each recipient adds a little over one [`OVERHEAD`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultiRecipientMessage {
+    recipients: Vec<WrappedKey>,
+    ciphertext: Vec<u8>,
+}
+
+impl MultiRecipientMessage {
+    /// Number of recipients the content key was wrapped for.
+    pub fn recipient_count(&self) -> usize {
+        self.recipients.len()
+    }
+}
+
+fn content_cipher(content_key: &[u8]) -> Result<Aes256Gcm> {
+    Aes256Gcm::new_from_slice(content_key)
+        .map_err(|e| QraiopError::CryptoError(format!("Invalid AEAD key: {}", e)))
+}
+
+/// Encrypts `plaintext` once to every holder of an ML-KEM-768 secret key
+/// matching one of `recipients`; see the module docs for the construction.
+/// Fails if `recipients` is empty.
+pub fn seal_multi(
+    recipients: &[kyber::PublicKey],
+    plaintext: &[u8],
+) -> Result<MultiRecipientMessage> {
+    if recipients.is_empty() {
+        return Err(QraiopError::CryptoError(
+            "A multi-recipient message needs at least one recipient".to_string(),
+        ));
+    }
+    let mut content_key = Zeroizing::new([0u8; KEY_SIZE]);
+    crate::utils::secure_random_into(content_key.as_mut())?;
+
+    let recipients = recipients
+        .iter()
+        .map(|recipient_pk| {
+            let (kem_ciphertext, shared_secret) = MlKem768::encapsulate(recipient_pk)?;
+            let (cipher, nonce) = cipher(&shared_secret, WRAP_INFO)?;
+            let key_id = recipient_pk.fingerprint_full();
+            let wrapped_key = cipher
+                .encrypt(
+                    Nonce::from_slice(&nonce),
+                    Payload {
+                        msg: content_key.as_ref(),
+                        aad: &key_id,
+                    },
+                )
+                .map_err(|_| QraiopError::CryptoError("Encryption failed".to_string()))?;
+            Ok(WrappedKey {
+                key_id,
+                kem_ciphertext: kem_ciphertext.as_bytes().to_vec(),
+                wrapped_key,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let ciphertext = content_cipher(content_key.as_ref())?
+        .encrypt(
+            Nonce::from_slice(&[0; NONCE_SIZE]),
+            Payload {
+                msg: plaintext,
+                aad: PAYLOAD_AAD,
+            },
+        )
+        .map_err(|_| QraiopError::CryptoError("Encryption failed".to_string()))?;
+    Ok(MultiRecipientMessage {
+        recipients,
+        ciphertext,
+    })
+}
+
+/// Decrypts a message from [`seal_multi`] with the secret key of one of its
+/// recipients. A key the message was not sealed to, or any modification of
+/// the parts meant for this recipient, yields `QraiopError::CryptoError`.
+pub fn open_multi(
+    recipient_sk: &kyber::SecretKey,
+    message: &MultiRecipientMessage,
+) -> Result<Vec<u8>> {
+    let key_id = recipient_sk.public_key()?.fingerprint_full();
+    let entry = message
+        .recipients
+        .iter()
+        .find(|entry| entry.key_id == key_id)
+        .ok_or_else(|| {
+            QraiopError::CryptoError("The message was not sealed to this key".to_string())
+        })?;
+    let kem_ciphertext = kyber::Ciphertext::from_bytes(&entry.kem_ciphertext)?;
+    let shared_secret = MlKem768::decapsulate(recipient_sk, &kem_ciphertext)?;
+    let (cipher, nonce) = cipher(&shared_secret, WRAP_INFO)?;
+    let content_key = cipher
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &entry.wrapped_key,
+                aad: &key_id,
+            },
+        )
+        .map(Zeroizing::new)
+        .map_err(|_| QraiopError::CryptoError("Decryption failed".to_string()))?;
+
+    content_cipher(&content_key)?
+        .decrypt(
+            Nonce::from_slice(&[0; NONCE_SIZE]),
+            Payload {
+                msg: &message.ciphertext,
+                aad: PAYLOAD_AAD,
+            },
+        )
+        .map_err(|_| QraiopError::CryptoError("Decryption failed".to_string()))
+}
+
+/// AEAD a [`SealConfig`] encrypts the plaintext with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AeadAlgorithm {
+    Aes256Gcm,
+    ChaCha20Poly1305,
+}
+
+/// KDF a [`SealConfig`] derives the AEAD key and nonce with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KdfAlgorithm {
+    HkdfSha256,
+    HkdfSha512,
+}
+
+fn kem_ciphertext_size(kem: KemAlgorithm) -> usize {
+    match kem {
+        KemAlgorithm::MlKem512 => ml_kem_512::CIPHERTEXT_SIZE,
+        KemAlgorithm::MlKem768 => ml_kem_768::CIPHERTEXT_SIZE,
+        KemAlgorithm::MlKem1024 => ml_kem_1024::CIPHERTEXT_SIZE,
+    }
+}
+
+/// Encrypts or decrypts `payload` with `C`, keyed by the first
+/// `KEY_SIZE` bytes of `okm` and using the rest as the nonce.
+fn crypt<C: Aead + KeyInit>(
+    okm: &[u8],
+    payload: Payload<'_, '_>,
+    encrypt: bool,
+) -> Result<Vec<u8>> {
+    let cipher = C::new_from_slice(&okm[..KEY_SIZE])
+        .map_err(|e| QraiopError::CryptoError(format!("Invalid AEAD key: {}", e)))?;
+    let nonce = aead::Nonce::<C>::from_slice(&okm[KEY_SIZE..]);
+    if encrypt {
+        cipher
+            .encrypt(nonce, payload)
+            .map_err(|_| QraiopError::CryptoError("Encryption failed".to_string()))
+    } else {
+        cipher
+            .decrypt(nonce, payload)
+            .map_err(|_| QraiopError::CryptoError("Decryption failed".to_string()))
+    }
+}
+
+/// Choice of KEM, AEAD and KDF for sealing, built up from [`SealConfig::new`]
+/// with [`kem`](SealConfig::kem), [`aead`](SealConfig::aead) and
+/// [`kdf`](SealConfig::kdf).
+///
+/// Every combination is supported, so building a config cannot fail; only
+/// a recipient key of another ML-KEM parameter set is rejected, by
+/// [`SealConfig::seal`]. The default matches [`seal`]: ML-KEM-768,
+/// AES-256-GCM and HKDF-SHA256.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SealConfig {
+    kem: KemAlgorithm,
+    aead: AeadAlgorithm,
+    kdf: KdfAlgorithm,
+}
+
+impl Default for SealConfig {
+    fn default() -> Self {
+        SealConfig {
+            kem: KemAlgorithm::MlKem768,
+            aead: AeadAlgorithm::Aes256Gcm,
+            kdf: KdfAlgorithm::HkdfSha256,
+        }
+    }
+}
+
+impl SealConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn kem(self, kem: KemAlgorithm) -> Self {
+        SealConfig { kem, ..self }
+    }
+
+    pub fn aead(self, aead: AeadAlgorithm) -> Self {
+        SealConfig { aead, ..self }
+    }
+
+    pub fn kdf(self, kdf: KdfAlgorithm) -> Self {
+        SealConfig { kdf, ..self }
+    }
+
+    /// Bytes [`SealConfig::seal`] adds on top of the plaintext length.
+    pub fn overhead(&self) -> usize {
+        HEADER_SIZE + kem_ciphertext_size(self.kem) + TAG_SIZE
+    }
+
+    /// Reads the config a message was sealed with from its header.
+    pub fn from_header(ciphertext: &[u8]) -> Result<Self> {
+        let header = ciphertext.get(..HEADER_SIZE).ok_or_else(|| {
+            QraiopError::CryptoError(format!(
+                "Sealed message header must be {} bytes, got {}",
+                HEADER_SIZE,
+                ciphertext.len()
+            ))
+        })?;
+        if header[0] != CONFIG_VERSION {
+            return Err(QraiopError::SerializationError(format!(
+                "Unsupported sealed message version {}",
+                header[0]
+            )));
+        }
+        let kem = match header[1] {
+            1 => KemAlgorithm::MlKem512,
+            2 => KemAlgorithm::MlKem768,
+            3 => KemAlgorithm::MlKem1024,
+            id => return Err(QraiopError::UnsupportedAlgorithm(format!("KEM id {}", id))),
+        };
+        let aead = match header[2] {
+            1 => AeadAlgorithm::Aes256Gcm,
+            2 => AeadAlgorithm::ChaCha20Poly1305,
+            id => return Err(QraiopError::UnsupportedAlgorithm(format!("AEAD id {}", id))),
+        };
+        let kdf = match header[3] {
+            1 => KdfAlgorithm::HkdfSha256,
+            2 => KdfAlgorithm::HkdfSha512,
+            id => return Err(QraiopError::UnsupportedAlgorithm(format!("KDF id {}", id))),
+        };
+        Ok(SealConfig { kem, aead, kdf })
+    }
+
+    fn header(&self) -> [u8; HEADER_SIZE] {
+        let kem = match self.kem {
+            KemAlgorithm::MlKem512 => 1,
+            KemAlgorithm::MlKem768 => 2,
+            KemAlgorithm::MlKem1024 => 3,
+        };
+        let aead = match self.aead {
+            AeadAlgorithm::Aes256Gcm => 1,
+            AeadAlgorithm::ChaCha20Poly1305 => 2,
+        };
+        let kdf = match self.kdf {
+            KdfAlgorithm::HkdfSha256 => 1,
+            KdfAlgorithm::HkdfSha512 => 2,
+        };
+        [CONFIG_VERSION, kem, aead, kdf]
+    }
+
+    fn key_schedule(
+        &self,
+        shared_secret: &kyber::SharedSecret,
+    ) -> Result<Zeroizing<[u8; KEY_SIZE + NONCE_SIZE]>> {
+        let info: [&[u8]; 2] = [CONFIG_INFO, &self.header()];
+        let mut okm = Zeroizing::new([0u8; KEY_SIZE + NONCE_SIZE]);
+        match self.kdf {
+            KdfAlgorithm::HkdfSha256 => Hkdf::<Sha256>::new(None, shared_secret.as_bytes())
+                .expand_multi_info(&info, okm.as_mut()),
+            KdfAlgorithm::HkdfSha512 => Hkdf::<Sha512>::new(None, shared_secret.as_bytes())
+                .expand_multi_info(&info, okm.as_mut()),
+        }
+        .map_err(|e| QraiopError::CryptoError(format!("Key derivation failed: {}", e)))?;
+        Ok(okm)
+    }
+
+    fn crypt(
+        &self,
+        shared_secret: &kyber::SharedSecret,
+        payload: Payload<'_, '_>,
+        encrypt: bool,
+    ) -> Result<Vec<u8>> {
+        let okm = self.key_schedule(shared_secret)?;
+        match self.aead {
+            AeadAlgorithm::Aes256Gcm => crypt::<Aes256Gcm>(okm.as_ref(), payload, encrypt),
+            AeadAlgorithm::ChaCha20Poly1305 => {
+                crypt::<ChaCha20Poly1305>(okm.as_ref(), payload, encrypt)
+            }
+        }
+    }
+
+    /// Like [`seal`], with the primitives of this config. `recipient_pk`
+    /// must belong to the configured ML-KEM parameter set.
+    pub fn seal(
+        &self,
+        recipient_pk: &kyber::PublicKey,
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>> {
+        let (kem_ciphertext, shared_secret) = self.kem.encapsulate(recipient_pk)?;
+        let sealed = self.crypt(
+            &shared_secret,
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+            true,
+        )?;
+
+        let mut out = Vec::with_capacity(self.overhead() + plaintext.len());
+        out.extend_from_slice(&self.header());
+        out.extend_from_slice(kem_ciphertext.as_bytes());
+        out.extend_from_slice(&sealed);
+        Ok(out)
+    }
+
+    /// Decrypts a message produced by [`SealConfig::seal`] under any
+    /// config, read from the message header. Any modification of the
+    /// message or `aad`, or the wrong secret key, yields
+    /// `QraiopError::CryptoError`; an unknown header yields
+    /// `SerializationError` or `UnsupportedAlgorithm`.
+    pub fn open(recipient_sk: &kyber::SecretKey, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let config = Self::from_header(ciphertext)?;
+        if ciphertext.len() < config.overhead() {
+            return Err(QraiopError::CryptoError(format!(
+                "Sealed message must be at least {} bytes, got {}",
+                config.overhead(),
+                ciphertext.len()
+            )));
+        }
+        let (kem_ciphertext, sealed) =
+            ciphertext[HEADER_SIZE..].split_at(kem_ciphertext_size(config.kem));
+        let kem_ciphertext = kyber::Ciphertext::from_bytes(kem_ciphertext)?;
+        let shared_secret = config.kem.decapsulate(recipient_sk, &kem_ciphertext)?;
+        config.crypt(&shared_secret, Payload { msg: sealed, aad }, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_open_roundtrip() {
+        let (pk, sk) = MlKem768::keypair().unwrap();
+        let sealed = seal(&pk, b"header", b"attack at dawn").unwrap();
+        assert_eq!(sealed.len(), OVERHEAD + b"attack at dawn".len());
+        assert_eq!(open(&sk, b"header", &sealed).unwrap(), b"attack at dawn");
+
+        let empty = seal(&pk, b"", b"").unwrap();
+        assert!(open(&sk, b"", &empty).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let (pk, sk) = MlKem768::keypair().unwrap();
+        let sealed = seal(&pk, b"header", b"attack at dawn").unwrap();
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 0x01;
+        assert!(matches!(
+            open(&sk, b"header", &tampered),
+            Err(QraiopError::CryptoError(_))
+        ));
+
+        let mut tampered = sealed.clone();
+        tampered[0] ^= 0x01;
+        assert!(matches!(
+            open(&sk, b"header", &tampered),
+            Err(QraiopError::CryptoError(_))
+        ));
+
+        assert!(matches!(
+            open(&sk, b"other header", &sealed),
+            Err(QraiopError::CryptoError(_))
+        ));
+        assert!(matches!(
+            open(&sk, b"header", &sealed[..OVERHEAD - 1]),
+            Err(QraiopError::CryptoError(_))
+        ));
+
+        let (_, other_sk) = MlKem768::keypair().unwrap();
+        assert!(matches!(
+            open(&other_sk, b"header", &sealed),
+            Err(QraiopError::CryptoError(_))
+        ));
+    }
+
+    fn all_configs() -> impl Iterator<Item = SealConfig> {
+        let kems = [
+            KemAlgorithm::MlKem512,
+            KemAlgorithm::MlKem768,
+            KemAlgorithm::MlKem1024,
+        ];
+        let aeads = [AeadAlgorithm::Aes256Gcm, AeadAlgorithm::ChaCha20Poly1305];
+        let kdfs = [KdfAlgorithm::HkdfSha256, KdfAlgorithm::HkdfSha512];
+        kems.into_iter().flat_map(move |kem| {
+            aeads.into_iter().flat_map(move |aead| {
+                kdfs.into_iter()
+                    .map(move |kdf| SealConfig::new().kem(kem).aead(aead).kdf(kdf))
+            })
+        })
+    }
+
+    #[test]
+    fn test_seal_config_roundtrip() {
+        for config in all_configs() {
+            let (pk, sk) = config.kem.keypair().unwrap();
+            let sealed = config.seal(&pk, b"header", b"attack at dawn").unwrap();
+            assert_eq!(sealed.len(), config.overhead() + b"attack at dawn".len());
+            assert_eq!(SealConfig::from_header(&sealed).unwrap(), config);
+            assert_eq!(
+                SealConfig::open(&sk, b"header", &sealed).unwrap(),
+                b"attack at dawn"
+            );
+            assert!(matches!(
+                SealConfig::open(&sk, b"other header", &sealed),
+                Err(QraiopError::CryptoError(_))
+            ));
+        }
+        assert_eq!(SealConfig::default().overhead(), HEADER_SIZE + OVERHEAD);
+    }
+
+    #[test]
+    fn test_seal_config_header_is_bound() {
+        let config = SealConfig::new();
+        let (pk, sk) = MlKem768::keypair().unwrap();
+        let sealed = config.seal(&pk, b"", b"attack at dawn").unwrap();
+
+        // Same KEM, different AEAD or KDF: the header still parses but the
+        // derived key no longer matches
+        for index in [2, 3] {
+            let mut tampered = sealed.clone();
+            tampered[index] = 2;
+            assert!(matches!(
+                SealConfig::open(&sk, b"", &tampered),
+                Err(QraiopError::CryptoError(_))
+            ));
+        }
+
+        let mut unknown = sealed.clone();
+        unknown[2] = 9;
+        assert!(matches!(
+            SealConfig::open(&sk, b"", &unknown),
+            Err(QraiopError::UnsupportedAlgorithm(_))
+        ));
+        let mut version = sealed.clone();
+        version[0] = 2;
+        assert!(matches!(
+            SealConfig::open(&sk, b"", &version),
+            Err(QraiopError::SerializationError(_))
+        ));
+        assert!(matches!(
+            SealConfig::open(&sk, b"", &sealed[..config.overhead() - 1]),
+            Err(QraiopError::CryptoError(_))
+        ));
+
+        // A key of another parameter set is refused
+        let (pk_1024, _) = KemAlgorithm::MlKem1024.keypair().unwrap();
+        assert!(config.seal(&pk_1024, b"", b"attack at dawn").is_err());
+    }
+
+    #[test]
+    fn test_seal_multi_roundtrip() {
+        let keypairs: Vec<_> = (0..3).map(|_| MlKem768::keypair().unwrap()).collect();
+        let recipients: Vec<_> = keypairs.iter().map(|(pk, _)| pk.clone()).collect();
+        let message = seal_multi(&recipients, b"attack at dawn").unwrap();
+        assert_eq!(message.recipient_count(), 3);
+        assert_eq!(message.ciphertext.len(), b"attack at dawn".len() + TAG_SIZE);
+        for (_, sk) in &keypairs {
+            assert_eq!(open_multi(sk, &message).unwrap(), b"attack at dawn");
+        }
+
+        let (_, outsider) = MlKem768::keypair().unwrap();
+        assert!(matches!(
+            open_multi(&outsider, &message),
+            Err(QraiopError::CryptoError(_))
+        ));
+
+        let decoded: MultiRecipientMessage =
+            bincode::deserialize(&bincode::serialize(&message).unwrap()).unwrap();
+        assert_eq!(
+            open_multi(&keypairs[1].1, &decoded).unwrap(),
+            b"attack at dawn"
+        );
+    }
+
+    #[test]
+    fn test_seal_multi_tampering_is_detected() {
+        let (pk, sk) = MlKem768::keypair().unwrap();
+        let message = seal_multi(&[pk], b"attack at dawn").unwrap();
+
+        let mut tampered = message.clone();
+        tampered.ciphertext[0] ^= 0x01;
+        let mut wrong_wrap = message.clone();
+        wrong_wrap.recipients[0].wrapped_key[0] ^= 0x01;
+        let mut wrong_kem = message.clone();
+        wrong_kem.recipients[0].kem_ciphertext[0] ^= 0x01;
+        for tampered in [tampered, wrong_wrap, wrong_kem] {
+            assert!(matches!(
+                open_multi(&sk, &tampered),
+                Err(QraiopError::CryptoError(_))
+            ));
+        }
+
+        assert!(matches!(
+            seal_multi(&[], b"attack at dawn"),
+            Err(QraiopError::CryptoError(_))
+        ));
+    }
+}
@@ -0,0 +1,345 @@
+//! Power-on self-tests in the style of FIPS 140-3
+//!
+//! [`run_self_tests`] checks every ML-KEM and ML-DSA parameter set against
+//! a known answer. With the `self_test` feature, [`crate::init`] runs them
+//! before anything else and fails if they do. [`ensure_self_tested`] runs
+//! them once per process and remembers the outcome, for embedders that
+//! check on every request rather than once at startup.
+//!
+//! The ML-KEM answers are fixed: key generation from a fixed seed and
+//! encapsulation with a fixed message must reproduce the embedded SHA3-256
+//! digest of `pk || ct` and the embedded shared secret, and decapsulation
+//! must recover the same secret. For ML-DSA, key generation from a fixed
+//! seed must reproduce the embedded SHA3-256 digest of the public key.
+//! Signing is always randomized, so it has no fixed answer: a fresh
+//! signature over a fixed message must verify for that message and no
+//! other.
+//!
+//! The ML-DSA answers are the public keys OpenSSL 3.5, an implementation
+//! independent of this crate, derives from the same seed, and the ACVP
+//! keyGen vectors in `tests/kat` hold this key generation to FIPS 204 too.
+
+use crate::pqc::dilithium::{self, MlDsa44, MlDsa65, MlDsa87};
+use crate::pqc::kyber::{
+    Ciphertext, MlKem1024, MlKem512, MlKem768, PublicKey, SecretKey, SharedSecret,
+};
+use crate::pqc::DigitalSignature;
+use crate::pqc::KeyEncapsulation;
+use crate::{QraiopError, Result};
+use alloc::format;
+#[cfg(feature = "std")]
+use alloc::string::{String, ToString};
+use sha3::{Digest, Sha3_256};
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+use subtle::ConstantTimeEq;
+
+/// `d || z` for the ML-KEM known-answer tests: the bytes 0, 1, ..., 63.
+const KEM_SEED: [u8; 64] = {
+    let mut seed = [0u8; 64];
+    let mut i = 0;
+    while i < seed.len() {
+        seed[i] = i as u8;
+        i += 1;
+    }
+    seed
+};
+
+/// Encapsulation randomness for the ML-KEM known-answer tests.
+const KEM_MESSAGE: [u8; 32] = [0xa5; 32];
+
+/// Key generation seed for the ML-DSA known-answer tests: the bytes 0, 1,
+/// ..., 31.
+const SIGNATURE_SEED: [u8; 32] = {
+    let mut seed = [0u8; 32];
+    let mut i = 0;
+    while i < seed.len() {
+        seed[i] = i as u8;
+        i += 1;
+    }
+    seed
+};
+
+const SIGNATURE_MESSAGE: &[u8] = b"QRAIOP power-on self-test";
+
+type Encapsulate = fn(&PublicKey, &[u8; 32]) -> Result<(Ciphertext, SharedSecret)>;
+
+struct KemVector {
+    name: &'static str,
+    keypair_from_seed: fn(&[u8; 64]) -> Result<(PublicKey, SecretKey)>,
+    encapsulate: Encapsulate,
+    decapsulate: fn(&SecretKey, &Ciphertext) -> Result<SharedSecret>,
+    /// SHA3-256 of `pk || ct`
+    digest: [u8; 32],
+    shared_secret: [u8; 32],
+}
+
+const KEM_VECTORS: [KemVector; 3] = [
+    KemVector {
+        name: "ML-KEM-512",
+        keypair_from_seed: MlKem512::keypair_from_seed,
+        encapsulate: MlKem512::encapsulate_deterministic,
+        decapsulate: MlKem512::decapsulate,
+        digest: [
+            0x92, 0xf7, 0xa4, 0xdc, 0x37, 0x67, 0xcd, 0xf6, 0x5c, 0xfa, 0x3c, 0x01, 0xdd, 0x12,
+            0x60, 0x52, 0x15, 0x55, 0xc1, 0x2d, 0x07, 0x07, 0x72, 0xa5, 0xc3, 0x2d, 0x46, 0x10,
+            0x78, 0x97, 0xb5, 0xa8,
+        ],
+        shared_secret: [
+            0x12, 0xa5, 0x7f, 0x1f, 0x78, 0x88, 0x26, 0xed, 0x2e, 0xe9, 0x4a, 0x56, 0x35, 0x5d,
+            0x70, 0x26, 0xaf, 0x9e, 0x67, 0xf4, 0x13, 0x4c, 0xf8, 0xc6, 0xf3, 0xfe, 0xc8, 0x68,
+            0x2a, 0xdc, 0x68, 0xd3,
+        ],
+    },
+    KemVector {
+        name: "ML-KEM-768",
+        keypair_from_seed: MlKem768::keypair_from_seed,
+        encapsulate: MlKem768::encapsulate_deterministic,
+        decapsulate: MlKem768::decapsulate,
+        digest: [
+            0xc1, 0xd7, 0x98, 0x7f, 0x53, 0xfb, 0x8a, 0xc3, 0x71, 0x56, 0xf2, 0x00, 0xc6, 0x46,
+            0x71, 0x3e, 0x08, 0x8d, 0x0d, 0xb7, 0xb1, 0x78, 0x23, 0x77, 0xb6, 0x44, 0x9f, 0x81,
+            0xb9, 0xe9, 0xab, 0x2d,
+        ],
+        shared_secret: [
+            0xe9, 0x9b, 0x52, 0x05, 0xed, 0xfa, 0xec, 0x7c, 0x0a, 0x96, 0x86, 0x68, 0x42, 0x56,
+            0xdf, 0xd4, 0xa2, 0x5a, 0x4f, 0xf4, 0xd7, 0x60, 0xef, 0x69, 0xf9, 0x13, 0xe9, 0x87,
+            0x8c, 0x05, 0xc4, 0xca,
+        ],
+    },
+    KemVector {
+        name: "ML-KEM-1024",
+        keypair_from_seed: MlKem1024::keypair_from_seed,
+        encapsulate: MlKem1024::encapsulate_deterministic,
+        decapsulate: MlKem1024::decapsulate,
+        digest: [
+            0x79, 0x5b, 0x60, 0x97, 0x91, 0x0a, 0x8c, 0xff, 0x3d, 0x91, 0xe7, 0x27, 0x6f, 0x92,
+            0x11, 0x10, 0x88, 0x32, 0x55, 0x26, 0xb0, 0xa7, 0x0d, 0xd9, 0x7c, 0x46, 0xcf, 0x31,
+            0x77, 0xe2, 0x82, 0x26,
+        ],
+        shared_secret: [
+            0xc5, 0x96, 0x0f, 0xa6, 0x87, 0x84, 0xdf, 0x41, 0xaf, 0x3d, 0x9d, 0xef, 0xaf, 0x85,
+            0xb7, 0x6c, 0x63, 0x63, 0xd8, 0x55, 0x95, 0xa3, 0xc8, 0x60, 0x3a, 0x7e, 0x1b, 0xe0,
+            0xef, 0x69, 0x7f, 0xfc,
+        ],
+    },
+];
+
+struct SignatureVector {
+    name: &'static str,
+    keypair_from_seed: fn(&[u8; 32]) -> Result<(dilithium::PublicKey, dilithium::SecretKey)>,
+    sign: fn(&dilithium::SecretKey, &[u8]) -> Result<dilithium::Signature>,
+    verify: fn(&dilithium::PublicKey, &[u8], &dilithium::Signature) -> Result<bool>,
+    /// SHA3-256 of `pk`
+    digest: [u8; 32],
+}
+
+const SIGNATURE_VECTORS: [SignatureVector; 3] = [
+    SignatureVector {
+        name: "ML-DSA-44",
+        keypair_from_seed: MlDsa44::keypair_from_seed,
+        sign: MlDsa44::sign,
+        verify: MlDsa44::verify,
+        digest: [
+            0x37, 0x3c, 0x7b, 0xf2, 0xca, 0xc5, 0xbd, 0x2a, 0x6c, 0x35, 0x93, 0x3b, 0xab, 0x0f,
+            0xa1, 0xc9, 0x51, 0xf2, 0x22, 0x47, 0xe1, 0x33, 0x33, 0x83, 0xfc, 0xb6, 0x18, 0x82,
+            0x20, 0x80, 0x37, 0x3f,
+        ],
+    },
+    SignatureVector {
+        name: "ML-DSA-65",
+        keypair_from_seed: MlDsa65::keypair_from_seed,
+        sign: MlDsa65::sign,
+        verify: MlDsa65::verify,
+        digest: [
+            0x18, 0x00, 0x72, 0x50, 0x67, 0xe3, 0x88, 0xd8, 0x37, 0xd9, 0x11, 0xfe, 0x4f, 0x66,
+            0x10, 0x1c, 0xc1, 0x96, 0x1b, 0x1b, 0xb7, 0x55, 0x03, 0x0d, 0xc5, 0x74, 0x27, 0x2c,
+            0xfb, 0x00, 0x01, 0x3f,
+        ],
+    },
+    SignatureVector {
+        name: "ML-DSA-87",
+        keypair_from_seed: MlDsa87::keypair_from_seed,
+        sign: MlDsa87::sign,
+        verify: MlDsa87::verify,
+        digest: [
+            0xe6, 0xcf, 0x50, 0xa9, 0xc2, 0xfa, 0x52, 0x34, 0xf5, 0x99, 0x49, 0xff, 0x61, 0xf8,
+            0x16, 0x1d, 0xb4, 0xd6, 0x29, 0x53, 0x21, 0x27, 0xf4, 0xae, 0xfa, 0x8b, 0xb1, 0x08,
+            0x11, 0xec, 0xfb, 0x1e,
+        ],
+    },
+];
+
+fn failure(name: &str, what: &str) -> QraiopError {
+    log::error!("Self-test failed: {}: {}", name, what);
+    QraiopError::CryptoError(format!("Self-test failed: {}: {}", name, what))
+}
+
+fn check_kem(vector: &KemVector) -> Result<()> {
+    let (pk, sk) = (vector.keypair_from_seed)(&KEM_SEED)?;
+    let (ct, ss) = (vector.encapsulate)(&pk, &KEM_MESSAGE)?;
+
+    let digest = Sha3_256::new()
+        .chain_update(pk.as_bytes())
+        .chain_update(ct.as_bytes())
+        .finalize();
+    if digest.as_slice() != vector.digest {
+        return Err(failure(
+            vector.name,
+            "key or ciphertext differs from the known answer",
+        ));
+    }
+    if !bool::from(ss.expose_bytes().ct_eq(&vector.shared_secret)) {
+        return Err(failure(
+            vector.name,
+            "shared secret differs from the known answer",
+        ));
+    }
+    let decapsulated = (vector.decapsulate)(&sk, &ct)?;
+    if decapsulated != ss {
+        return Err(failure(
+            vector.name,
+            "decapsulation does not recover the shared secret",
+        ));
+    }
+    Ok(())
+}
+
+fn check_signature(vector: &SignatureVector) -> Result<()> {
+    let (pk, sk) = (vector.keypair_from_seed)(&SIGNATURE_SEED)?;
+    let signature = (vector.sign)(&sk, SIGNATURE_MESSAGE)?;
+
+    if Sha3_256::digest(pk.as_bytes()).as_slice() != vector.digest {
+        return Err(failure(vector.name, "key differs from the known answer"));
+    }
+    if !(vector.verify)(&pk, SIGNATURE_MESSAGE, &signature)? {
+        return Err(failure(vector.name, "valid signature rejected"));
+    }
+    if (vector.verify)(&pk, b"QRAIOP power-on self-tesT", &signature)? {
+        return Err(failure(
+            vector.name,
+            "signature accepted for another message",
+        ));
+    }
+    Ok(())
+}
+
+/// Runs the known-answer tests for every ML-KEM and ML-DSA parameter set.
+///
+/// Returns [`QraiopError::CryptoError`] naming the first algorithm that
+/// fails, after logging it at error level. A failure means the build or the
+/// machine is broken; the library should not be used.
+pub fn run_self_tests() -> Result<()> {
+    for vector in &KEM_VECTORS {
+        check_kem(vector)?;
+    }
+    for vector in &SIGNATURE_VECTORS {
+        check_signature(vector)?;
+    }
+    Ok(())
+}
+
+/// Outcome of the first [`ensure_self_tested`] call, as the failure message
+/// since errors are not `Clone`.
+#[cfg(feature = "std")]
+type Outcome = core::result::Result<(), String>;
+
+#[cfg(feature = "std")]
+static OUTCOME: OnceLock<Outcome> = OnceLock::new();
+
+/// [`run_self_tests`], run at most once per process.
+///
+/// The first call runs the tests; concurrent first calls wait for it
+/// rather than running them again. Every later call returns the same
+/// outcome without repeating any work, so a failure is sticky: it comes
+/// back as [`QraiopError::CryptoError`] with the original message for the
+/// life of the process.
+#[cfg(feature = "std")]
+pub fn ensure_self_tested() -> Result<()> {
+    ensure_once(&OUTCOME, run_self_tests)
+}
+
+#[cfg(feature = "std")]
+fn ensure_once(outcome: &OnceLock<Outcome>, run: impl FnOnce() -> Result<()>) -> Result<()> {
+    outcome
+        .get_or_init(|| {
+            run().map_err(|e| match e {
+                QraiopError::CryptoError(message) => message,
+                other => other.to_string(),
+            })
+        })
+        .clone()
+        .map_err(QraiopError::CryptoError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_tests_pass() {
+        run_self_tests().unwrap();
+    }
+
+    #[test]
+    fn test_tampered_vector_fails() {
+        let mut digest = KEM_VECTORS[1].digest;
+        digest[0] ^= 1;
+        let tampered = KemVector {
+            digest,
+            ..KEM_VECTORS[1]
+        };
+        let err = check_kem(&tampered).unwrap_err();
+        assert!(err.to_string().contains("ML-KEM-768"), "{}", err);
+
+        let mut shared_secret = KEM_VECTORS[1].shared_secret;
+        shared_secret[31] ^= 0x80;
+        let tampered = KemVector {
+            shared_secret,
+            ..KEM_VECTORS[1]
+        };
+        assert!(matches!(
+            check_kem(&tampered),
+            Err(QraiopError::CryptoError(_))
+        ));
+
+        let mut digest = SIGNATURE_VECTORS[2].digest;
+        digest[15] ^= 0x10;
+        let tampered = SignatureVector {
+            digest,
+            ..SIGNATURE_VECTORS[2]
+        };
+        let err = check_signature(&tampered).unwrap_err();
+        assert!(err.to_string().contains("ML-DSA-87"), "{}", err);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_ensure_self_tested_runs_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        ensure_self_tested().unwrap();
+        ensure_self_tested().unwrap();
+
+        let outcome = OnceLock::new();
+        let runs = AtomicUsize::new(0);
+        let run = || {
+            runs.fetch_add(1, Ordering::SeqCst);
+            Err(failure("ML-KEM-768", "injected"))
+        };
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..100 {
+                        let err = ensure_once(&outcome, run).unwrap_err();
+                        assert_eq!(
+                            err.to_string(),
+                            "Cryptographic operation failed: Self-test failed: ML-KEM-768: injected"
+                        );
+                    }
+                });
+            }
+        });
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,163 @@
+//! IANA TLS named groups for the KEMs in this crate
+//!
+//! TLS 1.3 negotiates key exchange by the 16-bit codepoints of the "TLS
+//! Supported Groups" registry, sent in the `supported_groups` and
+//! `key_share` extensions. [`NamedGroup`] maps each group this crate can
+//! provide to its codepoint and back:
+//!
+//! ```text
+//! MLKEM512        0x0200    ML-KEM-512
+//! MLKEM768        0x0201    ML-KEM-768
+//! MLKEM1024       0x0202    ML-KEM-1024
+//! X25519MLKEM768  0x11EC    ML-KEM-768 with X25519
+//! ```
+//!
+//! Only the codepoints are covered here. The X25519MLKEM768 key share puts
+//! the ML-KEM-768 value first, then the X25519 one, and its shared secret
+//! is the plain concatenation of the two in the same order; that is not
+//! what [`crate::hybrid::HybridKem`] with its default combiner produces.
+
+use crate::pqc::kyber::KemAlgorithm;
+use crate::{QraiopError, Result};
+use alloc::format;
+use core::fmt;
+
+/// A TLS named group backed by ML-KEM, alone or with X25519.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NamedGroup {
+    MlKem512,
+    MlKem768,
+    MlKem1024,
+    X25519MlKem768,
+}
+
+impl NamedGroup {
+    /// Every group, in codepoint order.
+    pub const ALL: [NamedGroup; 4] = [
+        NamedGroup::MlKem512,
+        NamedGroup::MlKem768,
+        NamedGroup::MlKem1024,
+        NamedGroup::X25519MlKem768,
+    ];
+
+    /// The group with IANA codepoint `codepoint`, or `None` for one this
+    /// crate does not provide.
+    pub fn from_named_group(codepoint: u16) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|group| group.named_group() == codepoint)
+    }
+
+    /// IANA codepoint of this group.
+    pub fn named_group(self) -> u16 {
+        match self {
+            NamedGroup::MlKem512 => 0x0200,
+            NamedGroup::MlKem768 => 0x0201,
+            NamedGroup::MlKem1024 => 0x0202,
+            NamedGroup::X25519MlKem768 => 0x11EC,
+        }
+    }
+
+    /// Name of this group in the IANA registry, e.g. `"X25519MLKEM768"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            NamedGroup::MlKem512 => "MLKEM512",
+            NamedGroup::MlKem768 => "MLKEM768",
+            NamedGroup::MlKem1024 => "MLKEM1024",
+            NamedGroup::X25519MlKem768 => "X25519MLKEM768",
+        }
+    }
+
+    /// The ML-KEM parameter set of this group.
+    pub fn kem(self) -> KemAlgorithm {
+        match self {
+            NamedGroup::MlKem512 => KemAlgorithm::MlKem512,
+            NamedGroup::MlKem768 | NamedGroup::X25519MlKem768 => KemAlgorithm::MlKem768,
+            NamedGroup::MlKem1024 => KemAlgorithm::MlKem1024,
+        }
+    }
+
+    /// Whether the group also runs X25519.
+    pub fn is_hybrid(self) -> bool {
+        matches!(self, NamedGroup::X25519MlKem768)
+    }
+}
+
+/// The pure ML-KEM group of each parameter set.
+impl From<KemAlgorithm> for NamedGroup {
+    fn from(algorithm: KemAlgorithm) -> Self {
+        match algorithm {
+            KemAlgorithm::MlKem512 => NamedGroup::MlKem512,
+            KemAlgorithm::MlKem768 => NamedGroup::MlKem768,
+            KemAlgorithm::MlKem1024 => NamedGroup::MlKem1024,
+        }
+    }
+}
+
+impl TryFrom<u16> for NamedGroup {
+    type Error = QraiopError;
+
+    /// Fails with [`QraiopError::UnsupportedAlgorithm`] for codepoints
+    /// [`NamedGroup::from_named_group`] does not know.
+    fn try_from(codepoint: u16) -> Result<Self> {
+        Self::from_named_group(codepoint).ok_or_else(|| {
+            QraiopError::UnsupportedAlgorithm(format!("TLS named group 0x{:04x}", codepoint))
+        })
+    }
+}
+
+impl From<NamedGroup> for u16 {
+    fn from(group: NamedGroup) -> Self {
+        group.named_group()
+    }
+}
+
+impl fmt::Display for NamedGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_x25519_mlkem768_roundtrip() {
+        let group = NamedGroup::from_named_group(0x11EC).unwrap();
+        assert_eq!(group, NamedGroup::X25519MlKem768);
+        assert_eq!(group.named_group(), 0x11EC);
+        assert_eq!(u16::from(group), 0x11EC);
+        assert_eq!(group.to_string(), "X25519MLKEM768");
+        assert_eq!(group.kem(), KemAlgorithm::MlKem768);
+        assert!(group.is_hybrid());
+    }
+
+    #[test]
+    fn test_every_group_roundtrips() {
+        for group in NamedGroup::ALL {
+            assert_eq!(NamedGroup::try_from(group.named_group()).unwrap(), group);
+        }
+        for algorithm in [
+            KemAlgorithm::MlKem512,
+            KemAlgorithm::MlKem768,
+            KemAlgorithm::MlKem1024,
+        ] {
+            let group = NamedGroup::from(algorithm);
+            assert_eq!(group.kem(), algorithm);
+            assert!(!group.is_hybrid());
+        }
+    }
+
+    #[test]
+    fn test_unknown_codepoint() {
+        // X25519 alone, and SecP256r1MLKEM768, which needs P-256
+        for codepoint in [0x001D, 0x11EB] {
+            assert_eq!(NamedGroup::from_named_group(codepoint), None);
+            assert!(matches!(
+                NamedGroup::try_from(codepoint),
+                Err(QraiopError::UnsupportedAlgorithm(_))
+            ));
+        }
+    }
+}
@@ -1,9 +1,672 @@
 //! Utility functions for QRAIOP cryptography
 
+#[cfg(all(feature = "std", feature = "kyber"))]
+mod file;
+pub mod passphrase;
+pub mod pkcs8;
+mod rng;
+pub(crate) mod secret;
+mod transcript;
+
+#[cfg(all(feature = "std", feature = "kyber"))]
+pub use file::{decrypt_file, encrypt_file};
+#[cfg(feature = "test_rng")]
+pub use rng::reset_test_rng;
+pub(crate) use rng::SystemRng;
+pub use transcript::Transcript;
+
+use crate::{QraiopError, Result};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use hkdf::Hkdf;
+use rand::RngCore;
+use sha2::Sha256;
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroizing;
+
 pub fn bytes_to_hex(bytes: &[u8]) -> String {
     hex::encode(bytes)
 }
 
-pub fn hex_to_bytes(hex_str: &str) -> Result<Vec<u8>, hex::FromHexError> {
-    hex::decode(hex_str)
+/// Decodes hex with the `hex` crate, whose running time depends on the
+/// digits. Fine for public data such as keys' public halves and
+/// ciphertexts; for secret keys use [`hex_to_bytes_ct`].
+///
+/// Errors are `QraiopError::SerializationError`, naming the bad character
+/// and its position.
+pub fn hex_to_bytes(hex_str: &str) -> Result<Vec<u8>> {
+    Ok(hex::decode(hex_str)?)
+}
+
+/// Decodes upper- or lowercase hex without branching on or indexing by the
+/// digits, for secret material such as keys read from config. Only the
+/// length, and whether the input was valid at all, show in the timing.
+///
+/// Errors are `QraiopError::SerializationError` and deliberately do not
+/// say which character was bad.
+pub fn hex_to_bytes_ct(hex_str: &str) -> Result<Zeroizing<Vec<u8>>> {
+    let digits = hex_str.as_bytes();
+    if !digits.len().is_multiple_of(2) {
+        return Err(QraiopError::SerializationError(
+            "Hex string has an odd number of digits".to_string(),
+        ));
+    }
+    let mut bytes = Zeroizing::new(vec![0u8; digits.len() / 2]);
+    let mut valid = 0xffu8;
+    for (byte, pair) in bytes.iter_mut().zip(digits.chunks_exact(2)) {
+        let (high, high_valid) = hex_digit_ct(pair[0]);
+        let (low, low_valid) = hex_digit_ct(pair[1]);
+        *byte = (high << 4) | low;
+        valid &= high_valid & low_valid;
+    }
+    if valid != 0xff {
+        return Err(QraiopError::SerializationError(
+            "Hex string contains a non-hex character".to_string(),
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Value of one hex digit and a mask that is `0xff` if `c` is a digit and
+/// `0` otherwise. Each range test is a pair of subtractions whose sign bits
+/// are both set only inside the range.
+fn hex_digit_ct(c: u8) -> (u8, u8) {
+    let c = i16::from(c);
+    let in_range = |low: i16, high: i16| ((low - 1 - c) & (c - high - 1)) >> 8;
+    let digit = in_range(0x30, 0x39);
+    let upper = in_range(0x41, 0x46);
+    let lower = in_range(0x61, 0x66);
+    let value = (digit & (c - 0x30)) | (upper & (c - 0x37)) | (lower & (c - 0x57));
+    (value as u8, (digit | upper | lower) as u8)
+}
+
+/// Reads a secret from the environment variable `name`, encoded as hex
+/// (decoded with [`hex_to_bytes_ct`]) or, failing that, standard padded
+/// base64. Surrounding whitespace is ignored. The copy of the variable and
+/// the decoded bytes are wiped when dropped; a missing, non-UTF-8 or
+/// undecodable variable fails with `QraiopError::InvalidKey`.
+///
+/// Environment variables are a weak place for secrets and this only
+/// limits the damage:
+///
+/// - The process's own environment block is not touched, so the value
+///   stays readable through `/proc/<pid>/environ` by the same user and
+///   root, is inherited by child processes, and can end up in core dumps.
+///   Unset the variable once every key is loaded if that matters.
+/// - Orchestrators often show the value in plain text, e.g. in
+///   `docker inspect` or a pod spec, and it may be logged on startup.
+/// - Base64 decoding is not constant time; use hex if the timing of
+///   key loading is observable.
+///
+/// Prefer a mounted secrets file or a KMS where the platform offers one.
+#[cfg(feature = "std")]
+pub fn secret_from_env(name: &str) -> Result<Zeroizing<Vec<u8>>> {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+
+    let value =
+        Zeroizing::new(std::env::var(name).map_err(|e| {
+            QraiopError::InvalidKey(format!("Environment variable {}: {}", name, e))
+        })?);
+    let encoded = value.trim();
+    if let Ok(bytes) = hex_to_bytes_ct(encoded) {
+        return Ok(bytes);
+    }
+    // Decode into a buffer that already has room, so no unwiped copy is
+    // left behind by reallocation
+    let mut bytes = Zeroizing::new(Vec::with_capacity(encoded.len() / 4 * 3 + 3));
+    STANDARD.decode_vec(encoded, &mut bytes).map_err(|_| {
+        QraiopError::InvalidKey(format!(
+            "Environment variable {} is neither hex nor base64",
+            name
+        ))
+    })?;
+    Ok(bytes)
+}
+
+/// Compares two byte strings without exiting early at the first difference,
+/// for checking tags, MACs and other values an attacker must not learn
+/// byte by byte.
+///
+/// Only the contents are compared in constant time: slices of different
+/// lengths return `false` straight away, so timing reveals whether the
+/// lengths match. That is harmless when the length is public anyway, but
+/// for fixed-size secrets [`constant_time_eq_fixed`] avoids the branch.
+#[must_use = "verification result must be checked"]
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
+
+/// [`constant_time_eq`] for arrays of one fixed length, such as 32-byte
+/// shared secrets. Both lengths are `N`, so there is no length branch and
+/// the comparison takes the same time for any contents.
+#[must_use = "verification result must be checked"]
+pub fn constant_time_eq_fixed<const N: usize>(a: &[u8; N], b: &[u8; N]) -> bool {
+    a.iter()
+        .zip(b.iter())
+        .fold(Choice::from(1), |equal, (x, y)| equal & x.ct_eq(y))
+        .into()
+}
+
+/// Returns `len` bytes from the operating system's CSPRNG.
+///
+/// On `wasm32-unknown-unknown` this needs the `js` feature, which routes
+/// `getrandom` to the browser's (or Node's) `crypto.getRandomValues`.
+/// Without it the crate fails to build for that target rather than
+/// panicking at runtime.
+pub fn secure_random(len: usize) -> Result<Vec<u8>> {
+    let mut bytes = vec![0u8; len];
+    secure_random_into(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Fills `buf` from the operating system's CSPRNG without allocating, for
+/// callers that reuse one buffer. Platform requirements are as for
+/// [`secure_random`]; a failing RNG is reported as
+/// `QraiopError::CryptoError`, leaving `buf` in an unspecified state.
+pub fn secure_random_into(buf: &mut [u8]) -> Result<()> {
+    SystemRng
+        .try_fill_bytes(buf)
+        .map_err(|e| QraiopError::CryptoError(format!("Random number generation failed: {}", e)))
+}
+
+/// Fills `buf` from `rng`. Unlike `RngCore::fill_bytes`, which panics when
+/// the RNG fails, this reports the failure as `error`, e.g.
+/// `QraiopError::EncapsulationFailed` for an encapsulation's message.
+pub(crate) fn fill_random<R: RngCore + ?Sized>(
+    rng: &mut R,
+    buf: &mut [u8],
+    error: fn(String) -> QraiopError,
+) -> Result<()> {
+    rng.try_fill_bytes(buf)
+        .map_err(|e| error(format!("Random number generation failed: {}", e)))
+}
+
+/// [`fill_random`] for a key generation seed, failing with
+/// `QraiopError::KeyGenerationFailed`.
+pub(crate) fn keygen_seed<R: RngCore + ?Sized>(rng: &mut R, seed: &mut [u8]) -> Result<()> {
+    fill_random(rng, seed, QraiopError::KeyGenerationFailed)
+}
+
+/// An RNG that always fails, as `OsRng` does when `getrandom` cannot reach
+/// the operating system. Only the families that take a caller's RNG have a
+/// use for it.
+#[cfg(all(
+    test,
+    any(feature = "kyber", feature = "dilithium", feature = "sphincs")
+))]
+pub(crate) struct FailingRng;
+
+#[cfg(all(
+    test,
+    any(feature = "kyber", feature = "dilithium", feature = "sphincs")
+))]
+impl RngCore for FailingRng {
+    fn next_u32(&mut self) -> u32 {
+        panic!("FailingRng only supports try_fill_bytes")
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        panic!("FailingRng only supports try_fill_bytes")
+    }
+
+    fn fill_bytes(&mut self, _dest: &mut [u8]) {
+        panic!("FailingRng only supports try_fill_bytes")
+    }
+
+    fn try_fill_bytes(&mut self, _dest: &mut [u8]) -> core::result::Result<(), rand::Error> {
+        let code = core::num::NonZeroU32::new(rand::Error::CUSTOM_START).unwrap();
+        Err(rand::Error::from(code))
+    }
+}
+
+#[cfg(all(
+    test,
+    any(feature = "kyber", feature = "dilithium", feature = "sphincs")
+))]
+impl rand::CryptoRng for FailingRng {}
+
+/// Expands a KEM shared secret into `out_len` bytes of key material with
+/// HKDF-SHA256 (RFC 5869), using no salt and `info` for domain separation.
+///
+/// Takes any shared secret type, such as [`crate::pqc::kyber::SharedSecret`]
+/// or [`crate::hybrid::SharedSecret`]. `out_len` may be at most 8160 bytes.
+/// The pseudorandom key and the output are held in zeroizing buffers.
+pub fn derive_keys<S: AsRef<[u8]>>(
+    shared_secret: &S,
+    info: &[u8],
+    out_len: usize,
+) -> Result<Zeroizing<Vec<u8>>> {
+    let (prk, _) = Hkdf::<Sha256>::extract(None, shared_secret.as_ref());
+    let prk = Zeroizing::new(<[u8; 32]>::from(prk));
+    let hkdf = Hkdf::<Sha256>::from_prk(prk.as_ref())
+        .map_err(|e| QraiopError::CryptoError(format!("Key derivation failed: {}", e)))?;
+    let mut okm = Zeroizing::new(vec![0u8; out_len]);
+    hkdf.expand(info, &mut okm)
+        .map_err(|e| QraiopError::CryptoError(format!("Key derivation failed: {}", e)))?;
+    Ok(okm)
+}
+
+/// [`derive_keys`] for a single 32-byte key.
+pub fn derive_key_32<S: AsRef<[u8]>>(
+    shared_secret: &S,
+    info: &[u8],
+) -> Result<Zeroizing<[u8; 32]>> {
+    let okm = derive_keys(shared_secret, info, 32)?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    key.copy_from_slice(&okm);
+    Ok(key)
+}
+
+/// Ratchet step: mixes a fresh KEM shared secret into a running root key
+/// and returns the next root.
+///
+/// This is HKDF-SHA256 with `root` as the salt, `new_secret` as the input
+/// key material and `transcript` (for example the ciphertext and both
+/// parties' public keys) as the info string. The same inputs always give
+/// the same root; a different transcript gives an unrelated one. Like
+/// [`derive_keys`] it works with any shared secret type. Drop or zeroize
+/// the old root once the new one is in place: forward secrecy only holds
+/// if it cannot be recovered later.
+///
+/// The root comes back as a `Zeroizing<[u8; 32]>`, as from
+/// [`derive_key_32`], rather than a `Vec<u8>`: it is wiped when dropped,
+/// and its length is fixed by the type. Call `.to_vec()` where a vector is
+/// needed, and zeroize that copy yourself.
+pub fn mix_secrets<S: AsRef<[u8]>>(
+    root: &[u8],
+    new_secret: &S,
+    transcript: &[u8],
+) -> Zeroizing<[u8; 32]> {
+    let (prk, _) = Hkdf::<Sha256>::extract(Some(root), new_secret.as_ref());
+    let prk = Zeroizing::new(<[u8; 32]>::from(prk));
+    let mut next = Zeroizing::new([0u8; 32]);
+    Hkdf::<Sha256>::from_prk(prk.as_ref())
+        .expect("a 32-byte PRK is valid for HKDF-SHA256")
+        .expand(transcript, next.as_mut())
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    next
+}
+
+#[cfg(any(feature = "kyber", feature = "dilithium", feature = "sphincs"))]
+/// SHA3-256 digest identifying a public key.
+pub(crate) fn fingerprint_full(key: &[u8]) -> [u8; 32] {
+    use sha3::{Digest, Sha3_256};
+    Sha3_256::digest(key).into()
+}
+
+#[cfg(any(feature = "kyber", feature = "dilithium", feature = "sphincs"))]
+/// Short display form of [`fingerprint_full`]: its first 8 bytes as
+/// colon-separated hex, e.g. `3f:a0:1c:77:09:be:42:d5`.
+pub(crate) fn fingerprint(key: &[u8]) -> String {
+    fingerprint_full(key)[..8]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Canonical byte lengths for each supported parameter set.
+pub mod key_sizes {
+    pub mod ml_kem_512 {
+        pub const PUBLIC_KEY_SIZE: usize = 800;
+        pub const SECRET_KEY_SIZE: usize = 1632;
+        pub const CIPHERTEXT_SIZE: usize = 768;
+        pub const SHARED_SECRET_SIZE: usize = 32;
+    }
+
+    pub mod ml_kem_768 {
+        pub const PUBLIC_KEY_SIZE: usize = 1184;
+        pub const SECRET_KEY_SIZE: usize = 2400;
+        pub const CIPHERTEXT_SIZE: usize = 1088;
+        pub const SHARED_SECRET_SIZE: usize = 32;
+    }
+
+    pub mod ml_kem_1024 {
+        pub const PUBLIC_KEY_SIZE: usize = 1568;
+        pub const SECRET_KEY_SIZE: usize = 3168;
+        pub const CIPHERTEXT_SIZE: usize = 1568;
+        pub const SHARED_SECRET_SIZE: usize = 32;
+    }
+
+    pub mod ml_dsa_44 {
+        pub const PUBLIC_KEY_SIZE: usize = 1312;
+        pub const SECRET_KEY_SIZE: usize = 2560;
+        pub const SIGNATURE_SIZE: usize = 2420;
+    }
+
+    pub mod ml_dsa_65 {
+        pub const PUBLIC_KEY_SIZE: usize = 1952;
+        pub const SECRET_KEY_SIZE: usize = 4032;
+        pub const SIGNATURE_SIZE: usize = 3309;
+    }
+
+    pub mod ml_dsa_87 {
+        pub const PUBLIC_KEY_SIZE: usize = 2592;
+        pub const SECRET_KEY_SIZE: usize = 4896;
+        pub const SIGNATURE_SIZE: usize = 4627;
+    }
+
+    pub mod slh_dsa_128s {
+        pub const PUBLIC_KEY_SIZE: usize = 32;
+        pub const SECRET_KEY_SIZE: usize = 64;
+        pub const SIGNATURE_SIZE: usize = 7856;
+    }
+
+    pub mod slh_dsa_192s {
+        pub const PUBLIC_KEY_SIZE: usize = 48;
+        pub const SECRET_KEY_SIZE: usize = 96;
+        pub const SIGNATURE_SIZE: usize = 16224;
+    }
+
+    pub mod slh_dsa_256s {
+        pub const PUBLIC_KEY_SIZE: usize = 64;
+        pub const SECRET_KEY_SIZE: usize = 128;
+        pub const SIGNATURE_SIZE: usize = 29792;
+    }
+
+    pub mod lms_sha256_h5 {
+        pub const PUBLIC_KEY_SIZE: usize = 60;
+        pub const SECRET_KEY_SIZE: usize = 48;
+        pub const SIGNATURE_SIZE: usize = 2352;
+    }
+
+    pub mod lms_sha256_h10 {
+        pub const PUBLIC_KEY_SIZE: usize = 60;
+        pub const SECRET_KEY_SIZE: usize = 48;
+        pub const SIGNATURE_SIZE: usize = 2512;
+    }
+
+    /// Sizes of one parameter set, as returned by [`for_algorithm`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum KeySizes {
+        Kem(KemSizes),
+        Signature(SignatureSizes),
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct KemSizes {
+        pub public_key: usize,
+        pub secret_key: usize,
+        pub ciphertext: usize,
+        pub shared_secret: usize,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct SignatureSizes {
+        pub public_key: usize,
+        pub secret_key: usize,
+        pub signature: usize,
+    }
+
+    impl KeySizes {
+        pub const fn public_key(&self) -> usize {
+            match self {
+                KeySizes::Kem(sizes) => sizes.public_key,
+                KeySizes::Signature(sizes) => sizes.public_key,
+            }
+        }
+
+        pub const fn secret_key(&self) -> usize {
+            match self {
+                KeySizes::Kem(sizes) => sizes.secret_key,
+                KeySizes::Signature(sizes) => sizes.secret_key,
+            }
+        }
+    }
+
+    macro_rules! kem_sizes {
+        ($module:ident) => {
+            KeySizes::Kem(KemSizes {
+                public_key: $module::PUBLIC_KEY_SIZE,
+                secret_key: $module::SECRET_KEY_SIZE,
+                ciphertext: $module::CIPHERTEXT_SIZE,
+                shared_secret: $module::SHARED_SECRET_SIZE,
+            })
+        };
+    }
+
+    macro_rules! signature_sizes {
+        ($module:ident) => {
+            KeySizes::Signature(SignatureSizes {
+                public_key: $module::PUBLIC_KEY_SIZE,
+                secret_key: $module::SECRET_KEY_SIZE,
+                signature: $module::SIGNATURE_SIZE,
+            })
+        };
+    }
+
+    const fn str_eq(a: &str, b: &str) -> bool {
+        let (a, b) = (a.as_bytes(), b.as_bytes());
+        if a.len() != b.len() {
+            return false;
+        }
+        let mut i = 0;
+        while i < a.len() {
+            if a[i] != b[i] {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    /// Looks up sizes by algorithm name: any name in [`crate::ALGORITHMS`],
+    /// plus `"LMS-SHA256-H5"` and `"LMS-SHA256-H10"`. Returns `None` for
+    /// anything else. Usable in `const` context, e.g. to size a static
+    /// buffer from a configured name.
+    pub const fn for_algorithm(name: &str) -> Option<KeySizes> {
+        Some(if str_eq(name, "ML-KEM-512") {
+            kem_sizes!(ml_kem_512)
+        } else if str_eq(name, "ML-KEM-768") {
+            kem_sizes!(ml_kem_768)
+        } else if str_eq(name, "ML-KEM-1024") {
+            kem_sizes!(ml_kem_1024)
+        } else if str_eq(name, "ML-DSA-44") {
+            signature_sizes!(ml_dsa_44)
+        } else if str_eq(name, "ML-DSA-65") {
+            signature_sizes!(ml_dsa_65)
+        } else if str_eq(name, "ML-DSA-87") {
+            signature_sizes!(ml_dsa_87)
+        } else if str_eq(name, "SLH-DSA-128s") {
+            signature_sizes!(slh_dsa_128s)
+        } else if str_eq(name, "SLH-DSA-192s") {
+            signature_sizes!(slh_dsa_192s)
+        } else if str_eq(name, "SLH-DSA-256s") {
+            signature_sizes!(slh_dsa_256s)
+        } else if str_eq(name, "LMS-SHA256-H5") {
+            signature_sizes!(lms_sha256_h5)
+        } else if str_eq(name, "LMS-SHA256-H10") {
+            signature_sizes!(lms_sha256_h10)
+        } else {
+            return None;
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"", b""));
+        assert!(constant_time_eq(b"secret tag", b"secret tag"));
+        assert!(!constant_time_eq(b"secret tag", b"secret taG"));
+        assert!(!constant_time_eq(b"Secret tag", b"secret tag"));
+        // A prefix is not equal, however the contents compare
+        assert!(!constant_time_eq(b"secret", b"secret tag"));
+        assert!(!constant_time_eq(b"", b"x"));
+
+        let a = [0x5a; 32];
+        let mut b = a;
+        assert!(constant_time_eq_fixed(&a, &b));
+        for i in [0, 17, 31] {
+            b = a;
+            b[i] ^= 0x80;
+            assert!(!constant_time_eq_fixed(&a, &b));
+        }
+        assert!(constant_time_eq_fixed(&[], &[]));
+    }
+
+    #[test]
+    fn test_key_sizes_for_every_algorithm() {
+        use crate::{AlgorithmKind, SupportedAlgorithm};
+        use key_sizes::{for_algorithm, KeySizes};
+
+        for algorithm in SupportedAlgorithm::ALL {
+            let sizes = for_algorithm(algorithm.name()).unwrap();
+            match (algorithm.kind(), sizes) {
+                (AlgorithmKind::Kem, KeySizes::Kem(kem)) => assert_eq!(kem.shared_secret, 32),
+                (
+                    AlgorithmKind::Signature | AlgorithmKind::HashBasedSignature,
+                    KeySizes::Signature(_),
+                ) => {}
+                _ => panic!("{} has sizes of the wrong kind", algorithm),
+            }
+        }
+
+        const ML_KEM_768: Option<KeySizes> = for_algorithm("ML-KEM-768");
+        match ML_KEM_768 {
+            Some(KeySizes::Kem(kem)) => {
+                assert_eq!(kem.public_key, key_sizes::ml_kem_768::PUBLIC_KEY_SIZE);
+                assert_eq!(kem.ciphertext, key_sizes::ml_kem_768::CIPHERTEXT_SIZE);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(
+            for_algorithm("LMS-SHA256-H10").unwrap().public_key(),
+            key_sizes::lms_sha256_h10::PUBLIC_KEY_SIZE
+        );
+        assert_eq!(for_algorithm("ML-KEM-76"), None);
+        assert_eq!(for_algorithm("ml-kem-768"), None);
+    }
+
+    #[test]
+    fn test_secure_random() {
+        assert!(secure_random(0).unwrap().is_empty());
+        let a = secure_random(32).unwrap();
+        let b = secure_random(32).unwrap();
+        assert_eq!(a.len(), 32);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_secret_from_env() {
+        std::env::set_var("QRAIOP_TEST_SECRET_HEX", " 00ff10\n");
+        std::env::set_var("QRAIOP_TEST_SECRET_BASE64", "AP8Q");
+        std::env::set_var("QRAIOP_TEST_SECRET_GARBAGE", "not a key!");
+        assert_eq!(
+            *secret_from_env("QRAIOP_TEST_SECRET_HEX").unwrap(),
+            [0, 255, 16]
+        );
+        assert_eq!(
+            *secret_from_env("QRAIOP_TEST_SECRET_BASE64").unwrap(),
+            [0, 255, 16]
+        );
+        for name in ["QRAIOP_TEST_SECRET_GARBAGE", "QRAIOP_TEST_SECRET_UNSET"] {
+            assert!(matches!(
+                secret_from_env(name),
+                Err(QraiopError::InvalidKey(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_secure_random_into() {
+        let mut a = [0u8; 64];
+        secure_random_into(&mut a).unwrap();
+        // Both ends written: 16 zero bytes by chance is a 2^-128 event
+        assert_ne!(a[..16], [0u8; 16]);
+        assert_ne!(a[48..], [0u8; 16]);
+
+        let mut b = [0u8; 64];
+        secure_random_into(&mut b).unwrap();
+        assert_ne!(a, b);
+        secure_random_into(&mut []).unwrap();
+    }
+
+    #[test]
+    fn test_derive_keys_rfc5869_vector() {
+        // RFC 5869, test case 3: SHA-256 with empty salt and info
+        let okm = derive_keys(&[0x0bu8; 22], b"", 42).unwrap();
+        assert_eq!(
+            bytes_to_hex(&okm),
+            "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d\
+             9d201395faa4b61a96c8"
+        );
+    }
+
+    #[test]
+    fn test_hex_to_bytes_ct() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for encoded in [hex::encode(&bytes), hex::encode_upper(&bytes)] {
+            assert_eq!(*hex_to_bytes_ct(&encoded).unwrap(), bytes);
+            assert_eq!(
+                *hex_to_bytes_ct(&encoded).unwrap(),
+                hex_to_bytes(&encoded).unwrap()
+            );
+        }
+        assert!(hex_to_bytes_ct("").unwrap().is_empty());
+        assert_eq!(*hex_to_bytes_ct("aB0f").unwrap(), [0xab, 0x0f]);
+
+        for bad in [
+            "abc", "0g", "g0", "zz", "/0", ":0", "@0", "G0", "`0", "0x", " 0",
+        ] {
+            assert!(
+                matches!(
+                    hex_to_bytes_ct(bad),
+                    Err(QraiopError::SerializationError(_))
+                ),
+                "{:?}",
+                bad
+            );
+            assert!(hex_to_bytes(bad).is_err());
+        }
+    }
+
+    #[test]
+    fn test_mix_secrets() {
+        let root = [1u8; 32];
+        let secret = [2u8; 32];
+        let next = mix_secrets(&root, &secret, b"transcript");
+        assert_eq!(*next, *mix_secrets(&root, &secret, b"transcript"));
+        assert_ne!(*next, *mix_secrets(&root, &secret, b"transcripT"));
+        assert_ne!(*next, *mix_secrets(&[0u8; 32], &secret, b"transcript"));
+        assert_ne!(*next, root);
+
+        // Matches a plain HKDF-SHA256 with the root as salt
+        let mut expected = [0u8; 32];
+        Hkdf::<Sha256>::new(Some(&root), &secret)
+            .expand(b"transcript", &mut expected)
+            .unwrap();
+        assert_eq!(*next, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "kyber")]
+    fn test_derive_keys_from_shared_secret() {
+        use crate::pqc::kyber::MlKem768;
+        use crate::pqc::KeyEncapsulation;
+
+        let (pk, sk) = MlKem768::keypair().unwrap();
+        let (ct, ss) = MlKem768::encapsulate(&pk).unwrap();
+        let ss2 = MlKem768::decapsulate(&sk, &ct).unwrap();
+
+        let key = derive_key_32(&ss, b"encryption").unwrap();
+        assert_eq!(*key, *derive_key_32(&ss2, b"encryption").unwrap());
+        assert_ne!(*key, *derive_key_32(&ss, b"authentication").unwrap());
+        assert_eq!(
+            &derive_keys(&ss, b"encryption", 64).unwrap()[..32],
+            &key[..]
+        );
+
+        assert!(matches!(
+            derive_keys(&ss, b"", 255 * 32 + 1),
+            Err(QraiopError::CryptoError(_))
+        ));
+    }
 }
@@ -0,0 +1,282 @@
+//! Streaming public-key file encryption
+//!
+//! Like [`crate::seal`], a fresh ML-KEM-768 encapsulation to the recipient
+//! yields the key, but the file is encrypted in 64 KiB chunks so neither
+//! side holds it in memory. Each chunk is sealed with AES-256-GCM under a
+//! nonce made of its index and a final-chunk flag, in the manner of the
+//! STREAM construction, so reordered, dropped or truncated chunks fail to
+//! decrypt. The header is authenticated with every chunk:
+//!
+//! ```text
+//! version (1) || kem_ciphertext (1088)
+//!   || chunk_0 || ... || chunk_n        each aes_gcm_ciphertext || tag (16)
+//! ```
+//!
+//! Every chunk but the last carries exactly 64 KiB of plaintext; the last
+//! carries less, possibly nothing.
+
+use crate::pqc::kyber::{self, MlKem768};
+use crate::pqc::KeyEncapsulation;
+use crate::utils::derive_key_32;
+use crate::utils::key_sizes::ml_kem_768;
+use crate::{QraiopError, Result};
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+use zeroize::Zeroizing;
+
+const FORMAT_VERSION: u8 = 1;
+/// Domain separator for deriving the file key from the shared secret.
+const INFO: &[u8] = b"QRAIOP-file-v1 ML-KEM-768 HKDF-SHA256 AES-256-GCM";
+const HEADER_SIZE: usize = 1 + ml_kem_768::CIPHERTEXT_SIZE;
+const CHUNK_SIZE: usize = 64 * 1024;
+const TAG_SIZE: usize = 16;
+
+fn cipher<S: AsRef<[u8]>>(shared_secret: &S) -> Result<Aes256Gcm> {
+    let key = derive_key_32(shared_secret, INFO)?;
+    Aes256Gcm::new_from_slice(key.as_ref())
+        .map_err(|e| QraiopError::CryptoError(format!("Invalid AEAD key: {}", e)))
+}
+
+/// Nonce for chunk `index`: the index as 11 big-endian bytes, then 1 on
+/// the final chunk and 0 otherwise.
+fn chunk_nonce(index: u64, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[3..11].copy_from_slice(&index.to_be_bytes());
+    nonce[11] = u8::from(last);
+    nonce
+}
+
+/// Reads until `buffer` is full or the input ends, returning how much was
+/// read.
+fn read_full<R: Read>(reader: &mut R, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Runs `write` against a new temporary file next to `out_path` and
+/// renames it over `out_path` only once `write` succeeds, so a failure
+/// deletes the temporary file and leaves any existing `out_path` as it
+/// was. The temporary file must not exist yet, so a symlink planted in
+/// its place is never followed, and on Unix it is created owner-only,
+/// since it holds decrypted plaintext while it is being written.
+fn write_output<F>(out_path: &Path, write: F) -> Result<()>
+where
+    F: FnOnce(&mut BufWriter<File>) -> Result<()>,
+{
+    let mut temporary = out_path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let temporary = Path::new(&temporary);
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let file = options.open(temporary)?;
+    let mut output = BufWriter::new(file);
+    let result = write(&mut output).and_then(|()| Ok(output.flush()?));
+    drop(output);
+    match result {
+        Ok(()) => fs::rename(temporary, out_path).map_err(|e| {
+            let _ = fs::remove_file(temporary);
+            e.into()
+        }),
+        Err(e) => {
+            let _ = fs::remove_file(temporary);
+            Err(e)
+        }
+    }
+}
+
+/// Encrypts the file at `in_path` to the holder of the ML-KEM-768 secret
+/// key matching `recipient_pk`, writing the result to `out_path`.
+///
+/// Read and write failures surface as `QraiopError::IoError`. The output
+/// only replaces `out_path` once complete, so on failure an existing file
+/// there is left untouched.
+pub fn encrypt_file(
+    recipient_pk: &kyber::PublicKey,
+    in_path: impl AsRef<Path>,
+    out_path: impl AsRef<Path>,
+) -> Result<()> {
+    let mut input = File::open(in_path)?;
+    let (kem_ciphertext, shared_secret) = MlKem768::encapsulate(recipient_pk)?;
+    let cipher = cipher(&shared_secret)?;
+    let mut header = [0u8; HEADER_SIZE];
+    header[0] = FORMAT_VERSION;
+    header[1..].copy_from_slice(kem_ciphertext.as_bytes());
+
+    write_output(out_path.as_ref(), |output| {
+        output.write_all(&header)?;
+        let mut chunk = Zeroizing::new(vec![0u8; CHUNK_SIZE]);
+        for index in 0.. {
+            let read = read_full(&mut input, &mut chunk)?;
+            let last = read < CHUNK_SIZE;
+            let sealed = cipher
+                .encrypt(
+                    Nonce::from_slice(&chunk_nonce(index, last)),
+                    Payload {
+                        msg: &chunk[..read],
+                        aad: &header,
+                    },
+                )
+                .map_err(|_| QraiopError::CryptoError("Encryption failed".to_string()))?;
+            output.write_all(&sealed)?;
+            if last {
+                break;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Decrypts a file produced by [`encrypt_file`] from `in_path` to
+/// `out_path`.
+///
+/// Any modification, reordering or truncation of the encrypted file, or
+/// the wrong secret key, yields `QraiopError::CryptoError`; read and write
+/// failures yield `QraiopError::IoError`. Chunks are written to a
+/// temporary file as they are authenticated, which replaces `out_path`
+/// only after the final chunk has been, so on failure an existing file
+/// there is left untouched.
+pub fn decrypt_file(
+    recipient_sk: &kyber::SecretKey,
+    in_path: impl AsRef<Path>,
+    out_path: impl AsRef<Path>,
+) -> Result<()> {
+    let mut input = File::open(in_path)?;
+    let mut header = [0u8; HEADER_SIZE];
+    if read_full(&mut input, &mut header)? < HEADER_SIZE {
+        return Err(QraiopError::CryptoError(format!(
+            "Encrypted file must be at least {} bytes",
+            HEADER_SIZE + TAG_SIZE
+        )));
+    }
+    if header[0] != FORMAT_VERSION {
+        return Err(QraiopError::SerializationError(format!(
+            "Unsupported encrypted file version {}",
+            header[0]
+        )));
+    }
+    let kem_ciphertext = kyber::Ciphertext::from_bytes(&header[1..])?;
+    let shared_secret = MlKem768::decapsulate(recipient_sk, &kem_ciphertext)?;
+    let cipher = cipher(&shared_secret)?;
+
+    write_output(out_path.as_ref(), |output| {
+        let mut chunk = vec![0u8; CHUNK_SIZE + TAG_SIZE];
+        for index in 0.. {
+            let read = read_full(&mut input, &mut chunk)?;
+            let last = read < chunk.len();
+            let plaintext = cipher
+                .decrypt(
+                    Nonce::from_slice(&chunk_nonce(index, last)),
+                    Payload {
+                        msg: &chunk[..read],
+                        aad: &header,
+                    },
+                )
+                .map(Zeroizing::new)
+                .map_err(|_| QraiopError::CryptoError("Decryption failed".to_string()))?;
+            output.write_all(&plaintext)?;
+            if last {
+                break;
+            }
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("qraiop-file-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_file_roundtrip() {
+        let (pk, sk) = MlKem768::keypair().unwrap();
+        let (plain, sealed, opened) = (temp_path("rt"), temp_path("rt.enc"), temp_path("rt.out"));
+
+        // Empty, exactly one chunk, and a partial trailing chunk
+        for len in [0, CHUNK_SIZE, 2 * CHUNK_SIZE + 100] {
+            let data: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            fs::write(&plain, &data).unwrap();
+            encrypt_file(&pk, &plain, &sealed).unwrap();
+            let chunks = len / CHUNK_SIZE + 1;
+            assert_eq!(
+                fs::metadata(&sealed).unwrap().len() as usize,
+                HEADER_SIZE + len + chunks * TAG_SIZE
+            );
+            decrypt_file(&sk, &sealed, &opened).unwrap();
+            assert_eq!(fs::read(&opened).unwrap(), data);
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&opened).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        for path in [plain, sealed, opened] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_tampered_file_fails() {
+        let (pk, sk) = MlKem768::keypair().unwrap();
+        let (plain, sealed, opened) = (temp_path("tm"), temp_path("tm.enc"), temp_path("tm.out"));
+        fs::write(&plain, vec![7u8; CHUNK_SIZE + 10]).unwrap();
+        encrypt_file(&pk, &plain, &sealed).unwrap();
+        let original = fs::read(&sealed).unwrap();
+
+        let mut flipped = original.clone();
+        flipped[HEADER_SIZE + 5] ^= 1;
+        // Drops the final chunk, leaving a valid-looking full first chunk
+        let truncated = original[..HEADER_SIZE + CHUNK_SIZE + TAG_SIZE].to_vec();
+        for tampered in [flipped.clone(), truncated] {
+            fs::write(&sealed, tampered).unwrap();
+            assert!(matches!(
+                decrypt_file(&sk, &sealed, &opened),
+                Err(QraiopError::CryptoError(_))
+            ));
+            assert!(!opened.exists());
+        }
+
+        // An existing output file survives a failed decryption
+        fs::write(&opened, b"keep me").unwrap();
+        fs::write(&sealed, flipped).unwrap();
+        assert!(decrypt_file(&sk, &sealed, &opened).is_err());
+        assert_eq!(fs::read(&opened).unwrap(), b"keep me");
+        let mut temporary = opened.clone().into_os_string();
+        temporary.push(".tmp");
+        assert!(!Path::new(&temporary).exists());
+        fs::remove_file(&opened).unwrap();
+
+        assert!(matches!(
+            decrypt_file(&sk, temp_path("missing"), &opened),
+            Err(QraiopError::IoError(_))
+        ));
+
+        for path in [plain, sealed] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+}
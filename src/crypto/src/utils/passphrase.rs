@@ -0,0 +1,205 @@
+//! Passphrase-based encryption for secrets at rest
+//!
+//! The passphrase is stretched with Argon2id into an AES-256-GCM key. The
+//! output is self-describing so the cost parameters can be raised later
+//! without breaking existing blobs:
+//!
+//! ```text
+//! version (1) || m_cost (4, BE) || t_cost (4, BE) || p_cost (4, BE)
+//!   || salt (16) || nonce (12) || ciphertext || tag (16)
+//! ```
+//!
+//! Everything before the ciphertext is authenticated as associated data.
+
+use crate::utils::secure_random_into;
+use crate::{QraiopError, Result};
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use argon2::{Algorithm, Argon2, Params, Version};
+use zeroize::Zeroizing;
+
+const FORMAT_VERSION: u8 = 1;
+const SALT_SIZE: usize = 16;
+const NONCE_SIZE: usize = 12;
+const TAG_SIZE: usize = 16;
+const HEADER_SIZE: usize = 1 + 3 * 4 + SALT_SIZE + NONCE_SIZE;
+
+/// Largest Argon2 memory cost (in KiB) accepted when decrypting, 256 MiB, so
+/// a crafted blob cannot make us allocate more than a modest server can
+/// spare. That is over ten times the 19 MiB default.
+const MAX_M_COST: u32 = 1 << 18;
+/// Largest Argon2 pass count accepted when decrypting, so a crafted blob
+/// cannot make us hash for hours.
+const MAX_T_COST: u32 = 16;
+/// Largest Argon2 lane count accepted when decrypting.
+const MAX_P_COST: u32 = 16;
+
+fn derive_cipher(passphrase: &[u8], salt: &[u8], params: Params) -> Result<Aes256Gcm> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase, salt, key.as_mut())
+        .map_err(|e| QraiopError::CryptoError(format!("Key derivation failed: {}", e)))?;
+    Aes256Gcm::new_from_slice(key.as_ref())
+        .map_err(|e| QraiopError::CryptoError(format!("Invalid AEAD key: {}", e)))
+}
+
+/// Encrypts `plaintext` under `passphrase` with the default Argon2id cost.
+/// The salt and nonce come from [`secure_random_into`], so an RNG failure
+/// is `QraiopError::CryptoError` rather than a panic.
+pub fn encrypt(passphrase: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    encrypt_with_params(passphrase, plaintext, Params::default())
+}
+
+pub(crate) fn encrypt_with_params(
+    passphrase: &[u8],
+    plaintext: &[u8],
+    params: Params,
+) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(HEADER_SIZE + plaintext.len() + TAG_SIZE);
+    out.push(FORMAT_VERSION);
+    out.extend_from_slice(&params.m_cost().to_be_bytes());
+    out.extend_from_slice(&params.t_cost().to_be_bytes());
+    out.extend_from_slice(&params.p_cost().to_be_bytes());
+    let mut salt_and_nonce = [0u8; SALT_SIZE + NONCE_SIZE];
+    secure_random_into(&mut salt_and_nonce)?;
+    out.extend_from_slice(&salt_and_nonce);
+
+    let (salt, nonce) = salt_and_nonce.split_at(SALT_SIZE);
+    let cipher = derive_cipher(passphrase, salt, params)?;
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: plaintext,
+                aad: &out,
+            },
+        )
+        .map_err(|_| QraiopError::CryptoError("Encryption failed".to_string()))?;
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Decrypts a blob produced by [`encrypt`]. A wrong passphrase or any
+/// modification of the blob yields `QraiopError::SerializationError`.
+pub fn decrypt(passphrase: &[u8], data: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+    if data.len() < HEADER_SIZE + TAG_SIZE {
+        return Err(QraiopError::SerializationError(format!(
+            "Encrypted data must be at least {} bytes, got {}",
+            HEADER_SIZE + TAG_SIZE,
+            data.len()
+        )));
+    }
+    if data[0] != FORMAT_VERSION {
+        return Err(QraiopError::SerializationError(format!(
+            "Unsupported encrypted data version {}",
+            data[0]
+        )));
+    }
+
+    let (header, ciphertext) = data.split_at(HEADER_SIZE);
+    let cost =
+        |i: usize| u32::from_be_bytes([header[i], header[i + 1], header[i + 2], header[i + 3]]);
+    let (m_cost, t_cost, p_cost) = (cost(1), cost(5), cost(9));
+    if m_cost > MAX_M_COST {
+        return Err(QraiopError::SerializationError(format!(
+            "Argon2 memory cost {} KiB exceeds the {} KiB limit",
+            m_cost, MAX_M_COST
+        )));
+    }
+    if t_cost > MAX_T_COST {
+        return Err(QraiopError::SerializationError(format!(
+            "Argon2 time cost {} exceeds the limit of {}",
+            t_cost, MAX_T_COST
+        )));
+    }
+    if p_cost > MAX_P_COST {
+        return Err(QraiopError::SerializationError(format!(
+            "Argon2 parallelism {} exceeds the limit of {}",
+            p_cost, MAX_P_COST
+        )));
+    }
+    let params = Params::new(m_cost, t_cost, p_cost, None).map_err(|e| {
+        QraiopError::SerializationError(format!("Invalid Argon2 parameters: {}", e))
+    })?;
+
+    let salt = &header[13..13 + SALT_SIZE];
+    let nonce = &header[13 + SALT_SIZE..];
+    let cipher = derive_cipher(passphrase, salt, params)?;
+    cipher
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map(Zeroizing::new)
+        .map_err(|_| {
+            QraiopError::SerializationError(
+                "Decryption failed: wrong passphrase or corrupted data".to_string(),
+            )
+        })
+}
+
+#[cfg(test)]
+pub(crate) fn test_params() -> Params {
+    // Far below the defaults, only so debug-build tests stay fast
+    Params::new(64, 1, 1, None).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let sealed = encrypt_with_params(b"hunter2", b"secret bytes", test_params()).unwrap();
+        assert_eq!(
+            decrypt(b"hunter2", &sealed).unwrap().as_slice(),
+            b"secret bytes"
+        );
+    }
+
+    #[test]
+    fn test_wrong_passphrase_and_tampering() {
+        let sealed = encrypt_with_params(b"hunter2", b"secret bytes", test_params()).unwrap();
+        assert!(matches!(
+            decrypt(b"hunter3", &sealed),
+            Err(QraiopError::SerializationError(_))
+        ));
+
+        // The cost parameters are authenticated along with the ciphertext
+        let mut tampered = sealed.clone();
+        tampered[4] ^= 0x01;
+        assert!(matches!(
+            decrypt(b"hunter2", &tampered),
+            Err(QraiopError::SerializationError(_))
+        ));
+
+        assert!(matches!(
+            decrypt(b"hunter2", &sealed[..HEADER_SIZE]),
+            Err(QraiopError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_excessive_costs_are_rejected() {
+        let sealed = encrypt_with_params(b"hunter2", b"secret bytes", test_params()).unwrap();
+        // m_cost, t_cost and p_cost, each just over its limit
+        for (offset, cost) in [
+            (1, MAX_M_COST + 1),
+            (5, MAX_T_COST + 1),
+            (9, MAX_P_COST + 1),
+        ] {
+            let mut crafted = sealed.clone();
+            crafted[offset..offset + 4].copy_from_slice(&cost.to_be_bytes());
+            assert!(matches!(
+                decrypt(b"hunter2", &crafted),
+                Err(QraiopError::SerializationError(ref m)) if m.contains("exceeds")
+            ));
+        }
+    }
+}
@@ -0,0 +1,278 @@
+//! PKCS#8 (`PrivateKeyInfo`) and X.509 `SubjectPublicKeyInfo` DER encodings
+//!
+//! Follows the IETF LAMPS drafts for ML-KEM and ML-DSA keys: the algorithm
+//! identifier carries the NIST-registered OID with absent parameters, the
+//! public key is the raw FIPS 203/204 encoding in the SPKI bit string, and the
+//! private key uses the `expandedKey` form (an OCTET STRING holding the full
+//! secret key) since the key wrappers do not retain the generation seed.
+
+#[cfg(feature = "dilithium")]
+use crate::pqc::dilithium::{self, MlDsa44, MlDsa65, MlDsa87};
+#[cfg(feature = "kyber")]
+use crate::pqc::kyber::{self, MlKem1024, MlKem512, MlKem768};
+#[cfg(feature = "dilithium")]
+use crate::utils::key_sizes::{ml_dsa_44, ml_dsa_65, ml_dsa_87};
+#[cfg(feature = "kyber")]
+use crate::utils::key_sizes::{ml_kem_1024, ml_kem_512, ml_kem_768};
+use crate::{QraiopError, Result};
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use pkcs8::der::asn1::{BitStringRef, OctetStringRef};
+use pkcs8::der::{Decode, Encode};
+use pkcs8::spki::{AlgorithmIdentifierRef, SubjectPublicKeyInfoRef};
+use pkcs8::{ObjectIdentifier, PrivateKeyInfo};
+use zeroize::Zeroizing;
+
+/// Parameter sets with a registered OID and a DER key encoding.
+pub trait Pkcs8Algorithm {
+    const OID: ObjectIdentifier;
+    const PUBLIC_KEY_SIZE: usize;
+    const SECRET_KEY_SIZE: usize;
+
+    type PublicKey;
+    type SecretKey;
+
+    fn public_key_bytes(public_key: &Self::PublicKey) -> &[u8];
+    fn secret_key_bytes(secret_key: &Self::SecretKey) -> &[u8];
+    fn public_key_from_bytes(bytes: &[u8]) -> Result<Self::PublicKey>;
+    fn secret_key_from_bytes(bytes: &[u8]) -> Result<Self::SecretKey>;
+}
+
+#[cfg(any(feature = "kyber", feature = "dilithium"))]
+macro_rules! pkcs8_algorithm {
+    ($name:ty, $family:ident, $sizes:ident, $oid:literal) => {
+        impl Pkcs8Algorithm for $name {
+            const OID: ObjectIdentifier = ObjectIdentifier::new_unwrap($oid);
+            const PUBLIC_KEY_SIZE: usize = $sizes::PUBLIC_KEY_SIZE;
+            const SECRET_KEY_SIZE: usize = $sizes::SECRET_KEY_SIZE;
+
+            type PublicKey = $family::PublicKey;
+            type SecretKey = $family::SecretKey;
+
+            fn public_key_bytes(public_key: &Self::PublicKey) -> &[u8] {
+                public_key.as_bytes()
+            }
+
+            fn secret_key_bytes(secret_key: &Self::SecretKey) -> &[u8] {
+                secret_key.as_bytes()
+            }
+
+            fn public_key_from_bytes(bytes: &[u8]) -> Result<Self::PublicKey> {
+                $family::PublicKey::from_bytes(bytes)
+            }
+
+            fn secret_key_from_bytes(bytes: &[u8]) -> Result<Self::SecretKey> {
+                $family::SecretKey::from_bytes(bytes)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "kyber")]
+pkcs8_algorithm!(MlKem512, kyber, ml_kem_512, "2.16.840.1.101.3.4.4.1");
+#[cfg(feature = "kyber")]
+pkcs8_algorithm!(MlKem768, kyber, ml_kem_768, "2.16.840.1.101.3.4.4.2");
+#[cfg(feature = "kyber")]
+pkcs8_algorithm!(MlKem1024, kyber, ml_kem_1024, "2.16.840.1.101.3.4.4.3");
+// The ML-DSA OIDs name final FIPS 204, which the backend implements; see
+// `pqc::tests::test_algorithm_oids_match_backends` for the check
+#[cfg(feature = "dilithium")]
+pkcs8_algorithm!(MlDsa44, dilithium, ml_dsa_44, "2.16.840.1.101.3.4.3.17");
+#[cfg(feature = "dilithium")]
+pkcs8_algorithm!(MlDsa65, dilithium, ml_dsa_65, "2.16.840.1.101.3.4.3.18");
+#[cfg(feature = "dilithium")]
+pkcs8_algorithm!(MlDsa87, dilithium, ml_dsa_87, "2.16.840.1.101.3.4.3.19");
+
+fn der_error(e: pkcs8::der::Error) -> QraiopError {
+    QraiopError::SerializationError(e.to_string())
+}
+
+fn algorithm_identifier<A: Pkcs8Algorithm>() -> AlgorithmIdentifierRef<'static> {
+    AlgorithmIdentifierRef {
+        oid: A::OID,
+        parameters: None,
+    }
+}
+
+fn check_algorithm<A: Pkcs8Algorithm>(algorithm: &AlgorithmIdentifierRef<'_>) -> Result<()> {
+    if algorithm.oid != A::OID {
+        return Err(QraiopError::SerializationError(format!(
+            "Algorithm OID mismatch: expected {}, got {}",
+            A::OID,
+            algorithm.oid
+        )));
+    }
+    if algorithm.parameters.is_some() {
+        return Err(QraiopError::SerializationError(
+            "Algorithm parameters must be absent".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn check_size(what: &str, actual: usize, expected: usize) -> Result<()> {
+    if actual != expected {
+        return Err(QraiopError::SerializationError(format!(
+            "{} must be {} bytes, got {}",
+            what, expected, actual
+        )));
+    }
+    Ok(())
+}
+
+/// Encodes a secret key as a DER `PrivateKeyInfo`.
+pub fn to_pkcs8_der<A: Pkcs8Algorithm>(secret_key: &A::SecretKey) -> Result<Zeroizing<Vec<u8>>> {
+    let bytes = A::secret_key_bytes(secret_key);
+    check_size("Secret key", bytes.len(), A::SECRET_KEY_SIZE)?;
+    let expanded_key = Zeroizing::new(
+        OctetStringRef::new(bytes)
+            .and_then(|key| key.to_der())
+            .map_err(der_error)?,
+    );
+    let info = PrivateKeyInfo::new(algorithm_identifier::<A>(), &expanded_key);
+    info.to_der().map(Zeroizing::new).map_err(der_error)
+}
+
+/// Decodes a DER `PrivateKeyInfo`, rejecting keys for any other algorithm.
+pub fn from_pkcs8_der<A: Pkcs8Algorithm>(der: &[u8]) -> Result<A::SecretKey> {
+    let info = PrivateKeyInfo::from_der(der).map_err(der_error)?;
+    check_algorithm::<A>(&info.algorithm)?;
+    let expanded_key = OctetStringRef::from_der(info.private_key).map_err(der_error)?;
+    check_size(
+        "Secret key",
+        expanded_key.as_bytes().len(),
+        A::SECRET_KEY_SIZE,
+    )?;
+    A::secret_key_from_bytes(expanded_key.as_bytes())
+}
+
+/// Encodes a public key as a DER `SubjectPublicKeyInfo`.
+pub fn to_spki_der<A: Pkcs8Algorithm>(public_key: &A::PublicKey) -> Result<Vec<u8>> {
+    let bytes = A::public_key_bytes(public_key);
+    check_size("Public key", bytes.len(), A::PUBLIC_KEY_SIZE)?;
+    let info = SubjectPublicKeyInfoRef {
+        algorithm: algorithm_identifier::<A>(),
+        subject_public_key: BitStringRef::from_bytes(bytes).map_err(der_error)?,
+    };
+    info.to_der().map_err(der_error)
+}
+
+/// Decodes a DER `SubjectPublicKeyInfo`, rejecting keys for any other algorithm.
+pub fn from_spki_der<A: Pkcs8Algorithm>(der: &[u8]) -> Result<A::PublicKey> {
+    let info = SubjectPublicKeyInfoRef::from_der(der).map_err(der_error)?;
+    check_algorithm::<A>(&info.algorithm)?;
+    let bytes = info.subject_public_key.as_bytes().ok_or_else(|| {
+        QraiopError::SerializationError("Public key bit string is not octet-aligned".to_string())
+    })?;
+    check_size("Public key", bytes.len(), A::PUBLIC_KEY_SIZE)?;
+    A::public_key_from_bytes(bytes)
+}
+
+#[cfg(all(test, feature = "kyber", feature = "dilithium"))]
+mod tests {
+    use super::*;
+    use crate::pqc::{DigitalSignature, KeyEncapsulation};
+
+    // SEQUENCE { SEQUENCE { OID 2.16.840.1.101.3.4.4.2 }, BIT STRING (1185 bytes) }
+    const ML_KEM_768_SPKI_HEADER: [u8; 22] = [
+        0x30, 0x82, 0x04, 0xb2, 0x30, 0x0b, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04,
+        0x04, 0x02, 0x03, 0x82, 0x04, 0xa1, 0x00,
+    ];
+
+    // SEQUENCE { INTEGER 0, SEQUENCE { OID 2.16.840.1.101.3.4.4.2 },
+    //            OCTET STRING { OCTET STRING (2400 bytes) } }
+    const ML_KEM_768_PKCS8_HEADER: [u8; 28] = [
+        0x30, 0x82, 0x09, 0x78, 0x02, 0x01, 0x00, 0x30, 0x0b, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01,
+        0x65, 0x03, 0x04, 0x04, 0x02, 0x04, 0x82, 0x09, 0x64, 0x04, 0x82, 0x09, 0x60,
+    ];
+
+    // SEQUENCE { SEQUENCE { OID 2.16.840.1.101.3.4.3.18 }, BIT STRING (1952 bytes) }
+    const ML_DSA_65_SPKI_HEADER: [u8; 22] = [
+        0x30, 0x82, 0x07, 0xb2, 0x30, 0x0b, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04,
+        0x03, 0x12, 0x03, 0x82, 0x07, 0xa1, 0x00,
+    ];
+
+    // SEQUENCE { INTEGER 0, SEQUENCE { OID 2.16.840.1.101.3.4.3.18 },
+    //            OCTET STRING { OCTET STRING (4032 bytes) } }
+    const ML_DSA_65_PKCS8_HEADER: [u8; 28] = [
+        0x30, 0x82, 0x0f, 0xd8, 0x02, 0x01, 0x00, 0x30, 0x0b, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01,
+        0x65, 0x03, 0x04, 0x03, 0x12, 0x04, 0x82, 0x0f, 0xc4, 0x04, 0x82, 0x0f, 0xc0,
+    ];
+
+    #[test]
+    fn test_decode_known_der() {
+        let (pk, sk) = MlKem768::keypair_from_seed(&[0x42; 64]).unwrap();
+
+        let spki = [&ML_KEM_768_SPKI_HEADER[..], pk.as_bytes()].concat();
+        let decoded = from_spki_der::<MlKem768>(&spki).unwrap();
+        assert_eq!(decoded.as_bytes(), pk.as_bytes());
+        assert_eq!(to_spki_der::<MlKem768>(&pk).unwrap(), spki);
+
+        let pkcs8 = [&ML_KEM_768_PKCS8_HEADER[..], sk.as_bytes()].concat();
+        let decoded = from_pkcs8_der::<MlKem768>(&pkcs8).unwrap();
+        assert_eq!(decoded.as_bytes(), sk.as_bytes());
+        assert_eq!(*to_pkcs8_der::<MlKem768>(&sk).unwrap(), pkcs8);
+    }
+
+    #[test]
+    fn test_decode_known_ml_dsa_der() {
+        let (pk, sk) = MlDsa65::keypair().unwrap();
+
+        let spki = [&ML_DSA_65_SPKI_HEADER[..], pk.as_bytes()].concat();
+        let decoded = from_spki_der::<MlDsa65>(&spki).unwrap();
+        assert_eq!(decoded.as_bytes(), pk.as_bytes());
+        assert_eq!(to_spki_der::<MlDsa65>(&pk).unwrap(), spki);
+
+        let pkcs8 = [&ML_DSA_65_PKCS8_HEADER[..], sk.as_bytes()].concat();
+        let decoded = from_pkcs8_der::<MlDsa65>(&pkcs8).unwrap();
+        assert_eq!(decoded.as_bytes(), sk.as_bytes());
+        assert_eq!(*to_pkcs8_der::<MlDsa65>(&sk).unwrap(), pkcs8);
+    }
+
+    #[test]
+    fn test_ml_dsa_roundtrip() {
+        let (pk, sk) = MlDsa65::keypair().unwrap();
+        let spki = to_spki_der::<MlDsa65>(&pk).unwrap();
+        let pkcs8 = to_pkcs8_der::<MlDsa65>(&sk).unwrap();
+
+        let pk = from_spki_der::<MlDsa65>(&spki).unwrap();
+        let sk = from_pkcs8_der::<MlDsa65>(&pkcs8).unwrap();
+        let signature = MlDsa65::sign(&sk, b"message").unwrap();
+        assert!(MlDsa65::verify(&pk, b"message", &signature).unwrap());
+    }
+
+    #[test]
+    fn test_oids_match_algorithm_oid() {
+        fn oid<A: Pkcs8Algorithm>() -> String {
+            A::OID.to_string()
+        }
+        assert_eq!(MlKem512::algorithm_oid(), Some(&*oid::<MlKem512>()));
+        assert_eq!(MlKem768::algorithm_oid(), Some(&*oid::<MlKem768>()));
+        assert_eq!(MlKem1024::algorithm_oid(), Some(&*oid::<MlKem1024>()));
+        assert_eq!(MlDsa44::algorithm_oid(), Some(&*oid::<MlDsa44>()));
+        assert_eq!(MlDsa65::algorithm_oid(), Some(&*oid::<MlDsa65>()));
+        assert_eq!(MlDsa87::algorithm_oid(), Some(&*oid::<MlDsa87>()));
+    }
+
+    #[test]
+    fn test_oid_mismatch() {
+        let (pk, sk) = MlKem512::keypair().unwrap();
+        let spki = to_spki_der::<MlKem512>(&pk).unwrap();
+        let pkcs8 = to_pkcs8_der::<MlKem512>(&sk).unwrap();
+
+        assert!(matches!(
+            from_spki_der::<MlKem768>(&spki),
+            Err(QraiopError::SerializationError(_))
+        ));
+        assert!(matches!(
+            from_pkcs8_der::<MlDsa44>(&pkcs8),
+            Err(QraiopError::SerializationError(_))
+        ));
+        // A 512 key is a valid ML-KEM key, but not for the 768 parameter set
+        assert!(matches!(
+            to_spki_der::<MlKem768>(&pk),
+            Err(QraiopError::SerializationError(_))
+        ));
+    }
+}
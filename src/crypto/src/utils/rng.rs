@@ -0,0 +1,138 @@
+//! Where key generation, [`super::secure_random`] and the encapsulations
+//! that sample their own randomness get it from
+//!
+//! Normally that is `OsRng`. With the `test_rng` feature it is instead
+//! ChaCha20 from a fixed seed, on a separate stream per thread so that
+//! keys generated on different threads, e.g. by
+//! [`crate::pqc::generate_keypairs_parallel`], do not repeat one another.
+//! Threads are numbered in the order they first draw, which depends on
+//! scheduling; [`reset_test_rng`] moves the calling thread to stream 0, so
+//! a test that calls it first gets the same keys on every run.
+//!
+//! ML-KEM's `encapsulate` and SLH-DSA signing leave the randomness to the
+//! backend, which reads the OS directly, so they stay random either way.
+//!
+//! `test_rng` makes every key predictable. The crate refuses to build with
+//! it unless debug assertions are on, so it cannot end up in a release
+//! build.
+
+use rand::{CryptoRng, RngCore};
+
+/// `OsRng`, or this thread's fixed-seed stream under `test_rng`.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SystemRng;
+
+#[cfg(not(feature = "test_rng"))]
+impl RngCore for SystemRng {
+    fn next_u32(&mut self) -> u32 {
+        rand::rngs::OsRng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand::rngs::OsRng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand::rngs::OsRng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        rand::rngs::OsRng.try_fill_bytes(dest)
+    }
+}
+
+#[cfg(feature = "test_rng")]
+mod test_rng {
+    use core::cell::RefCell;
+    use core::sync::atomic::{AtomicU64, Ordering};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    const SEED: [u8; 32] = *b"QRAIOP test_rng fixed seed v1...";
+
+    /// Next stream to hand out; 0 is kept for [`reset_test_rng`].
+    static NEXT_STREAM: AtomicU64 = AtomicU64::new(1);
+
+    std::thread_local! {
+        pub(super) static STREAM: RefCell<ChaCha20Rng> =
+            RefCell::new(stream(NEXT_STREAM.fetch_add(1, Ordering::Relaxed)));
+    }
+
+    fn stream(number: u64) -> ChaCha20Rng {
+        let mut rng = ChaCha20Rng::from_seed(SEED);
+        rng.set_stream(number);
+        rng
+    }
+
+    /// Restarts this thread on stream 0 of the fixed seed, so the keys
+    /// generated on it next are the same on every run. Other threads keep
+    /// their own streams, unless they call this too.
+    pub fn reset_test_rng() {
+        STREAM.with(|rng| *rng.borrow_mut() = stream(0));
+    }
+}
+
+#[cfg(feature = "test_rng")]
+pub use test_rng::reset_test_rng;
+
+#[cfg(feature = "test_rng")]
+impl RngCore for SystemRng {
+    fn next_u32(&mut self) -> u32 {
+        test_rng::STREAM.with(|stream| stream.borrow_mut().next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        test_rng::STREAM.with(|stream| stream.borrow_mut().next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        test_rng::STREAM.with(|stream| stream.borrow_mut().fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        test_rng::STREAM.with(|stream| stream.borrow_mut().try_fill_bytes(dest))
+    }
+}
+
+impl CryptoRng for SystemRng {}
+
+#[cfg(all(test, feature = "test_rng"))]
+mod tests {
+    use super::*;
+    use crate::utils::secure_random;
+
+    #[test]
+    fn test_generations_repeat_after_reset() {
+        reset_test_rng();
+        let first = secure_random(64).unwrap();
+        assert_ne!(secure_random(64).unwrap(), first);
+        reset_test_rng();
+        assert_eq!(secure_random(64).unwrap(), first);
+    }
+
+    #[cfg(feature = "kyber")]
+    #[test]
+    fn test_keypairs_repeat_after_reset() {
+        use crate::pqc::kyber::MlKem768;
+        use crate::pqc::KeyEncapsulation;
+
+        reset_test_rng();
+        let (pk1, sk1) = MlKem768::keypair().unwrap();
+        reset_test_rng();
+        let (pk2, sk2) = MlKem768::keypair().unwrap();
+        assert_eq!(pk1, pk2);
+        assert_eq!(sk1.as_bytes(), sk2.as_bytes());
+
+        // Another thread has its own stream, until it resets too
+        let (pk3, pk4) = std::thread::spawn(|| {
+            let (pk3, _) = MlKem768::keypair().unwrap();
+            reset_test_rng();
+            let (pk4, _) = MlKem768::keypair().unwrap();
+            (pk3, pk4)
+        })
+        .join()
+        .unwrap();
+        assert_ne!(pk3, pk1);
+        assert_eq!(pk4, pk1);
+    }
+}
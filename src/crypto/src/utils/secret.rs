@@ -0,0 +1,165 @@
+//! Heap storage for secrets: keys, shared secrets and anything else that
+//! must not outlive its use
+//!
+//! Every secret type in the crate wraps [`Secret`], which gives them the same
+//! handling: the bytes are wiped when the value is dropped, `Debug` prints
+//! only their length, equality is checked in constant time, and reading
+//! them takes an explicit [`Secret::expose_secret`]. A wrapper that derives
+//! `Debug` prints as `SecretKey([REDACTED; 32])`.
+//!
+//! With the `locked` feature on Unix, the buffer is `mlock`ed when it is
+//! created so the kernel never writes it to swap, and `munlock`ed on drop
+//! once it has been wiped. Locking is best effort: if it fails, typically
+//! because `RLIMIT_MEMLOCK` is exhausted, the secret still works and a
+//! warning is logged.
+//!
+//! `mlock` works on whole pages, and unlocking one buffer unlocks every page
+//! it touches, including parts shared with neighbouring allocations. This
+//! keeps secrets out of swap for their lifetime in the common case; it is
+//! not a guarantee.
+
+use alloc::vec::Vec;
+use core::fmt;
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub(crate) struct Secret(Vec<u8>);
+
+impl Secret {
+    /// Takes ownership of `bytes`, including any spare capacity, which is
+    /// wiped along with the rest.
+    pub(crate) fn new(bytes: Vec<u8>) -> Self {
+        lock(bytes.as_ptr(), bytes.capacity());
+        Self(bytes)
+    }
+
+    pub(crate) fn expose_secret(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl From<&[u8]> for Secret {
+    fn from(bytes: &[u8]) -> Self {
+        Self::new(bytes.to_vec())
+    }
+}
+
+impl Clone for Secret {
+    fn clone(&self) -> Self {
+        Self::new(self.0.clone())
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[REDACTED; {}]", self.0.len())
+    }
+}
+
+/// Byte equality evaluated in constant time, so still an equivalence
+/// relation. There is deliberately no `Hash`.
+impl PartialEq for Secret {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.ct_eq(&other.0).into()
+    }
+}
+
+impl Eq for Secret {}
+
+impl Zeroize for Secret {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        // Wipe while still locked; `zeroize` keeps the allocation, so the
+        // capacity still covers the locked range.
+        self.0.zeroize();
+        unlock(self.0.as_ptr(), self.0.capacity());
+    }
+}
+
+impl ZeroizeOnDrop for Secret {}
+
+#[cfg(feature = "serde_secret")]
+impl serde::Serialize for Secret {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde_secret")]
+impl<'de> serde::Deserialize<'de> for Secret {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(all(unix, feature = "locked"))]
+fn lock(ptr: *const u8, len: usize) {
+    if len == 0 {
+        return;
+    }
+    // SAFETY: `ptr..ptr + len` is a live allocation owned by the caller.
+    if unsafe { libc::mlock(ptr.cast(), len) } != 0 {
+        log::warn!(
+            "mlock of a {}-byte secret failed; it may be written to swap",
+            len
+        );
+    }
+}
+
+#[cfg(all(unix, feature = "locked"))]
+fn unlock(ptr: *const u8, len: usize) {
+    if len != 0 {
+        // SAFETY: as for `lock`; unlocking a range that was never locked,
+        // because `mlock` failed, is harmless.
+        unsafe { libc::munlock(ptr.cast(), len) };
+    }
+}
+
+#[cfg(not(all(unix, feature = "locked")))]
+fn lock(_ptr: *const u8, _len: usize) {}
+
+#[cfg(not(all(unix, feature = "locked")))]
+fn unlock(_ptr: *const u8, _len: usize) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::vec;
+
+    #[test]
+    fn test_debug_is_redacted() {
+        let secret = Secret::new(vec![0x42; 32]);
+        assert_eq!(format!("{:?}", secret), "[REDACTED; 32]");
+        assert_eq!(secret.expose_secret(), &[0x42; 32]);
+    }
+
+    #[test]
+    fn test_eq_compares_bytes() {
+        let secret = Secret::from(&[1u8, 2, 3][..]);
+        assert_eq!(secret, secret.clone());
+        assert_ne!(secret, Secret::from(&[1u8, 2, 4][..]));
+        assert_ne!(secret, Secret::from(&[1u8, 2][..]));
+    }
+
+    #[test]
+    fn test_zeroize_clears_bytes() {
+        let mut secret = Secret::new(vec![0xff; 16]);
+        secret.zeroize();
+        assert!(secret.expose_secret().iter().all(|&b| b == 0));
+    }
+}
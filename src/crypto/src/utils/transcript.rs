@@ -0,0 +1,122 @@
+//! Transcript hashing for protocols built on the KEMs and signatures
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
+use zeroize::Zeroizing;
+
+const MESSAGE: u8 = 0x01;
+const CHALLENGE: u8 = 0x02;
+/// Label of the message that opens every transcript with its protocol name.
+const PROTOCOL_LABEL: &[u8] = b"QRAIOP-transcript-v1";
+
+/// Running hash of a protocol transcript, with an API modelled on Merlin.
+///
+/// Labelled messages are absorbed into a SHAKE256 state and labelled
+/// challenges squeezed out of it. Every operation is framed before it is
+/// absorbed:
+///
+/// ```text
+/// message:   0x01 || len(label) (4, BE) || label || len(data) (8, BE) || data
+/// challenge: 0x02 || len(label) (4, BE) || label || out_len (8, BE)
+/// ```
+///
+/// so no two different sequences of operations absorb the same bytes. A
+/// challenge is absorbed before its output is squeezed from a copy of the
+/// state, so later challenges depend on earlier ones.
+#[derive(Clone)]
+pub struct Transcript {
+    state: Shake256,
+}
+
+impl Transcript {
+    /// Starts a transcript for the protocol named `protocol_label`, so
+    /// transcripts of different protocols never agree.
+    pub fn new(protocol_label: &[u8]) -> Self {
+        let mut transcript = Transcript {
+            state: Shake256::default(),
+        };
+        transcript.append_message(PROTOCOL_LABEL, protocol_label);
+        transcript
+    }
+
+    /// Absorbs `data` under `label`, e.g. a public key or ciphertext.
+    pub fn append_message(&mut self, label: &[u8], data: &[u8]) {
+        self.frame(MESSAGE, label);
+        self.state.update(&(data.len() as u64).to_be_bytes());
+        self.state.update(data);
+    }
+
+    /// Derives `out_len` bytes bound to everything appended so far, and
+    /// records the challenge in the transcript.
+    pub fn challenge(&mut self, label: &[u8], out_len: usize) -> Zeroizing<Vec<u8>> {
+        self.frame(CHALLENGE, label);
+        self.state.update(&(out_len as u64).to_be_bytes());
+        let mut out = Zeroizing::new(vec![0u8; out_len]);
+        self.state.clone().finalize_xof().read(&mut out);
+        out
+    }
+
+    fn frame(&mut self, operation: u8, label: &[u8]) {
+        self.state.update(&[operation]);
+        self.state.update(&(label.len() as u32).to_be_bytes());
+        self.state.update(label);
+    }
+}
+
+// The hash state depends on every message, so it is not shown.
+impl fmt::Debug for Transcript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Transcript { .. }")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn challenge_after(messages: &[(&[u8], &[u8])]) -> Zeroizing<Vec<u8>> {
+        let mut transcript = Transcript::new(b"test protocol");
+        for (label, data) in messages {
+            transcript.append_message(label, data);
+        }
+        transcript.challenge(b"key", 32)
+    }
+
+    #[test]
+    fn test_identical_transcripts_agree() {
+        let messages: &[(&[u8], &[u8])] = &[(b"pk", b"alice"), (b"ct", b"bob")];
+        assert_eq!(challenge_after(messages), challenge_after(messages));
+        assert_eq!(challenge_after(messages).len(), 32);
+    }
+
+    #[test]
+    fn test_order_and_labels_matter() {
+        let base = challenge_after(&[(b"pk", b"alice"), (b"ct", b"bob")]);
+        assert_ne!(base, challenge_after(&[(b"ct", b"bob"), (b"pk", b"alice")]));
+        assert_ne!(base, challenge_after(&[(b"pk", b"bob"), (b"ct", b"alice")]));
+        assert_ne!(base, challenge_after(&[(b"PK", b"alice"), (b"ct", b"bob")]));
+        // Moving bytes between label and data is not a collision
+        assert_ne!(base, challenge_after(&[(b"pka", b"lice"), (b"ct", b"bob")]));
+
+        let mut other = Transcript::new(b"other protocol");
+        other.append_message(b"pk", b"alice");
+        other.append_message(b"ct", b"bob");
+        assert_ne!(base, other.challenge(b"key", 32));
+    }
+
+    #[test]
+    fn test_challenges_advance_the_transcript() {
+        let mut transcript = Transcript::new(b"test protocol");
+        let first = transcript.challenge(b"key", 32);
+        let second = transcript.challenge(b"key", 32);
+        assert_ne!(first, second);
+
+        // The output length is bound, so a shorter challenge is no prefix
+        let mut a = Transcript::new(b"test protocol");
+        let mut b = a.clone();
+        assert_ne!(a.challenge(b"key", 16)[..], b.challenge(b"key", 32)[..16]);
+    }
+}
@@ -0,0 +1,256 @@
+//! Self-describing wire encoding for public values
+//!
+//! Unlike bincode framing, this format is fixed and simple to implement in
+//! any language:
+//!
+//! ```text
+//! tag (1) || length (4, big-endian) || raw bytes (length)
+//! ```
+//!
+//! The tag identifies the algorithm (see [`tag`]); the raw bytes are the
+//! standard encoding of the public key, ciphertext or signature. Decoding
+//! checks the tag, that the length matches the algorithm's size for that
+//! kind of value, and that no bytes are missing or left over.
+
+use crate::utils::key_sizes::{
+    ml_dsa_44, ml_dsa_65, ml_dsa_87, ml_kem_1024, ml_kem_512, ml_kem_768, slh_dsa_128s,
+    slh_dsa_192s, slh_dsa_256s,
+};
+use crate::{QraiopError, Result, SupportedAlgorithm};
+use alloc::format;
+use alloc::vec::Vec;
+
+const HEADER_SIZE: usize = 1 + 4;
+
+/// Wire tag of `algorithm`. Tags are part of the format and never reused.
+pub fn tag(algorithm: SupportedAlgorithm) -> u8 {
+    match algorithm {
+        SupportedAlgorithm::MlKem512 => 0x01,
+        SupportedAlgorithm::MlKem768 => 0x02,
+        SupportedAlgorithm::MlKem1024 => 0x03,
+        SupportedAlgorithm::MlDsa44 => 0x11,
+        SupportedAlgorithm::MlDsa65 => 0x12,
+        SupportedAlgorithm::MlDsa87 => 0x13,
+        SupportedAlgorithm::SlhDsa128s => 0x21,
+        SupportedAlgorithm::SlhDsa192s => 0x22,
+        SupportedAlgorithm::SlhDsa256s => 0x23,
+    }
+}
+
+fn from_tag(tag_byte: u8) -> Result<SupportedAlgorithm> {
+    SupportedAlgorithm::ALL
+        .into_iter()
+        .find(|&algorithm| tag(algorithm) == tag_byte)
+        .ok_or_else(|| {
+            QraiopError::SerializationError(format!("Unknown algorithm tag 0x{:02x}", tag_byte))
+        })
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    PublicKey,
+    Ciphertext,
+    Signature,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::PublicKey => "public key",
+            Kind::Ciphertext => "ciphertext",
+            Kind::Signature => "signature",
+        }
+    }
+
+    /// Encoded size of this kind of value for `algorithm`, or `None` if the
+    /// algorithm has no such value (e.g. a signature for a KEM).
+    fn size(self, algorithm: SupportedAlgorithm) -> Option<usize> {
+        use SupportedAlgorithm::*;
+
+        match (self, algorithm) {
+            (Kind::PublicKey, MlKem512) => Some(ml_kem_512::PUBLIC_KEY_SIZE),
+            (Kind::PublicKey, MlKem768) => Some(ml_kem_768::PUBLIC_KEY_SIZE),
+            (Kind::PublicKey, MlKem1024) => Some(ml_kem_1024::PUBLIC_KEY_SIZE),
+            (Kind::PublicKey, MlDsa44) => Some(ml_dsa_44::PUBLIC_KEY_SIZE),
+            (Kind::PublicKey, MlDsa65) => Some(ml_dsa_65::PUBLIC_KEY_SIZE),
+            (Kind::PublicKey, MlDsa87) => Some(ml_dsa_87::PUBLIC_KEY_SIZE),
+            (Kind::PublicKey, SlhDsa128s) => Some(slh_dsa_128s::PUBLIC_KEY_SIZE),
+            (Kind::PublicKey, SlhDsa192s) => Some(slh_dsa_192s::PUBLIC_KEY_SIZE),
+            (Kind::PublicKey, SlhDsa256s) => Some(slh_dsa_256s::PUBLIC_KEY_SIZE),
+            (Kind::Ciphertext, MlKem512) => Some(ml_kem_512::CIPHERTEXT_SIZE),
+            (Kind::Ciphertext, MlKem768) => Some(ml_kem_768::CIPHERTEXT_SIZE),
+            (Kind::Ciphertext, MlKem1024) => Some(ml_kem_1024::CIPHERTEXT_SIZE),
+            (Kind::Signature, MlDsa44) => Some(ml_dsa_44::SIGNATURE_SIZE),
+            (Kind::Signature, MlDsa65) => Some(ml_dsa_65::SIGNATURE_SIZE),
+            (Kind::Signature, MlDsa87) => Some(ml_dsa_87::SIGNATURE_SIZE),
+            (Kind::Signature, SlhDsa128s) => Some(slh_dsa_128s::SIGNATURE_SIZE),
+            (Kind::Signature, SlhDsa192s) => Some(slh_dsa_192s::SIGNATURE_SIZE),
+            (Kind::Signature, SlhDsa256s) => Some(slh_dsa_256s::SIGNATURE_SIZE),
+            (Kind::Ciphertext | Kind::Signature, _) => None,
+        }
+    }
+
+    fn check_size(self, algorithm: SupportedAlgorithm, actual: usize) -> Result<()> {
+        match self.size(algorithm) {
+            Some(expected) if expected == actual => Ok(()),
+            Some(expected) => Err(QraiopError::SerializationError(format!(
+                "{} {} must be {} bytes, got {}",
+                algorithm,
+                self.name(),
+                expected,
+                actual
+            ))),
+            None => Err(QraiopError::SerializationError(format!(
+                "{} has no {}",
+                algorithm,
+                self.name()
+            ))),
+        }
+    }
+}
+
+fn encode(kind: Kind, algorithm: SupportedAlgorithm, bytes: &[u8]) -> Result<Vec<u8>> {
+    kind.check_size(algorithm, bytes.len())?;
+    let mut out = Vec::with_capacity(HEADER_SIZE + bytes.len());
+    out.push(tag(algorithm));
+    out.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    out.extend_from_slice(bytes);
+    Ok(out)
+}
+
+fn decode(kind: Kind, data: &[u8]) -> Result<(SupportedAlgorithm, &[u8])> {
+    let Some((header, body)) = data.split_first_chunk::<HEADER_SIZE>() else {
+        return Err(QraiopError::SerializationError(format!(
+            "Encoded {} must be at least {} bytes, got {}",
+            kind.name(),
+            HEADER_SIZE,
+            data.len()
+        )));
+    };
+    let algorithm = from_tag(header[0])?;
+    let length = u32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize;
+    if length != body.len() {
+        return Err(QraiopError::SerializationError(format!(
+            "Encoded {} declares {} bytes but carries {}",
+            kind.name(),
+            length,
+            body.len()
+        )));
+    }
+    kind.check_size(algorithm, body.len())?;
+    Ok((algorithm, body))
+}
+
+/// Encodes the raw bytes of a public key for `algorithm`, rejecting keys of
+/// the wrong size.
+pub fn encode_public_key(algorithm: SupportedAlgorithm, public_key: &[u8]) -> Result<Vec<u8>> {
+    encode(Kind::PublicKey, algorithm, public_key)
+}
+
+/// Decodes a public key, returning its algorithm and raw bytes.
+pub fn decode_public_key(data: &[u8]) -> Result<(SupportedAlgorithm, &[u8])> {
+    decode(Kind::PublicKey, data)
+}
+
+/// Encodes the raw bytes of a KEM ciphertext for `algorithm`.
+pub fn encode_ciphertext(algorithm: SupportedAlgorithm, ciphertext: &[u8]) -> Result<Vec<u8>> {
+    encode(Kind::Ciphertext, algorithm, ciphertext)
+}
+
+/// Decodes a KEM ciphertext, returning its algorithm and raw bytes.
+pub fn decode_ciphertext(data: &[u8]) -> Result<(SupportedAlgorithm, &[u8])> {
+    decode(Kind::Ciphertext, data)
+}
+
+/// Encodes the raw bytes of a signature for `algorithm`.
+pub fn encode_signature(algorithm: SupportedAlgorithm, signature: &[u8]) -> Result<Vec<u8>> {
+    encode(Kind::Signature, algorithm, signature)
+}
+
+/// Decodes a signature, returning its algorithm and raw bytes.
+pub fn decode_signature(data: &[u8]) -> Result<(SupportedAlgorithm, &[u8])> {
+    decode(Kind::Signature, data)
+}
+
+#[cfg(all(test, feature = "kyber", feature = "dilithium"))]
+mod tests {
+    use super::*;
+    use crate::pqc::dilithium::{self, MlDsa44};
+    use crate::pqc::kyber::{self, MlKem768};
+    use crate::pqc::{DigitalSignature, KeyEncapsulation};
+
+    #[test]
+    fn test_kem_roundtrip() {
+        let (pk, _) = MlKem768::keypair().unwrap();
+        let (ct, _) = MlKem768::encapsulate(&pk).unwrap();
+
+        let encoded = encode_public_key(SupportedAlgorithm::MlKem768, pk.as_bytes()).unwrap();
+        assert_eq!(&encoded[..5], &[0x02, 0x00, 0x00, 0x04, 0xa0]);
+        let (algorithm, bytes) = decode_public_key(&encoded).unwrap();
+        assert_eq!(algorithm, SupportedAlgorithm::MlKem768);
+        assert_eq!(kyber::PublicKey::from_bytes(bytes).unwrap(), pk);
+
+        let encoded = encode_ciphertext(SupportedAlgorithm::MlKem768, ct.as_bytes()).unwrap();
+        let (algorithm, bytes) = decode_ciphertext(&encoded).unwrap();
+        assert_eq!(algorithm, SupportedAlgorithm::MlKem768);
+        assert_eq!(bytes, ct.as_bytes());
+    }
+
+    #[test]
+    fn test_signature_roundtrip() {
+        let (pk, sk) = MlDsa44::keypair().unwrap();
+        let signature = MlDsa44::sign(&sk, b"message").unwrap();
+
+        let encoded = encode_public_key(SupportedAlgorithm::MlDsa44, pk.as_bytes()).unwrap();
+        let (_, pk_bytes) = decode_public_key(&encoded).unwrap();
+        let encoded = encode_signature(SupportedAlgorithm::MlDsa44, signature.as_bytes()).unwrap();
+        let (algorithm, signature_bytes) = decode_signature(&encoded).unwrap();
+        assert_eq!(algorithm, SupportedAlgorithm::MlDsa44);
+
+        let pk = dilithium::PublicKey::from_bytes(pk_bytes).unwrap();
+        let signature = dilithium::Signature::from_bytes(signature_bytes).unwrap();
+        assert!(MlDsa44::verify(&pk, b"message", &signature).unwrap());
+    }
+
+    #[test]
+    fn test_truncated_input_is_an_error() {
+        let (pk, _) = MlKem768::keypair().unwrap();
+        let encoded = encode_public_key(SupportedAlgorithm::MlKem768, pk.as_bytes()).unwrap();
+
+        for len in [0, 1, 4, 5, encoded.len() - 1] {
+            assert!(matches!(
+                decode_public_key(&encoded[..len]),
+                Err(QraiopError::SerializationError(_))
+            ));
+        }
+        assert!(matches!(
+            decode_public_key(&[encoded.as_slice(), &[0]].concat()),
+            Err(QraiopError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_mismatched_values_are_rejected() {
+        let (pk, _) = MlKem768::keypair().unwrap();
+        assert!(encode_public_key(SupportedAlgorithm::MlKem512, pk.as_bytes()).is_err());
+        assert!(encode_signature(SupportedAlgorithm::MlKem768, &[0; 32]).is_err());
+        assert!(encode_ciphertext(SupportedAlgorithm::MlDsa65, &[0; 32]).is_err());
+
+        // A public key frame is not a ciphertext frame, and unknown tags fail
+        let mut encoded = encode_public_key(SupportedAlgorithm::MlKem768, pk.as_bytes()).unwrap();
+        assert!(decode_ciphertext(&encoded).is_err());
+        encoded[0] = 0xff;
+        assert!(matches!(
+            decode_public_key(&encoded),
+            Err(QraiopError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_tags_are_unique() {
+        let mut tags: Vec<u8> = SupportedAlgorithm::ALL.into_iter().map(tag).collect();
+        tags.sort_unstable();
+        tags.dedup();
+        assert_eq!(tags.len(), SupportedAlgorithm::ALL.len());
+    }
+}
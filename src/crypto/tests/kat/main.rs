@@ -0,0 +1,343 @@
+//! Known Answer Tests against the NIST ACVP vectors for ML-KEM and ML-DSA
+//!
+//! The vector files are not checked in. Download the "internal projection"
+//! files from the ACVP-Server repository
+//! (`gen-val/json-files/<name>/internalProjection.json`) and save them as
+//!
+//! ```text
+//! tests/kat/vectors/ML-KEM-keyGen-FIPS203.json
+//! tests/kat/vectors/ML-KEM-encapDecap-FIPS203.json
+//! tests/kat/vectors/ML-DSA-keyGen-FIPS204.json
+//! tests/kat/vectors/ML-DSA-sigGen-FIPS204.json
+//! tests/kat/vectors/ML-DSA-sigVer-FIPS204.json
+//! ```
+//!
+//! or point `QRAIOP_ACVP_DIR` at a directory holding them. Each test is
+//! skipped, with a note on stderr, when its file is missing, unless
+//! `QRAIOP_ACVP_REQUIRED=1` is set, as in CI, which makes that a failure.
+//!
+//! ML-DSA signing is always randomized here, so sigGen cases cannot be
+//! reproduced byte for byte. Each one instead checks that the expected
+//! signature verifies and that a fresh signature by the case's key does.
+//! Only pure signing through the external interface is offered by this
+//! crate; cases for the internal interface, HashML-DSA or an external `mu`
+//! are counted and skipped.
+
+use qraiop_crypto::pqc::dilithium::{self, MlDsa44, MlDsa65, MlDsa87};
+use qraiop_crypto::pqc::kyber::{Ciphertext, MlKem1024, MlKem512, MlKem768, PublicKey, SecretKey};
+use qraiop_crypto::{KemAlgorithm, Result, SupportedAlgorithm};
+use serde_json::Value;
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+fn load_vectors(name: &str) -> Option<Value> {
+    let dir = std::env::var_os("QRAIOP_ACVP_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/kat/vectors"));
+    let path = dir.join(format!("{}.json", name));
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        if std::env::var_os("QRAIOP_ACVP_REQUIRED").is_some_and(|value| value == "1") {
+            panic!("{}: {} not found", name, path.display());
+        }
+        eprintln!("skipping {}: {} not found", name, path.display());
+        return None;
+    };
+    Some(serde_json::from_str(&contents).expect("vector file is not valid JSON"))
+}
+
+/// Looks `name` up on the test case first, then on its group, since ACVP
+/// hoists values shared by every case (such as `dk` for decapsulation) to
+/// the group.
+fn hex_field(test: &Value, group: &Value, name: &str) -> Vec<u8> {
+    let value = test
+        .get(name)
+        .or_else(|| group.get(name))
+        .and_then(Value::as_str)
+        .unwrap_or_else(|| panic!("tcId {}: missing field {}", test["tcId"], name));
+    hex::decode(value)
+        .unwrap_or_else(|e| panic!("tcId {}: bad hex in {}: {}", test["tcId"], name, e))
+}
+
+fn groups<A: FromStr>(vectors: &Value) -> impl Iterator<Item = (&Value, A)>
+where
+    A::Err: Debug,
+{
+    vectors["testGroups"]
+        .as_array()
+        .expect("testGroups must be an array")
+        .iter()
+        .map(|group| {
+            let parameter_set = group["parameterSet"].as_str().expect("parameterSet");
+            (group, parameter_set.parse().expect("unknown parameterSet"))
+        })
+}
+
+fn tests(group: &Value) -> &Vec<Value> {
+    group["tests"].as_array().expect("tests must be an array")
+}
+
+fn keypair_from_seed(algorithm: KemAlgorithm, seed: &[u8; 64]) -> Result<(PublicKey, SecretKey)> {
+    match algorithm {
+        KemAlgorithm::MlKem512 => MlKem512::keypair_from_seed(seed),
+        KemAlgorithm::MlKem768 => MlKem768::keypair_from_seed(seed),
+        KemAlgorithm::MlKem1024 => MlKem1024::keypair_from_seed(seed),
+    }
+}
+
+#[test]
+fn ml_kem_key_generation() {
+    let Some(vectors) = load_vectors("ML-KEM-keyGen-FIPS203") else {
+        return;
+    };
+
+    let mut count = 0;
+    for (group, algorithm) in groups(&vectors) {
+        for test in tests(group) {
+            let seed: [u8; 64] = [hex_field(test, group, "d"), hex_field(test, group, "z")]
+                .concat()
+                .try_into()
+                .expect("d and z must be 32 bytes each");
+            let (pk, sk) = keypair_from_seed(algorithm, &seed).unwrap();
+            assert_eq!(
+                pk.as_bytes(),
+                hex_field(test, group, "ek"),
+                "tcId {}: ek",
+                test["tcId"]
+            );
+            assert_eq!(
+                sk.as_bytes(),
+                hex_field(test, group, "dk"),
+                "tcId {}: dk",
+                test["tcId"]
+            );
+            count += 1;
+        }
+    }
+    assert!(count > 0, "no keyGen test cases found");
+}
+
+#[test]
+fn ml_dsa_key_generation() {
+    let Some(vectors) = load_vectors("ML-DSA-keyGen-FIPS204") else {
+        return;
+    };
+
+    let mut count = 0;
+    for (group, algorithm) in groups(&vectors) {
+        for test in tests(group) {
+            let seed: [u8; 32] = hex_field(test, group, "seed")
+                .try_into()
+                .expect("seed must be 32 bytes");
+            let (pk, sk): (dilithium::PublicKey, dilithium::SecretKey) = match algorithm {
+                SupportedAlgorithm::MlDsa44 => MlDsa44::keypair_from_seed(&seed),
+                SupportedAlgorithm::MlDsa65 => MlDsa65::keypair_from_seed(&seed),
+                SupportedAlgorithm::MlDsa87 => MlDsa87::keypair_from_seed(&seed),
+                other => panic!("not an ML-DSA parameter set: {}", other),
+            }
+            .unwrap();
+            assert_eq!(
+                pk.as_bytes(),
+                hex_field(test, group, "pk"),
+                "tcId {}: pk",
+                test["tcId"]
+            );
+            assert_eq!(
+                sk.as_bytes(),
+                hex_field(test, group, "sk"),
+                "tcId {}: sk",
+                test["tcId"]
+            );
+            count += 1;
+        }
+    }
+    assert!(count > 0, "no keyGen test cases found");
+}
+
+/// Whether `group` signs the message itself through the external
+/// interface, the only ML-DSA mode this crate offers.
+fn is_pure_external(group: &Value) -> bool {
+    group["signatureInterface"].as_str() == Some("external")
+        && group["preHash"].as_str() == Some("pure")
+        && !group["externalMu"].as_bool().unwrap_or(false)
+}
+
+/// The context string, which ACVP omits when it is empty.
+fn context(test: &Value, group: &Value) -> Vec<u8> {
+    if test
+        .get("context")
+        .or_else(|| group.get("context"))
+        .is_some()
+    {
+        hex_field(test, group, "context")
+    } else {
+        Vec::new()
+    }
+}
+
+fn sign_with_context(
+    algorithm: SupportedAlgorithm,
+    sk: &dilithium::SecretKey,
+    message: &[u8],
+    context: &[u8],
+) -> Result<dilithium::Signature> {
+    match algorithm {
+        SupportedAlgorithm::MlDsa44 => MlDsa44::sign_with_context(sk, message, context),
+        SupportedAlgorithm::MlDsa65 => MlDsa65::sign_with_context(sk, message, context),
+        SupportedAlgorithm::MlDsa87 => MlDsa87::sign_with_context(sk, message, context),
+        other => panic!("not an ML-DSA parameter set: {}", other),
+    }
+}
+
+fn verify_with_context(
+    algorithm: SupportedAlgorithm,
+    pk: &dilithium::PublicKey,
+    message: &[u8],
+    context: &[u8],
+    signature: &dilithium::Signature,
+) -> Result<bool> {
+    match algorithm {
+        SupportedAlgorithm::MlDsa44 => {
+            MlDsa44::verify_with_context(pk, message, context, signature)
+        }
+        SupportedAlgorithm::MlDsa65 => {
+            MlDsa65::verify_with_context(pk, message, context, signature)
+        }
+        SupportedAlgorithm::MlDsa87 => {
+            MlDsa87::verify_with_context(pk, message, context, signature)
+        }
+        other => panic!("not an ML-DSA parameter set: {}", other),
+    }
+}
+
+#[test]
+fn ml_dsa_signature_generation() {
+    let Some(vectors) = load_vectors("ML-DSA-sigGen-FIPS204") else {
+        return;
+    };
+
+    let (mut count, mut skipped) = (0, 0);
+    for (group, algorithm) in groups(&vectors) {
+        if !is_pure_external(group) {
+            skipped += tests(group).len();
+            continue;
+        }
+        for test in tests(group) {
+            let pk = dilithium::PublicKey::from_bytes(&hex_field(test, group, "pk")).unwrap();
+            let sk = dilithium::SecretKey::from_bytes(&hex_field(test, group, "sk")).unwrap();
+            let message = hex_field(test, group, "message");
+            let context = context(test, group);
+
+            let expected =
+                dilithium::Signature::from_bytes(&hex_field(test, group, "signature")).unwrap();
+            assert!(
+                verify_with_context(algorithm, &pk, &message, &context, &expected).unwrap(),
+                "tcId {}: expected signature rejected",
+                test["tcId"]
+            );
+            let signature = sign_with_context(algorithm, &sk, &message, &context).unwrap();
+            assert!(
+                verify_with_context(algorithm, &pk, &message, &context, &signature).unwrap(),
+                "tcId {}: fresh signature rejected",
+                test["tcId"]
+            );
+            count += 1;
+        }
+    }
+    eprintln!(
+        "ML-DSA sigGen: {} cases checked, {} skipped",
+        count, skipped
+    );
+    assert!(count > 0, "no sigGen test cases found");
+}
+
+#[test]
+fn ml_dsa_signature_verification() {
+    let Some(vectors) = load_vectors("ML-DSA-sigVer-FIPS204") else {
+        return;
+    };
+
+    let (mut count, mut skipped) = (0, 0);
+    for (group, algorithm) in groups(&vectors) {
+        if !is_pure_external(group) {
+            skipped += tests(group).len();
+            continue;
+        }
+        for test in tests(group) {
+            let pk = dilithium::PublicKey::from_bytes(&hex_field(test, group, "pk")).unwrap();
+            let message = hex_field(test, group, "message");
+            let context = context(test, group);
+            let expected = test["testPassed"].as_bool().expect("testPassed");
+
+            // A signature of the wrong length is simply not valid
+            let verified = dilithium::Signature::from_bytes(&hex_field(test, group, "signature"))
+                .is_ok_and(|signature| {
+                    verify_with_context(algorithm, &pk, &message, &context, &signature).unwrap()
+                });
+            assert_eq!(verified, expected, "tcId {}: testPassed", test["tcId"]);
+            count += 1;
+        }
+    }
+    eprintln!(
+        "ML-DSA sigVer: {} cases checked, {} skipped",
+        count, skipped
+    );
+    assert!(count > 0, "no sigVer test cases found");
+}
+
+#[test]
+fn ml_kem_encapsulation_and_decapsulation() {
+    let Some(vectors) = load_vectors("ML-KEM-encapDecap-FIPS203") else {
+        return;
+    };
+
+    let mut count = 0;
+    for (group, algorithm) in groups(&vectors) {
+        match group["function"].as_str() {
+            Some("encapsulation") => {
+                for test in tests(group) {
+                    let pk = PublicKey::from_bytes(&hex_field(test, group, "ek")).unwrap();
+                    let m: [u8; 32] = hex_field(test, group, "m")
+                        .try_into()
+                        .expect("m must be 32 bytes");
+                    let (ct, ss) = match algorithm {
+                        KemAlgorithm::MlKem512 => MlKem512::encapsulate_deterministic(&pk, &m),
+                        KemAlgorithm::MlKem768 => MlKem768::encapsulate_deterministic(&pk, &m),
+                        KemAlgorithm::MlKem1024 => MlKem1024::encapsulate_deterministic(&pk, &m),
+                    }
+                    .unwrap();
+                    assert_eq!(
+                        ct.as_bytes(),
+                        hex_field(test, group, "c"),
+                        "tcId {}: c",
+                        test["tcId"]
+                    );
+                    assert_eq!(
+                        ss.as_bytes(),
+                        hex_field(test, group, "k"),
+                        "tcId {}: k",
+                        test["tcId"]
+                    );
+                    count += 1;
+                }
+            }
+            Some("decapsulation") => {
+                for test in tests(group) {
+                    let sk = SecretKey::from_bytes(&hex_field(test, group, "dk")).unwrap();
+                    let ct = Ciphertext::from_bytes(&hex_field(test, group, "c")).unwrap();
+                    let ss = algorithm.decapsulate(&sk, &ct).unwrap();
+                    assert_eq!(
+                        ss.as_bytes(),
+                        hex_field(test, group, "k"),
+                        "tcId {}: k",
+                        test["tcId"]
+                    );
+                    count += 1;
+                }
+            }
+            // Key-check groups exercise input validation this API doesn't expose
+            _ => continue,
+        }
+    }
+    assert!(count > 0, "no encapDecap test cases found");
+}
@@ -0,0 +1,141 @@
+//! Property-based tests of the round-trip invariants, over random seeds,
+//! messages and byte strings rather than the fixed inputs of the unit tests.
+//!
+//! Keys come from the seeded constructors, so proptest can shrink a failure
+//! down to the seed that reproduces it.
+
+use proptest::prelude::*;
+use proptest::sample::Index;
+use qraiop_crypto::pqc::dilithium::{self, MlDsa44, MlDsa65, MlDsa87};
+use qraiop_crypto::pqc::kyber::{self, MlKem1024, MlKem512, MlKem768};
+use qraiop_crypto::{DigitalSignature, KeyEncapsulation};
+
+/// Key generation dominates, so fewer cases than proptest's default 256.
+fn config() -> ProptestConfig {
+    ProptestConfig::with_cases(32)
+}
+
+/// Every length a parser must reject, and every one it must accept, is
+/// reachable from here.
+fn any_bytes() -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(any::<u8>(), 0..5000)
+}
+
+macro_rules! ml_kem_properties {
+    ($module:ident, $kem:ident) => {
+        mod $module {
+            use super::*;
+
+            proptest! {
+                #![proptest_config(config())]
+
+                #[test]
+                fn decapsulate_inverts_encapsulate(
+                    seed in any::<[u8; 64]>(),
+                    m in any::<[u8; 32]>(),
+                ) {
+                    let (pk, sk) = $kem::keypair_from_seed(&seed).unwrap();
+                    let (ct, ss) = $kem::encapsulate_deterministic(&pk, &m).unwrap();
+                    prop_assert_eq!($kem::decapsulate(&sk, &ct).unwrap(), ss);
+                }
+
+                #[test]
+                fn keys_survive_encoding(seed in any::<[u8; 64]>(), m in any::<[u8; 32]>()) {
+                    let (pk, sk) = $kem::keypair_from_seed(&seed).unwrap();
+                    let (ct, ss) = $kem::encapsulate_deterministic(&pk, &m).unwrap();
+                    prop_assert_eq!(&kyber::PublicKey::from_bytes(pk.as_bytes()).unwrap(), &pk);
+                    let decoded: kyber::PublicKey =
+                        bincode::deserialize(&bincode::serialize(&pk).unwrap()).unwrap();
+                    prop_assert_eq!(&decoded, &pk);
+                    let sk_bytes = kyber::SecretKey::from_bytes(sk.as_bytes()).unwrap();
+                    prop_assert_eq!(sk_bytes.as_bytes(), sk.as_bytes());
+                    prop_assert_eq!(&kyber::Ciphertext::from_bytes(ct.as_bytes()).unwrap(), &ct);
+                    let ss_bytes = kyber::SharedSecret::from_bytes(ss.as_bytes()).unwrap();
+                    prop_assert_eq!(ss_bytes, ss);
+                }
+            }
+        }
+    };
+}
+
+ml_kem_properties!(ml_kem_512, MlKem512);
+ml_kem_properties!(ml_kem_768, MlKem768);
+ml_kem_properties!(ml_kem_1024, MlKem1024);
+
+macro_rules! ml_dsa_properties {
+    ($module:ident, $scheme:ident) => {
+        mod $module {
+            use super::*;
+
+            proptest! {
+                #![proptest_config(config())]
+
+                #[test]
+                fn signatures_verify_only_their_message(
+                    seed in any::<[u8; 32]>(),
+                    message in prop::collection::vec(any::<u8>(), 1..512),
+                    bit in any::<Index>(),
+                ) {
+                    let (pk, sk) = $scheme::keypair_from_seed(&seed).unwrap();
+                    let signature = $scheme::sign(&sk, &message).unwrap();
+                    prop_assert!($scheme::verify(&pk, &message, &signature).unwrap());
+
+                    let mut flipped = message.clone();
+                    let bit = bit.index(message.len() * 8);
+                    flipped[bit / 8] ^= 1 << (bit % 8);
+                    prop_assert!(!$scheme::verify(&pk, &flipped, &signature).unwrap());
+                }
+
+                #[test]
+                fn keys_survive_encoding(seed in any::<[u8; 32]>()) {
+                    let (pk, sk) = $scheme::keypair_from_seed(&seed).unwrap();
+                    let signature = $scheme::sign(&sk, b"encoding").unwrap();
+                    prop_assert_eq!(&dilithium::PublicKey::from_bytes(pk.as_bytes()).unwrap(), &pk);
+                    let sk_bytes = dilithium::SecretKey::from_bytes(sk.as_bytes()).unwrap();
+                    prop_assert_eq!(sk_bytes.as_bytes(), sk.as_bytes());
+                    prop_assert_eq!(
+                        &dilithium::Signature::from_bytes_strict(signature.as_bytes()).unwrap(),
+                        &signature
+                    );
+                }
+            }
+        }
+    };
+}
+
+ml_dsa_properties!(ml_dsa_44, MlDsa44);
+ml_dsa_properties!(ml_dsa_65, MlDsa65);
+ml_dsa_properties!(ml_dsa_87, MlDsa87);
+
+proptest! {
+    // Parsing is cheap, so this keeps the default case count
+    #[test]
+    fn parsers_accept_exactly_the_encoded_lengths(bytes in any_bytes()) {
+        let len = bytes.len();
+        let kem_pk = [MlKem512::public_key_size(), MlKem768::public_key_size(), MlKem1024::public_key_size()];
+        match kyber::PublicKey::from_bytes(&bytes) {
+            Ok(pk) => prop_assert_eq!(pk.as_bytes(), &bytes[..]),
+            Err(_) => prop_assert!(!kem_pk.contains(&len)),
+        }
+        let kem_ct = [MlKem512::ciphertext_size(), MlKem768::ciphertext_size(), MlKem1024::ciphertext_size()];
+        match kyber::Ciphertext::from_bytes(&bytes) {
+            Ok(ct) => prop_assert_eq!(ct.as_bytes(), &bytes[..]),
+            Err(_) => prop_assert!(!kem_ct.contains(&len)),
+        }
+        let dsa_sig = [MlDsa44::signature_size(), MlDsa65::signature_size(), MlDsa87::signature_size()];
+        match dilithium::Signature::from_bytes(&bytes) {
+            Ok(signature) => prop_assert_eq!(signature.as_bytes(), &bytes[..]),
+            Err(_) => prop_assert!(!dsa_sig.contains(&len)),
+        }
+    }
+
+    #[test]
+    fn encoded_lengths_always_parse(algorithm in 0usize..3, fill in any::<u8>()) {
+        let kem_pk = [MlKem512::public_key_size(), MlKem768::public_key_size(), MlKem1024::public_key_size()];
+        let dsa_pk = [MlDsa44::public_key_size(), MlDsa65::public_key_size(), MlDsa87::public_key_size()];
+        let kem_bytes = vec![fill; kem_pk[algorithm]];
+        prop_assert!(kyber::PublicKey::from_bytes(&kem_bytes).is_ok());
+        let dsa_bytes = vec![fill; dsa_pk[algorithm]];
+        prop_assert!(dilithium::PublicKey::from_bytes(&dsa_bytes).is_ok());
+    }
+}
@@ -0,0 +1,96 @@
+//! Statistical timing check for ML-KEM decapsulation, after dudect
+//! (Reparaz, Balasch and Verbauwhede, "Dude, is my code constant time?")
+//!
+//! Implicit rejection means decapsulating a corrupted ciphertext must take
+//! as long as decapsulating a valid one. Measurements of both classes are
+//! interleaved in random order, the slowest samples are cropped to cut
+//! scheduler noise, and Welch's t-test compares what is left. A |t| above
+//! the threshold is a leak no amount of noise explains.
+//!
+//! This is a coarse regression guard, not a proof: it only runs with the
+//! `timing_tests` feature, and is only meaningful in release mode on an
+//! otherwise idle machine:
+//!
+//! ```text
+//! cargo test --release --features timing_tests --test timing
+//! ```
+
+use qraiop_crypto::pqc::kyber::{Ciphertext, MlKem768};
+use qraiop_crypto::KeyEncapsulation;
+use rand::Rng;
+use std::hint::black_box;
+use std::time::Instant;
+
+const SAMPLES: usize = 20_000;
+const WARMUP: usize = 500;
+/// Samples above this percentile of each class are dropped.
+const CROP_PERCENTILE: f64 = 0.9;
+/// dudect treats |t| > 10 as a definite leak.
+const T_THRESHOLD: f64 = 10.0;
+
+struct Stats {
+    n: f64,
+    mean: f64,
+    variance: f64,
+}
+
+fn stats(samples: &[f64]) -> Stats {
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    Stats { n, mean, variance }
+}
+
+fn crop(mut samples: Vec<f64>) -> Vec<f64> {
+    samples.sort_by(f64::total_cmp);
+    samples.truncate((samples.len() as f64 * CROP_PERCENTILE) as usize);
+    samples
+}
+
+fn welch_t(a: &[f64], b: &[f64]) -> f64 {
+    let (a, b) = (stats(a), stats(b));
+    (a.mean - b.mean) / (a.variance / a.n + b.variance / b.n).sqrt()
+}
+
+#[test]
+fn decapsulation_time_is_independent_of_ciphertext_validity() {
+    let (pk, sk) = MlKem768::keypair().unwrap();
+    let (valid, _) = MlKem768::encapsulate(&pk).unwrap();
+    let mut corrupted = valid.as_bytes().to_vec();
+    for byte in corrupted.iter_mut().step_by(97) {
+        *byte ^= 0xa5;
+    }
+    let corrupted = Ciphertext::from_bytes(&corrupted).unwrap();
+    let inputs = [&valid, &corrupted];
+
+    let mut rng = rand::thread_rng();
+    let mut timings = [Vec::with_capacity(SAMPLES), Vec::with_capacity(SAMPLES)];
+    for i in 0..WARMUP + SAMPLES {
+        let class = rng.gen_range(0..2);
+        let start = Instant::now();
+        black_box(MlKem768::decapsulate(&sk, black_box(inputs[class])).unwrap());
+        let elapsed = start.elapsed().as_nanos() as f64;
+        if i >= WARMUP {
+            timings[class].push(elapsed);
+        }
+    }
+
+    let [valid_timings, corrupted_timings] = timings.map(crop);
+    let t = welch_t(&valid_timings, &corrupted_timings);
+    assert!(
+        t.abs() < T_THRESHOLD,
+        "decapsulation timing depends on ciphertext validity: t = {:.2} \
+         (valid mean {:.0} ns, corrupted mean {:.0} ns)",
+        t,
+        stats(&valid_timings).mean,
+        stats(&corrupted_timings).mean
+    );
+}
+
+#[test]
+fn welch_t_detects_a_shifted_distribution() {
+    let a: Vec<f64> = (0..1000).map(|i| 100.0 + (i % 10) as f64).collect();
+    let b: Vec<f64> = a.iter().map(|x| x + 5.0).collect();
+    assert!(welch_t(&a, &b).abs() > T_THRESHOLD);
+    assert!(welch_t(&a, &a).abs() < 1e-9);
+}
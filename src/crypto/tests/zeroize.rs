@@ -0,0 +1,101 @@
+//! Checks that secrets are wiped before their memory goes back to the
+//! allocator, rather than trusting that `ZeroizeOnDrop` is derived.
+//!
+//! This test binary installs an allocator that inspects blocks as they are
+//! freed. A test marks the heap buffer behind a secret, drops the secret,
+//! and asserts the whole block, spare capacity included, was zero when it
+//! was freed.
+
+use qraiop_crypto::hybrid::XWing;
+use qraiop_crypto::pqc::dilithium::MlDsa44;
+use qraiop_crypto::pqc::kyber::MlKem768;
+use qraiop_crypto::pqc::sphincs::SlhDsa128s;
+use qraiop_crypto::{DigitalSignature, KeyEncapsulation};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+const NOT_FREED: u8 = 0;
+const FREED_ZEROED: u8 = 1;
+const FREED_DIRTY: u8 = 2;
+
+/// Address of the block being watched, or 0.
+static WATCHED: AtomicUsize = AtomicUsize::new(0);
+static OUTCOME: AtomicU8 = AtomicU8::new(NOT_FREED);
+/// Tests run in parallel, but only one block can be watched at a time.
+static WATCH_LOCK: Mutex<()> = Mutex::new(());
+
+struct InspectingAllocator;
+
+unsafe impl GlobalAlloc for InspectingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if ptr as usize == WATCHED.load(Ordering::SeqCst) {
+            // SAFETY: the block is still allocated until `System.dealloc`.
+            let block = std::slice::from_raw_parts(ptr, layout.size());
+            let outcome = if block.iter().all(|&b| b == 0) {
+                FREED_ZEROED
+            } else {
+                FREED_DIRTY
+            };
+            OUTCOME.store(outcome, Ordering::SeqCst);
+            WATCHED.store(0, Ordering::SeqCst);
+        }
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: InspectingAllocator = InspectingAllocator;
+
+/// Drops `secret` and asserts that the block holding `bytes(&secret)` was
+/// all zeros when it was freed.
+fn assert_wiped_on_drop<T>(name: &str, secret: T, bytes: impl Fn(&T) -> &[u8]) {
+    let _guard = WATCH_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let data = bytes(&secret);
+    assert!(
+        data.iter().any(|&b| b != 0),
+        "{} is all zeros before the drop",
+        name
+    );
+    OUTCOME.store(NOT_FREED, Ordering::SeqCst);
+    WATCHED.store(data.as_ptr() as usize, Ordering::SeqCst);
+    drop(secret);
+    WATCHED.store(0, Ordering::SeqCst);
+    match OUTCOME.load(Ordering::SeqCst) {
+        FREED_ZEROED => {}
+        FREED_DIRTY => panic!("{} was freed without being wiped", name),
+        _ => panic!("{} was not freed on drop", name),
+    }
+}
+
+#[test]
+fn test_ml_kem_secrets_are_wiped() {
+    let (pk, sk) = MlKem768::keypair().unwrap();
+    let (ct, ss) = MlKem768::encapsulate(&pk).unwrap();
+    assert_wiped_on_drop("ML-KEM shared secret", ss, |ss| ss.expose_bytes());
+    // Reuses the ciphertext's larger buffer, whose spare capacity held
+    // ciphertext bytes
+    let ss = MlKem768::decapsulate_owned(&sk, ct).unwrap();
+    assert_wiped_on_drop("reused shared secret buffer", ss, |ss| ss.expose_bytes());
+    assert_wiped_on_drop("ML-KEM secret key", sk, |sk| sk.as_bytes());
+}
+
+#[test]
+fn test_signature_secret_keys_are_wiped() {
+    let (_, sk) = MlDsa44::keypair().unwrap();
+    assert_wiped_on_drop("ML-DSA secret key", sk, |sk| sk.as_bytes());
+    let (_, sk) = SlhDsa128s::keypair().unwrap();
+    assert_wiped_on_drop("SLH-DSA secret key", sk, |sk| sk.as_ref());
+}
+
+#[test]
+fn test_hybrid_secrets_are_wiped() {
+    let (pk, sk) = XWing::keypair().unwrap();
+    let (_, ss) = XWing::encapsulate(&pk).unwrap();
+    assert_wiped_on_drop("X-Wing shared secret", ss, |ss| ss.as_bytes());
+    assert_wiped_on_drop("X-Wing secret key", sk, |sk| sk.as_bytes());
+}